@@ -0,0 +1,132 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordForward,
+    WordBack,
+    LineStart,
+    LineEnd,
+}
+
+pub(crate) fn apply_motion(
+    lines: &[String],
+    (row, column): (usize, usize),
+    motion: CursorMotion,
+) -> (usize, usize) {
+    let last_row = lines.len().saturating_sub(1);
+    let chars = |row: usize| -> Vec<char> {
+        lines
+            .get(row)
+            .map(|line| line.chars().collect())
+            .unwrap_or_default()
+    };
+    let last_column = |row: usize| chars(row).len().saturating_sub(1);
+    match motion {
+        CursorMotion::Left => (row, column.saturating_sub(1)),
+        CursorMotion::Right => (row, (column + 1).min(last_column(row))),
+        CursorMotion::Up => {
+            let row = row.saturating_sub(1);
+            (row, column.min(last_column(row)))
+        }
+        CursorMotion::Down => {
+            let row = (row + 1).min(last_row);
+            (row, column.min(last_column(row)))
+        }
+        CursorMotion::LineStart => (row, 0),
+        CursorMotion::LineEnd => (row, last_column(row)),
+        CursorMotion::WordForward => {
+            let mut position = (row, column);
+            let mut skipped_word = false;
+            loop {
+                let line = chars(position.0);
+                while position.1 < line.len() {
+                    let word = is_word_char(line[position.1]);
+                    if word && skipped_word {
+                        return position;
+                    }
+                    if !word {
+                        skipped_word = true;
+                    }
+                    position.1 += 1;
+                }
+                if position.0 >= last_row {
+                    return (row, column);
+                }
+                position = (position.0 + 1, 0);
+                skipped_word = true;
+            }
+        }
+        CursorMotion::WordBack => {
+            let mut position = (row, column);
+            loop {
+                let line = chars(position.0);
+                let mut index = position.1.min(line.len());
+                while index > 0 && !is_word_char(line[index - 1]) {
+                    index -= 1;
+                }
+                if index > 0 {
+                    while index > 0 && is_word_char(line[index - 1]) {
+                        index -= 1;
+                    }
+                    return (position.0, index);
+                }
+                if position.0 == 0 {
+                    return (row, column);
+                }
+                position = (position.0 - 1, usize::MAX);
+            }
+        }
+    }
+}
+
+pub(crate) fn word_at(line: &str, column: usize) -> Option<String> {
+    let chars: Vec<char> = line.chars().collect();
+    if !chars.get(column).copied().is_some_and(is_word_char) {
+        return None;
+    }
+    let mut start = column;
+    while start > 0 && is_word_char(chars[start - 1]) {
+        start -= 1;
+    }
+    let mut end = column;
+    while end < chars.len() && is_word_char(chars[end]) {
+        end += 1;
+    }
+    let word: String = chars[start..end].iter().collect();
+    let word = word.trim_matches(|ch: char| matches!(ch, '.' | '-'));
+    (!word.is_empty()).then(|| word.to_string())
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_by_words_and_lines() {
+        let lines: Vec<String> = ["  see printf(3) and", "", "  ls -la."]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        let position = apply_motion(&lines, (0, 2), CursorMotion::WordForward);
+        assert_eq!(position, (0, 6));
+        let position = apply_motion(&lines, position, CursorMotion::WordForward);
+        assert_eq!(position, (0, 13));
+        let position = apply_motion(&lines, position, CursorMotion::WordForward);
+        assert_eq!(position, (0, 16));
+        let position = apply_motion(&lines, position, CursorMotion::WordForward);
+        assert_eq!(position, (2, 2));
+        let position = apply_motion(&lines, position, CursorMotion::WordBack);
+        assert_eq!(position, (0, 16));
+        assert_eq!(apply_motion(&lines, (0, 16), CursorMotion::Down), (1, 0));
+        assert_eq!(apply_motion(&lines, (2, 3), CursorMotion::LineEnd), (2, 8));
+        assert_eq!(word_at(&lines[0], 8), Some("printf".to_string()));
+        assert_eq!(word_at(&lines[2], 6), Some("la".to_string()));
+        assert_eq!(word_at(&lines[0], 1), None);
+    }
+}
@@ -0,0 +1,209 @@
+use render::{ManRenderer, RenderError};
+
+use crate::{Action, App, Effect, Mode, UpdateOutcome};
+
+const DEFAULT_WIDTH: u16 = 80;
+const DEFAULT_HEIGHT: usize = 24;
+
+#[derive(Debug, Clone, Default)]
+pub struct StaticRenderer {
+    pages: Vec<(String, Option<String>, Vec<String>)>,
+}
+
+impl StaticRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_page<I, S>(mut self, name: &str, section: Option<&str>, lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.pages.push((
+            name.to_string(),
+            section.map(str::to_string),
+            lines.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+}
+
+impl ManRenderer for StaticRenderer {
+    fn render(
+        &self,
+        name: &str,
+        section: Option<&str>,
+        _width: u16,
+    ) -> Result<Vec<String>, RenderError> {
+        self.pages
+            .iter()
+            .find(|(page, page_section, _)| {
+                page == name && (section.is_none() || page_section.as_deref() == section)
+            })
+            .map(|(_, _, lines)| lines.clone())
+            .ok_or_else(|| RenderError::CommandFailed(format!("No manual entry for {name}")))
+    }
+}
+
+pub struct Headless<R> {
+    app: App,
+    renderer: R,
+    width: u16,
+    viewport_height: usize,
+}
+
+impl<R: ManRenderer> Headless<R> {
+    pub fn new(renderer: R) -> Self {
+        Self::with_size(renderer, DEFAULT_WIDTH, DEFAULT_HEIGHT)
+    }
+
+    pub fn with_size(renderer: R, width: u16, viewport_height: usize) -> Self {
+        let mut app = App::empty();
+        app.set_screen_width(width);
+        Self {
+            app,
+            renderer,
+            width,
+            viewport_height,
+        }
+    }
+
+    pub fn open(&mut self, topic: &str, section: Option<&str>) -> Result<(), RenderError> {
+        self.app.open_pages(
+            vec![topic.to_string()],
+            section.map(str::to_string),
+            &self.renderer,
+            self.width,
+            self.viewport_height,
+        )
+    }
+
+    pub fn send(&mut self, action: Action) -> Result<UpdateOutcome, RenderError> {
+        self.app
+            .update(action, &self.renderer, self.width, self.viewport_height)
+    }
+
+    pub fn send_all(
+        &mut self,
+        actions: impl IntoIterator<Item = Action>,
+    ) -> Result<UpdateOutcome, RenderError> {
+        for action in actions {
+            if self.send(action)? == UpdateOutcome::Quit {
+                return Ok(UpdateOutcome::Quit);
+            }
+        }
+        Ok(UpdateOutcome::Continue)
+    }
+
+    pub fn command(&mut self, line: &str) -> Result<UpdateOutcome, RenderError> {
+        let typed = line.chars().map(Action::CommandChar);
+        self.send_all(
+            std::iter::once(Action::EnterCommandMode)
+                .chain(typed)
+                .chain([Action::CommandSubmit]),
+        )
+    }
+
+    pub fn search(&mut self, query: &str) -> Result<UpdateOutcome, RenderError> {
+        let typed = query.chars().map(Action::SearchChar);
+        self.send_all(
+            std::iter::once(Action::EnterSearchMode)
+                .chain(typed)
+                .chain([Action::SearchSubmit]),
+        )
+    }
+
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    pub fn lines(&self) -> &[String] {
+        self.app.lines()
+    }
+
+    pub fn visible_lines(&self) -> &[String] {
+        let (first, lines) = self.app.window();
+        let start = self.app.scroll().saturating_sub(first).min(lines.len());
+        let end = (start + self.viewport_height).min(lines.len());
+        &lines[start..end]
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.app.scroll()
+    }
+
+    pub fn mode(&self) -> &Mode {
+        self.app.mode()
+    }
+
+    pub fn status(&self) -> Option<&str> {
+        self.app.status_message()
+    }
+
+    pub fn tab_titles(&self) -> Vec<String> {
+        self.app
+            .tabs()
+            .iter()
+            .map(|page| match page.section() {
+                Some(section) => format!("{}({section})", page.name()),
+                None => page.name().to_string(),
+            })
+            .collect()
+    }
+
+    pub fn take_effects(&mut self) -> Vec<Effect> {
+        self.app.drain_effects()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renderer() -> StaticRenderer {
+        let body = (0..60).map(|index| format!("       line {index}"));
+        StaticRenderer::new()
+            .with_page(
+                "ls",
+                Some("1"),
+                ["NAME", "       ls - list directory contents"]
+                    .into_iter()
+                    .map(str::to_string)
+                    .chain(body),
+            )
+            .with_page("grep", Some("1"), ["NAME", "       grep - print lines"])
+    }
+
+    #[test]
+    fn drives_the_app_without_a_terminal() {
+        let mut driver = Headless::with_size(renderer(), 80, 10);
+        driver.open("ls", Some("1")).expect("open");
+        assert_eq!(driver.tab_titles(), vec!["ls(1)"]);
+        assert_eq!(driver.visible_lines().len(), 10);
+
+        driver.search("line 30").expect("search");
+        assert_eq!(driver.scroll(), 27);
+        assert_eq!(driver.visible_lines()[5], "       line 30");
+
+        driver.command("man grep").expect("command");
+        assert_eq!(driver.tab_titles(), vec!["ls(1)", "grep"]);
+        assert_eq!(driver.lines()[1], "       grep - print lines");
+        assert!(matches!(driver.mode(), Mode::Normal));
+
+        driver.command("man missing").expect("command");
+        assert!(driver.status().is_some());
+        assert_eq!(driver.tab_titles(), vec!["ls(1)", "grep", "missing"]);
+        assert_eq!(
+            driver
+                .send_all([Action::TabLeft, Action::Quit, Action::TabRight])
+                .expect("quit"),
+            UpdateOutcome::Quit
+        );
+        assert_eq!(driver.tab_titles().len(), 3);
+    }
+}
@@ -0,0 +1,81 @@
+use man::find_references;
+
+const HINT_KEYS: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkHint {
+    pub label: String,
+    pub row: usize,
+    pub column: usize,
+    pub target: String,
+}
+
+pub(crate) fn collect_hints(lines: &[String], first_row: usize) -> Vec<LinkHint> {
+    let references: Vec<(usize, usize, String)> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(offset, line)| {
+            find_references(line).into_iter().map(move |reference| {
+                let column = line[..reference.start].chars().count();
+                (first_row + offset, column, reference.target())
+            })
+        })
+        .collect();
+    hint_labels(references.len())
+        .into_iter()
+        .zip(references)
+        .map(|(label, (row, column, target))| LinkHint {
+            label,
+            row,
+            column,
+            target,
+        })
+        .collect()
+}
+
+fn hint_labels(count: usize) -> Vec<String> {
+    let keys: Vec<char> = HINT_KEYS.chars().collect();
+    if count <= keys.len() {
+        return keys.iter().take(count).map(|key| key.to_string()).collect();
+    }
+    keys.iter()
+        .flat_map(|first| keys.iter().map(move |second| format!("{first}{second}")))
+        .take(count)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_visible_references() {
+        let lines: Vec<String> = ["see read(2),", "  write(2) and stat(3)"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        let hints = collect_hints(&lines, 10);
+        let summary: Vec<(&str, usize, usize, &str)> = hints
+            .iter()
+            .map(|hint| {
+                (
+                    hint.label.as_str(),
+                    hint.row,
+                    hint.column,
+                    hint.target.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a", 10, 4, "read(2)"),
+                ("s", 11, 2, "write(2)"),
+                ("d", 11, 15, "stat(3)"),
+            ]
+        );
+        let labels = hint_labels(30);
+        assert_eq!(labels[0], "aa");
+        assert_eq!(labels[27], "ss");
+    }
+}
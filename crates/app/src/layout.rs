@@ -0,0 +1,497 @@
+const MIN_RATIO: u16 = 10;
+const MAX_RATIO: u16 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirection {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaneArea {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Pane(usize),
+    Split {
+        direction: SplitDirection,
+        ratio: u16,
+        first: Box<Node>,
+        second: Box<Node>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaneLayout {
+    root: Node,
+    focus: usize,
+}
+
+impl PaneLayout {
+    pub fn new(tab: usize) -> Self {
+        Self {
+            root: Node::Pane(tab),
+            focus: 0,
+        }
+    }
+
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    pub fn tabs(&self) -> Vec<usize> {
+        let mut tabs = Vec::new();
+        self.root.collect(&mut tabs);
+        tabs
+    }
+
+    pub fn pane_count(&self) -> usize {
+        self.tabs().len()
+    }
+
+    pub fn focused_tab(&self) -> usize {
+        self.tabs()[self.focus]
+    }
+
+    pub fn split(&mut self, direction: SplitDirection, tab: usize) {
+        let mut index = 0;
+        self.root.split_leaf(self.focus, &mut index, direction, tab);
+        self.focus += 1;
+    }
+
+    pub fn close_focused(&mut self) -> bool {
+        if self.pane_count() < 2 {
+            return false;
+        }
+        let mut index = 0;
+        self.root.remove_leaf(self.focus, &mut index);
+        self.focus = self.focus.min(self.pane_count() - 1);
+        true
+    }
+
+    pub fn focus_next(&mut self) -> usize {
+        self.focus = (self.focus + 1) % self.pane_count();
+        self.focused_tab()
+    }
+
+    pub fn set_tab(&mut self, pane: usize, tab: usize) {
+        let mut index = 0;
+        self.root.set_leaf(pane, &mut index, tab);
+    }
+
+    pub fn close_pane(&mut self, pane: usize) -> bool {
+        if self.pane_count() < 2 || pane >= self.pane_count() {
+            return false;
+        }
+        let mut index = 0;
+        self.root.remove_leaf(pane, &mut index);
+        if pane < self.focus {
+            self.focus -= 1;
+        }
+        self.focus = self.focus.min(self.pane_count() - 1);
+        true
+    }
+
+    pub fn forget_tab(&mut self, tab: usize) {
+        while let Some(pane) = self
+            .tabs()
+            .iter()
+            .enumerate()
+            .position(|(pane, shown)| *shown == tab && pane != self.focus)
+        {
+            if !self.close_pane(pane) {
+                break;
+            }
+        }
+        self.root.shift_tabs_after(tab);
+    }
+
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        self.root.move_tab(from, to);
+    }
+
+    pub fn resize(&mut self, direction: SplitDirection, delta: i16) -> bool {
+        let mut index = 0;
+        self.root
+            .resize_around(self.focus, &mut index, direction, delta)
+            .unwrap_or(false)
+    }
+
+    pub fn spec(&self) -> String {
+        format!("{}:{}", self.focus, self.root.spec())
+    }
+
+    pub fn from_spec(spec: &str, tab: impl Fn(usize) -> Option<usize>) -> Option<Self> {
+        let (focus, rest) = spec.split_once(':')?;
+        let (root, rest) = Node::parse(rest, &tab)?;
+        let layout = Self {
+            root,
+            focus: focus.parse().ok()?,
+        };
+        (rest.is_empty() && layout.focus < layout.pane_count()).then_some(layout)
+    }
+
+    pub fn areas(&self, width: u16, height: u16) -> Vec<PaneArea> {
+        self.compute(width, height).0
+    }
+
+    pub fn separators(&self, width: u16, height: u16) -> Vec<(SplitDirection, PaneArea)> {
+        self.compute(width, height).1
+    }
+
+    fn compute(&self, width: u16, height: u16) -> (Vec<PaneArea>, Vec<(SplitDirection, PaneArea)>) {
+        let mut areas = Vec::new();
+        let mut separators = Vec::new();
+        let area = PaneArea {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        };
+        self.root.layout(area, &mut areas, &mut separators);
+        (areas, separators)
+    }
+}
+
+impl Node {
+    fn spec(&self) -> String {
+        match self {
+            Node::Pane(tab) => tab.to_string(),
+            Node::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => {
+                let kind = match direction {
+                    SplitDirection::Horizontal => 'h',
+                    SplitDirection::Vertical => 'v',
+                };
+                format!("{kind}{ratio}({},{})", first.spec(), second.spec())
+            }
+        }
+    }
+
+    fn parse<'a>(text: &'a str, tab: &dyn Fn(usize) -> Option<usize>) -> Option<(Node, &'a str)> {
+        let direction = match text.chars().next()? {
+            'h' => SplitDirection::Horizontal,
+            'v' => SplitDirection::Vertical,
+            _ => {
+                let end = text
+                    .find(|ch: char| !ch.is_ascii_digit())
+                    .unwrap_or(text.len());
+                let index = tab(text[..end].parse().ok()?)?;
+                return Some((Node::Pane(index), &text[end..]));
+            }
+        };
+        let (ratio, rest) = text[1..].split_once('(')?;
+        let ratio = ratio.parse::<u16>().ok()?.clamp(MIN_RATIO, MAX_RATIO);
+        let (first, rest) = Node::parse(rest, tab)?;
+        let (second, rest) = Node::parse(rest.strip_prefix(',')?, tab)?;
+        let node = Node::Split {
+            direction,
+            ratio,
+            first: Box::new(first),
+            second: Box::new(second),
+        };
+        Some((node, rest.strip_prefix(')')?))
+    }
+
+    fn collect(&self, tabs: &mut Vec<usize>) {
+        match self {
+            Node::Pane(tab) => tabs.push(*tab),
+            Node::Split { first, second, .. } => {
+                first.collect(tabs);
+                second.collect(tabs);
+            }
+        }
+    }
+
+    fn split_leaf(
+        &mut self,
+        target: usize,
+        index: &mut usize,
+        direction: SplitDirection,
+        tab: usize,
+    ) -> bool {
+        match self {
+            Node::Pane(current) => {
+                if *index != target {
+                    *index += 1;
+                    return false;
+                }
+                let current = *current;
+                *self = Node::Split {
+                    direction,
+                    ratio: 50,
+                    first: Box::new(Node::Pane(current)),
+                    second: Box::new(Node::Pane(tab)),
+                };
+                true
+            }
+            Node::Split { first, second, .. } => {
+                first.split_leaf(target, index, direction, tab)
+                    || second.split_leaf(target, index, direction, tab)
+            }
+        }
+    }
+
+    fn set_leaf(&mut self, target: usize, index: &mut usize, tab: usize) -> bool {
+        match self {
+            Node::Pane(current) => {
+                if *index == target {
+                    *current = tab;
+                    return true;
+                }
+                *index += 1;
+                false
+            }
+            Node::Split { first, second, .. } => {
+                first.set_leaf(target, index, tab) || second.set_leaf(target, index, tab)
+            }
+        }
+    }
+
+    fn remove_leaf(&mut self, target: usize, index: &mut usize) -> bool {
+        let Node::Split { first, second, .. } = self else {
+            *index += 1;
+            return false;
+        };
+        if matches!(**first, Node::Pane(_)) && *index == target {
+            *self = std::mem::replace(&mut **second, Node::Pane(0));
+            return true;
+        }
+        if first.remove_leaf(target, index) {
+            return true;
+        }
+        if matches!(**second, Node::Pane(_)) && *index == target {
+            *self = std::mem::replace(&mut **first, Node::Pane(0));
+            return true;
+        }
+        second.remove_leaf(target, index)
+    }
+
+    fn shift_tabs_after(&mut self, removed: usize) {
+        match self {
+            Node::Pane(tab) => {
+                if *tab > removed {
+                    *tab -= 1;
+                }
+            }
+            Node::Split { first, second, .. } => {
+                first.shift_tabs_after(removed);
+                second.shift_tabs_after(removed);
+            }
+        }
+    }
+
+    fn move_tab(&mut self, from: usize, to: usize) {
+        match self {
+            Node::Pane(tab) => {
+                if *tab == from {
+                    *tab = to;
+                } else if from < *tab && *tab <= to {
+                    *tab -= 1;
+                } else if to <= *tab && *tab < from {
+                    *tab += 1;
+                }
+            }
+            Node::Split { first, second, .. } => {
+                first.move_tab(from, to);
+                second.move_tab(from, to);
+            }
+        }
+    }
+
+    fn resize_around(
+        &mut self,
+        target: usize,
+        index: &mut usize,
+        wanted: SplitDirection,
+        delta: i16,
+    ) -> Option<bool> {
+        match self {
+            Node::Pane(_) => {
+                let found = *index == target;
+                *index += 1;
+                found.then_some(false)
+            }
+            Node::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => {
+                let (in_first, resized) = match first.resize_around(target, index, wanted, delta) {
+                    Some(resized) => (true, resized),
+                    None => (false, second.resize_around(target, index, wanted, delta)?),
+                };
+                if resized || *direction != wanted {
+                    return Some(resized);
+                }
+                let change = if in_first { delta } else { -delta };
+                *ratio = (*ratio as i16 + change).clamp(MIN_RATIO as i16, MAX_RATIO as i16) as u16;
+                Some(true)
+            }
+        }
+    }
+
+    fn layout(
+        &self,
+        area: PaneArea,
+        areas: &mut Vec<PaneArea>,
+        separators: &mut Vec<(SplitDirection, PaneArea)>,
+    ) {
+        let Node::Split {
+            direction,
+            ratio,
+            first,
+            second,
+        } = self
+        else {
+            areas.push(area);
+            return;
+        };
+        let total = match direction {
+            SplitDirection::Horizontal => area.width,
+            SplitDirection::Vertical => area.height,
+        };
+        let available = total.saturating_sub(1);
+        let first_size = ((u32::from(available) * u32::from(*ratio) / 100) as u16)
+            .clamp(1.min(available), available);
+        let separator_size = 1.min(total - first_size);
+        let second_size = total - first_size - separator_size;
+        let (first_area, separator, second_area) = match direction {
+            SplitDirection::Horizontal => (
+                PaneArea {
+                    width: first_size,
+                    ..area
+                },
+                PaneArea {
+                    x: area.x + first_size,
+                    width: separator_size,
+                    ..area
+                },
+                PaneArea {
+                    x: area.x + first_size + separator_size,
+                    width: second_size,
+                    ..area
+                },
+            ),
+            SplitDirection::Vertical => (
+                PaneArea {
+                    height: first_size,
+                    ..area
+                },
+                PaneArea {
+                    y: area.y + first_size,
+                    height: separator_size,
+                    ..area
+                },
+                PaneArea {
+                    y: area.y + first_size + separator_size,
+                    height: second_size,
+                    ..area
+                },
+            ),
+        };
+        first.layout(first_area, areas, separators);
+        separators.push((*direction, separator));
+        second.layout(second_area, areas, separators);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_and_closes_panes() {
+        let mut layout = PaneLayout::new(0);
+        layout.split(SplitDirection::Horizontal, 1);
+        layout.split(SplitDirection::Vertical, 2);
+        assert_eq!(layout.tabs(), vec![0, 1, 2]);
+        assert_eq!(layout.focused_tab(), 2);
+        assert_eq!(
+            layout.areas(81, 20),
+            vec![
+                PaneArea {
+                    x: 0,
+                    y: 0,
+                    width: 40,
+                    height: 20
+                },
+                PaneArea {
+                    x: 41,
+                    y: 0,
+                    width: 40,
+                    height: 9
+                },
+                PaneArea {
+                    x: 41,
+                    y: 10,
+                    width: 40,
+                    height: 10
+                },
+            ]
+        );
+        assert!(layout.close_focused());
+        assert_eq!(layout.tabs(), vec![0, 1]);
+        assert_eq!(layout.focused_tab(), 1);
+        assert_eq!(layout.focus_next(), 0);
+    }
+
+    #[test]
+    fn resizes_the_nearest_matching_split() {
+        let mut layout = PaneLayout::new(0);
+        layout.split(SplitDirection::Horizontal, 1);
+        assert!(layout.resize(SplitDirection::Horizontal, 10));
+        assert_eq!(layout.areas(101, 10)[0].width, 40);
+        assert!(!layout.resize(SplitDirection::Vertical, 10));
+        for _ in 0..10 {
+            layout.resize(SplitDirection::Horizontal, 10);
+        }
+        assert_eq!(layout.areas(101, 10)[0].width, 10);
+    }
+
+    #[test]
+    fn forgets_closed_tabs() {
+        let mut layout = PaneLayout::new(0);
+        layout.split(SplitDirection::Horizontal, 2);
+        layout.split(SplitDirection::Vertical, 3);
+        layout.forget_tab(2);
+        assert_eq!(layout.tabs(), vec![0, 2]);
+        assert_eq!(layout.focused_tab(), 2);
+    }
+
+    #[test]
+    fn follows_moved_tabs() {
+        let mut layout = PaneLayout::new(1);
+        layout.split(SplitDirection::Horizontal, 3);
+        layout.move_tab(3, 0);
+        assert_eq!(layout.tabs(), vec![2, 0]);
+        layout.move_tab(0, 2);
+        assert_eq!(layout.tabs(), vec![1, 2]);
+    }
+
+    #[test]
+    fn round_trips_layout_specs() {
+        let mut layout = PaneLayout::new(0);
+        layout.split(SplitDirection::Horizontal, 1);
+        layout.resize(SplitDirection::Horizontal, 10);
+        layout.split(SplitDirection::Vertical, 2);
+        assert_eq!(layout.spec(), "2:h40(0,v50(1,2))");
+        assert_eq!(PaneLayout::from_spec(&layout.spec(), Some), Some(layout));
+        let shifted = PaneLayout::from_spec("1:h40(0,1)", |tab| Some(tab + 3)).expect("spec");
+        assert_eq!(shifted.tabs(), vec![3, 4]);
+        assert_eq!(shifted.focused_tab(), 4);
+        assert_eq!(PaneLayout::from_spec("2:h40(0,1)", Some), None);
+        assert_eq!(PaneLayout::from_spec("0:h40(0,1", Some), None);
+        assert_eq!(PaneLayout::from_spec("0:h40(0,1)", |_| None), None);
+    }
+}
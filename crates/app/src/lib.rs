@@ -1,11 +1,56 @@
-use man::ManPage;
-use render::{ArgsInterpretation, ManRenderer, RenderError, classify_args};
+use man::{
+    ExportFormat, ManPage, MatchStep, code_lines, diff_counts, diff_lines, export_lines,
+    find_references, keyword_matches,
+};
+use render::{
+    ArgsInterpretation, CheatRenderer, ManRenderer, PageEntry, RenderError, char_at_column,
+    classify_args, clean_formatted_text, is_local_page, read_page_source,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use store::{
+    Bookmark, Bookmarks, History, PinnedPages, PositionStore, SearchHistory, Session, SessionStore,
+    SnapshotStore, create_temp_file, format_timestamp,
+};
 
+mod cursor;
+mod headless;
+mod hints;
+mod layout;
+mod line_edit;
+mod page_index;
+mod selection;
+
+pub use cursor::CursorMotion;
+use cursor::{apply_motion, word_at};
+pub use headless::{Headless, StaticRenderer};
+pub use hints::LinkHint;
+use hints::collect_hints;
+pub use layout::{PaneArea, PaneLayout, SplitDirection};
+pub use line_edit::LineEdit;
+use line_edit::apply_edit;
+pub use man::{
+    CodeToken, DiffKind, KeywordMatch, RenderStats, SearchMatch, highlight_code, option_definition,
+    section_lines,
+};
+pub use page_index::PageIndex;
+pub use selection::TextPosition;
+use selection::TextSelection;
+
+const FIND_LIMIT: usize = 50;
+pub const MIN_CONTENT_WIDTH: u16 = 15;
+const DEFAULT_GROUP: &str = "main";
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Action {
     Quit,
     ScrollUp(usize),
     ScrollDown(usize),
+    ScrollLeft(usize),
+    ScrollRight(usize),
     PageUp,
     PageDown,
     HalfPageUp,
@@ -15,8 +60,14 @@ pub enum Action {
     Resize(u16, u16),
     GoTop,
     GoBottom,
+    GoToLine(usize),
+    CountDigit(usize),
     TabLeft,
     TabRight,
+    FocusTab(usize),
+    MousePress(u16, u16),
+    MouseDrag(u16, u16),
+    MouseRelease(u16, u16),
     EnterHelp,
     ExitHelp,
     EnterCommandMode,
@@ -24,14 +75,65 @@ pub enum Action {
     CommandBackspace,
     CommandSubmit,
     CommandCancel,
+    CommandComplete,
     EnterSearchMode,
     SearchChar(char),
     SearchBackspace,
+    EditLine(LineEdit),
     SearchSubmit,
     SearchCancel,
     SearchNext,
     SearchPrev,
     SearchClear,
+    SearchToggleScope,
+    SearchHistoryPrev,
+    SearchHistoryNext,
+    PickerUp,
+    PickerDown,
+    PickerSubmit,
+    PickerCancel,
+    ToggleMatches,
+    MatchesUp,
+    MatchesDown,
+    Yank,
+    EnterVisual,
+    VisualUp,
+    VisualDown,
+    VisualYank,
+    VisualOpen,
+    ExitVisual,
+    EnterCursor,
+    MoveCursor(CursorMotion),
+    CursorOpen,
+    CursorYank,
+    CursorLookup,
+    LookupSelection,
+    ExitCursor,
+    EnterHints,
+    HintChar(char),
+    ExitHints,
+    ConfirmAccept,
+    ConfirmReject,
+    RetryRender,
+    Refresh,
+    SwitchPane,
+    ResizePane(SplitDirection, i16),
+    BeginPending(char),
+    CancelPending,
+    NextOption,
+    PrevOption,
+    NextSection,
+    PrevSection,
+    ToggleFold,
+    FoldAll,
+    UnfoldAll,
+    ToggleZen,
+    ToggleSource,
+    ToggleScrollLock,
+    RepeatCommand,
+    RepeatSearch,
+    NextGroup,
+    PrevGroup,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -45,6 +147,111 @@ pub enum Mode {
         line: String,
         previous: Option<String>,
     },
+    Picker {
+        title: String,
+        entries: Vec<PickerEntry>,
+        selected: usize,
+        offset: usize,
+    },
+    Visual {
+        anchor: usize,
+        cursor: usize,
+    },
+    Confirm {
+        topic: String,
+        program: PathBuf,
+    },
+    Pending {
+        key: char,
+    },
+    Count {
+        count: usize,
+    },
+    Matches {
+        selected: usize,
+    },
+    Cursor {
+        row: usize,
+        column: usize,
+    },
+    Hints {
+        typed: String,
+        hints: Vec<LinkHint>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickerEntry {
+    pub name: String,
+    pub section: Option<String>,
+    pub description: String,
+    pub line: Option<usize>,
+}
+
+impl From<PageEntry> for PickerEntry {
+    fn from(entry: PageEntry) -> Self {
+        Self {
+            name: entry.name,
+            section: Some(entry.section),
+            description: entry.description,
+            line: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRef {
+    pub name: String,
+    pub section: Option<String>,
+    pub anchor: Option<String>,
+}
+
+pub fn parse_page_ref(value: &str) -> PageRef {
+    let (target, anchor) = match value.rsplit_once('#') {
+        Some((target, anchor)) if !target.is_empty() && !anchor.is_empty() => {
+            (target, Some(anchor.to_string()))
+        }
+        _ => match dotted_page_ref(value) {
+            Some(page) => return page,
+            None => (value, None),
+        },
+    };
+    if !is_local_page(target)
+        && let Some(stripped) = target.strip_suffix(')')
+        && let Some((name, section)) = stripped.split_once('(')
+        && !name.is_empty()
+        && !section.is_empty()
+        && !section.contains(char::is_whitespace)
+    {
+        return PageRef {
+            name: name.to_string(),
+            section: Some(section.to_string()),
+            anchor,
+        };
+    }
+    PageRef {
+        name: target.to_string(),
+        section: None,
+        anchor,
+    }
+}
+
+fn dotted_page_ref(value: &str) -> Option<PageRef> {
+    if is_local_page(value) {
+        return None;
+    }
+    let (page, anchor) = value.split_once(':')?;
+    let (name, section) = page.rsplit_once('.')?;
+    let valid = !name.is_empty()
+        && !anchor.is_empty()
+        && !name.contains(['.', '/'])
+        && section.starts_with(|ch: char| ch.is_ascii_digit())
+        && section.chars().all(|ch| ch.is_ascii_alphanumeric());
+    valid.then(|| PageRef {
+        name: name.to_string(),
+        section: Some(section.to_string()),
+        anchor: Some(anchor.to_string()),
+    })
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -53,25 +260,145 @@ enum ParsedCommand {
         topics: Vec<String>,
         section: Option<String>,
     },
+    ManAll(Vec<String>),
     Help,
     Quit,
     Wipe,
+    WipeAll,
+    Pin(bool),
+    Rename(Option<String>),
+    Width(Option<u16>),
+    MkSession(String),
+    Session(String),
+    Shell(String),
+    Cheat(String),
+    History,
+    Bookmark(String),
+    Bookmarks,
+    Apropos(String),
+    Find(String),
+    Split {
+        direction: SplitDirection,
+        topics: Vec<String>,
+    },
+    Only,
+    Close,
+    Tab(usize),
+    TabNext,
+    TabPrev,
+    Goto(String),
+    Option(String),
+    Diff(String),
+    Export(String),
+    ReloadConfig,
+    Theme(ThemeVariant),
+    Refresh,
+    Matches,
+    Debug,
+    Zen,
+    ScrollLock,
+    Line(usize),
+    Source,
+    Group(Option<String>),
+    MoveTab(String),
+    TabToSplit,
+    External {
+        tool: ExternalTool,
+        source: bool,
+    },
     Empty,
     Unknown(String),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalTool {
+    Editor,
+    Pager,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeVariant {
+    Auto,
+    Dark,
+    Light,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Effect {
+    Copy(String),
+    OpenExternal {
+        tool: ExternalTool,
+        path: PathBuf,
+        temporary: bool,
+    },
+    ReloadConfig,
+    SetTheme(ThemeVariant),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UpdateOutcome {
     Continue,
     Quit,
 }
 
+#[derive(Debug)]
+struct TabGroup {
+    name: String,
+    tabs: Vec<ManPage>,
+    active: usize,
+    layout: Option<PaneLayout>,
+}
+
+impl TabGroup {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            tabs: Vec::new(),
+            active: 0,
+            layout: None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct App {
     tabs: Vec<ManPage>,
     active: usize,
+    layout: Option<PaneLayout>,
+    screen_width: u16,
     mode: Mode,
     status_message: Option<String>,
+    effects: Vec<Effect>,
+    snapshots: Option<SnapshotStore>,
+    history: Option<History>,
+    bookmarks: Option<Bookmarks>,
+    pins: Option<PinnedPages>,
+    sessions: Option<SessionStore>,
+    cheats: Option<CheatRenderer>,
+    positions: Option<PositionStore>,
+    searches: Option<SearchHistory>,
+    search_history: Vec<String>,
+    search_recall: Option<(usize, String)>,
+    last_command: Option<String>,
+    prompt_cursor: Option<usize>,
+    highlight_code: bool,
+    keywords: Vec<String>,
+    low_memory: bool,
+    prerender_width: Option<u16>,
+    prerender_skipped: Vec<String>,
+    memory_budget: Option<usize>,
+    visits: u64,
+    search_wrap: bool,
+    debug_overlay: bool,
+    zen: bool,
+    scroll_lock: bool,
+    groups: Vec<TabGroup>,
+    group: usize,
+    frame_time: Option<Duration>,
+    text_selection: Option<TextSelection>,
+    picker_preview: Option<(String, Vec<String>)>,
+    preview_cache: HashMap<String, Vec<String>>,
+    page_index: PageIndex,
 }
 
 impl App {
@@ -79,8 +406,41 @@ impl App {
         Self {
             tabs: Vec::new(),
             active: 0,
+            layout: None,
+            screen_width: 0,
             mode: Mode::Normal,
             status_message: None,
+            effects: Vec::new(),
+            snapshots: None,
+            history: None,
+            bookmarks: None,
+            pins: None,
+            sessions: None,
+            cheats: None,
+            positions: None,
+            searches: None,
+            search_history: Vec::new(),
+            search_recall: None,
+            last_command: None,
+            prompt_cursor: None,
+            highlight_code: true,
+            keywords: Vec::new(),
+            low_memory: false,
+            prerender_width: None,
+            prerender_skipped: Vec::new(),
+            memory_budget: None,
+            visits: 0,
+            search_wrap: true,
+            debug_overlay: false,
+            zen: false,
+            scroll_lock: false,
+            groups: vec![TabGroup::new(DEFAULT_GROUP.to_string())],
+            group: 0,
+            frame_time: None,
+            text_selection: None,
+            picker_preview: None,
+            preview_cache: HashMap::new(),
+            page_index: PageIndex::default(),
         }
     }
 
@@ -88,33 +448,100 @@ impl App {
         Self {
             tabs: vec![ManPage::new(name, section)],
             active: 0,
+            layout: None,
+            screen_width: 0,
             mode: Mode::Normal,
             status_message: None,
+            effects: Vec::new(),
+            snapshots: None,
+            history: None,
+            bookmarks: None,
+            pins: None,
+            sessions: None,
+            cheats: None,
+            positions: None,
+            searches: None,
+            search_history: Vec::new(),
+            search_recall: None,
+            last_command: None,
+            prompt_cursor: None,
+            highlight_code: true,
+            keywords: Vec::new(),
+            low_memory: false,
+            prerender_width: None,
+            prerender_skipped: Vec::new(),
+            memory_budget: None,
+            visits: 0,
+            search_wrap: true,
+            debug_overlay: false,
+            zen: false,
+            scroll_lock: false,
+            groups: vec![TabGroup::new(DEFAULT_GROUP.to_string())],
+            group: 0,
+            frame_time: None,
+            text_selection: None,
+            picker_preview: None,
+            preview_cache: HashMap::new(),
+            page_index: PageIndex::default(),
         }
     }
 
+    pub fn layout(&self) -> Option<&PaneLayout> {
+        self.layout.as_ref()
+    }
+
+    pub fn is_split(&self) -> bool {
+        self.layout.is_some()
+    }
+
+    pub fn set_screen_width(&mut self, width: u16) {
+        self.screen_width = width;
+    }
+
     pub fn has_tabs(&self) -> bool {
         !self.tabs.is_empty()
     }
 
     pub fn title(&self) -> String {
-        let Some(page) = self.active_page() else {
-            return "Manifold".to_string();
-        };
-        match page.section() {
-            Some(section) => format!("{}({})", page.name(), section),
-            None => page.name().to_string(),
-        }
+        self.active_page()
+            .map(page_key)
+            .unwrap_or_else(|| "Manifold".to_string())
+    }
+
+    pub fn source_lines(&self) -> Cow<'_, [String]> {
+        self.active_page()
+            .map_or(Cow::Borrowed(&[]), ManPage::source_lines)
+    }
+
+    fn line_range(&self, rows: Range<usize>) -> Cow<'_, [String]> {
+        self.active_page()
+            .map_or(Cow::Borrowed(&[]), |page| page.line_range(rows))
+    }
+
+    pub fn fold_markers(&self) -> &[(usize, usize)] {
+        self.active_page().map(ManPage::fold_markers).unwrap_or(&[])
     }
 
     pub fn lines(&self) -> &[String] {
         self.active_page().map(ManPage::lines).unwrap_or(&[])
     }
 
+    pub fn window(&self) -> (usize, &[String]) {
+        self.active_page().map_or((0, &[]), ManPage::window)
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.active_page().map(ManPage::line_count).unwrap_or(0)
+    }
+
     pub fn scroll(&self) -> usize {
         self.active_page().map(|page| page.scroll).unwrap_or(0)
     }
 
+    pub fn column(&self) -> usize {
+        self.active_page().map(|page| page.column).unwrap_or(0)
+    }
+
     pub fn mode(&self) -> &Mode {
         &self.mode
     }
@@ -127,6 +554,179 @@ impl App {
         self.status_message = Some(message.into());
     }
 
+    pub fn track_changes(&mut self, store: SnapshotStore) {
+        self.snapshots = Some(store);
+    }
+
+    pub fn track_history(&mut self, history: History) {
+        self.history = Some(history);
+    }
+
+    pub fn track_pins(&mut self, pins: PinnedPages) {
+        self.pins = Some(pins);
+    }
+
+    pub fn track_sessions(&mut self, sessions: SessionStore) {
+        self.sessions = Some(sessions);
+    }
+
+    pub fn track_cheats(&mut self, cheats: CheatRenderer) {
+        self.cheats = Some(cheats);
+    }
+
+    pub fn track_bookmarks(&mut self, bookmarks: Bookmarks) {
+        self.bookmarks = Some(bookmarks);
+    }
+
+    pub fn track_searches(&mut self, searches: SearchHistory) {
+        self.search_history = searches.queries();
+        self.searches = Some(searches);
+    }
+
+    pub fn track_positions(&mut self, positions: PositionStore) {
+        self.positions = Some(positions);
+    }
+
+    pub fn save_positions(&mut self) -> std::io::Result<()> {
+        for index in 0..self.tabs.len() {
+            self.remember_position(index);
+        }
+        match self.positions.as_ref() {
+            Some(positions) => positions.save(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn watches_files(&self) -> bool {
+        self.tabs.iter().any(is_watched_page)
+    }
+
+    pub fn reload_changed(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> bool {
+        let mut changed: Vec<String> = Vec::new();
+        for page in self.tabs.iter().filter(|page| is_watched_page(page)) {
+            if page.source_changed() && !changed.iter().any(|name| name == page.name()) {
+                changed.push(page.name().to_string());
+            }
+        }
+        if changed.is_empty() {
+            return false;
+        }
+        for page in &mut self.tabs {
+            if changed.iter().any(|name| name == page.name()) {
+                page.reload_source();
+            }
+        }
+        self.status_message = Some(match self.resize_active(renderer, width, viewport_height) {
+            Ok(()) => format!("Reloaded {}", changed.join(", ")),
+            Err(err) => format!("Failed to reload {}: {err}", changed.join(", ")),
+        });
+        true
+    }
+
+    pub fn set_low_memory(&mut self, enabled: bool) {
+        self.low_memory = enabled;
+        for page in self
+            .tabs
+            .iter_mut()
+            .chain(self.groups.iter_mut().flat_map(|group| &mut group.tabs))
+        {
+            page.set_low_memory(enabled);
+        }
+    }
+
+    pub fn set_memory_budget(&mut self, bytes: Option<usize>) {
+        self.memory_budget = bytes;
+        self.enforce_memory_budget();
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.tabs.iter().map(ManPage::memory_usage).sum()
+    }
+
+    pub fn set_code_highlighting(&mut self, enabled: bool) {
+        self.highlight_code = enabled;
+    }
+
+    pub fn set_keywords(&mut self, keywords: Vec<String>) {
+        self.keywords = keywords;
+    }
+
+    pub fn set_search_wrap(&mut self, enabled: bool) {
+        self.search_wrap = enabled;
+    }
+
+    pub fn debug_overlay(&self) -> bool {
+        self.debug_overlay
+    }
+
+    pub fn zen(&self) -> bool {
+        self.zen
+    }
+
+    pub fn group_name(&self) -> &str {
+        &self.groups[self.group].name
+    }
+
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn record_frame(&mut self, elapsed: Duration) {
+        self.frame_time = Some(elapsed);
+    }
+
+    pub fn frame_time(&self) -> Option<Duration> {
+        self.frame_time
+    }
+
+    pub fn code_lines(&self) -> Vec<usize> {
+        match self.active_page() {
+            Some(page) if self.highlight_code => {
+                let (first, lines) = page.window();
+                code_lines(lines)
+                    .into_iter()
+                    .map(|row| first + row)
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn keyword_matches(&self) -> Vec<KeywordMatch> {
+        match self.active_page() {
+            Some(page) if !self.keywords.is_empty() => {
+                let (first, lines) = page.window();
+                keyword_matches(lines, &self.keywords)
+                    .into_iter()
+                    .map(|entry| KeywordMatch {
+                        line: first + entry.line,
+                        ..entry
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn diff_kinds(&self) -> &[DiffKind] {
+        self.active_page().map(ManPage::diff_kinds).unwrap_or(&[])
+    }
+
+    pub fn changed_lines(&self) -> &[usize] {
+        self.active_page()
+            .map(ManPage::changed_lines)
+            .unwrap_or(&[])
+    }
+
+    pub fn drain_effects(&mut self) -> Vec<Effect> {
+        std::mem::take(&mut self.effects)
+    }
+
     pub fn tabs(&self) -> &[ManPage] {
         &self.tabs
     }
@@ -135,6 +735,23 @@ impl App {
         self.active_page().and_then(ManPage::search_query)
     }
 
+    pub fn search_scope(&self) -> Option<&str> {
+        self.active_page().and_then(ManPage::search_scope)
+    }
+
+    pub fn text_selection(&self) -> Option<(TextPosition, TextPosition)> {
+        self.text_selection
+            .filter(|selection| selection.tab == self.active)
+            .map(|selection| selection.ordered())
+    }
+
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        match self.mode {
+            Mode::Visual { anchor, cursor } => Some((anchor.min(cursor), anchor.max(cursor))),
+            _ => None,
+        }
+    }
+
     pub fn active_index(&self) -> usize {
         self.active
     }
@@ -145,48 +762,281 @@ impl App {
         renderer: &dyn ManRenderer,
         width: u16,
         viewport_height: usize,
+    ) -> Result<UpdateOutcome, RenderError> {
+        if let Action::Resize(screen_width, _) = action {
+            self.screen_width = screen_width;
+        }
+        if !matches!(
+            action,
+            Action::MousePress(..)
+                | Action::MouseDrag(..)
+                | Action::MouseRelease(..)
+                | Action::ScrollUp(_)
+                | Action::ScrollDown(_)
+                | Action::ScrollLeft(_)
+                | Action::ScrollRight(_)
+                | Action::PageUp
+                | Action::PageDown
+                | Action::HalfPageUp
+                | Action::HalfPageDown
+                | Action::GoTop
+                | Action::GoBottom
+                | Action::GoToLine(_)
+                | Action::CountDigit(_)
+                | Action::LookupSelection
+        ) {
+            self.text_selection = None;
+        }
+        let Some(action) = self.localize_mouse(action, width, viewport_height) else {
+            self.text_selection = None;
+            return Ok(UpdateOutcome::Continue);
+        };
+        let before = self.active;
+        let was_split = self.is_split();
+        let scroll_before = self.scroll();
+        let (pane_width, pane_height) = self.focused_geometry(width, viewport_height);
+        let outcome = self.apply_action(action, renderer, pane_width, pane_height);
+        self.sync_layout(before);
+        self.visits += 1;
+        let visits = self.visits;
+        if let Some(page) = self.active_page_mut() {
+            page.last_active = visits;
+        }
+        self.enforce_memory_budget();
+        self.refresh_picker_preview(renderer, width, viewport_height);
+        let outcome = outcome?;
+        if self.is_split() || was_split {
+            self.resize_active(renderer, width, viewport_height)?;
+        }
+        if self.scroll_lock && self.active == before && self.scroll() != scroll_before {
+            self.sync_scroll(width, viewport_height);
+        }
+        self.spool_pages(viewport_height);
+        self.show_render_warning();
+        Ok(outcome)
+    }
+
+    fn apply_action(
+        &mut self,
+        action: Action,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
     ) -> Result<UpdateOutcome, RenderError> {
         if self.status_message.is_some() && should_clear_status(&action) {
             self.status_message = None;
         }
+        if matches!(self.mode, Mode::Pending { .. })
+            && !matches!(action, Action::BeginPending(_) | Action::Resize(..))
+        {
+            self.mode = Mode::Normal;
+        }
+        if matches!(self.mode, Mode::Count { .. })
+            && !matches!(action, Action::CountDigit(_) | Action::Resize(..))
+        {
+            self.mode = Mode::Normal;
+        }
         match action {
             Action::Quit => return Ok(UpdateOutcome::Quit),
             Action::ScrollUp(amount) => self.scroll_up(amount),
             Action::ScrollDown(amount) => self.scroll_down(amount, viewport_height),
+            Action::ScrollLeft(amount) => {
+                if let Some(page) = self.active_page_mut() {
+                    page.column = page.column.saturating_sub(amount);
+                }
+            }
+            Action::ScrollRight(amount) => self.scroll_right(amount, viewport_height),
             Action::PageUp => self.page_up(viewport_height),
             Action::PageDown => self.page_down(viewport_height),
             Action::HalfPageUp => self.half_page_up(viewport_height),
             Action::HalfPageDown => self.half_page_down(viewport_height),
-            Action::DecreaseWidth | Action::IncreaseWidth => {
-                self.resize_active(renderer, width, viewport_height)?;
+            Action::DecreaseWidth | Action::IncreaseWidth | Action::Resize(_, _) => {
+                if self.layout.is_none() {
+                    self.resize_active(renderer, width, viewport_height)?;
+                }
             }
-            Action::Resize(_, _) => self.resize_active(renderer, width, viewport_height)?,
             Action::GoTop => self.go_top(),
             Action::GoBottom => self.go_bottom(viewport_height),
+            Action::GoToLine(line) => self.go_to_line(line, viewport_height),
+            Action::CountDigit(digit) => {
+                let count = match self.mode {
+                    Mode::Count { count } => count.saturating_mul(10).saturating_add(digit),
+                    _ => digit,
+                };
+                self.mode = Mode::Count { count };
+            }
             Action::TabLeft => self.switch_tab_left(renderer, width, viewport_height)?,
             Action::TabRight => self.switch_tab_right(renderer, width, viewport_height)?,
+            Action::FocusTab(index) => self.focus_tab(index, renderer, width, viewport_height)?,
+            Action::MousePress(column, row) => {
+                let active = self.active;
+                self.text_selection = self
+                    .text_position(column, row)
+                    .map(|position| TextSelection::new(active, position));
+            }
+            Action::MouseDrag(column, row) => self.extend_text_selection(column, row),
+            Action::MouseRelease(column, row) => {
+                self.extend_text_selection(column, row);
+                self.copy_text_selection();
+            }
             Action::EnterHelp => self.mode = Mode::Help,
             Action::ExitHelp => self.mode = Mode::Normal,
             Action::EnterCommandMode => self.enter_command_mode(),
-            Action::CommandChar(value) => self.command_char(value),
-            Action::CommandBackspace => self.command_backspace(),
+            Action::CommandChar(value) => {
+                self.edit_prompt(LineEdit::Insert(value), viewport_height)
+            }
+            Action::CommandBackspace => self.edit_prompt(LineEdit::Backspace, viewport_height),
             Action::CommandCancel => self.mode = Mode::Normal,
+            Action::CommandComplete => self.complete_command(),
             Action::EnterSearchMode => self.enter_search_mode(),
-            Action::SearchChar(value) => self.search_char(value, viewport_height),
-            Action::SearchBackspace => self.search_backspace(viewport_height),
+            Action::SearchChar(value) => self.edit_prompt(LineEdit::Insert(value), viewport_height),
+            Action::SearchBackspace => self.edit_prompt(LineEdit::Backspace, viewport_height),
+            Action::EditLine(edit) => self.edit_prompt(edit, viewport_height),
             Action::SearchSubmit => self.search_submit(viewport_height),
             Action::SearchCancel => self.search_cancel(viewport_height),
-            Action::SearchNext => self.search_next(viewport_height),
-            Action::SearchPrev => self.search_prev(viewport_height),
+            Action::SearchNext => self.search_step(true, viewport_height),
+            Action::SearchPrev => self.search_step(false, viewport_height),
             Action::SearchClear => self.search_clear(),
+            Action::SearchToggleScope => self.search_toggle_scope(viewport_height),
+            Action::SearchHistoryPrev => self.recall_search(true, viewport_height),
+            Action::SearchHistoryNext => self.recall_search(false, viewport_height),
+            Action::PickerUp => self.picker_move(false, viewport_height),
+            Action::PickerDown => self.picker_move(true, viewport_height),
+            Action::PickerCancel => self.mode = Mode::Normal,
+            Action::ToggleMatches => self.toggle_matches(),
+            Action::MatchesUp => self.move_match_selection(false, viewport_height),
+            Action::MatchesDown => self.move_match_selection(true, viewport_height),
+            Action::Yank => self.yank_line(),
+            Action::EnterVisual => self.enter_visual(),
+            Action::VisualUp => self.visual_move(false, viewport_height),
+            Action::VisualDown => self.visual_move(true, viewport_height),
+            Action::VisualYank => self.visual_yank(),
+            Action::ExitVisual => self.mode = Mode::Normal,
+            Action::VisualOpen => self.visual_open(renderer, width, viewport_height)?,
+            Action::EnterCursor => self.enter_cursor(),
+            Action::MoveCursor(motion) => self.move_cursor(motion, viewport_height),
+            Action::CursorOpen => self.cursor_open(renderer, width, viewport_height)?,
+            Action::CursorYank => self.cursor_yank(),
+            Action::CursorLookup => {
+                if let Some(word) = self.word_under_cursor() {
+                    self.mode = Mode::Normal;
+                    let command = ParsedCommand::Man {
+                        topics: vec![word],
+                        section: None,
+                    };
+                    return self.execute_command(command, renderer, width, viewport_height);
+                }
+                self.status_message = Some("No word under the cursor".to_string());
+            }
+            Action::LookupSelection => {
+                return self.lookup_selection(renderer, width, viewport_height);
+            }
+            Action::ExitCursor => self.mode = Mode::Normal,
+            Action::EnterHints => {
+                let hints = self.link_hints(viewport_height);
+                if hints.is_empty() {
+                    self.status_message = Some("No references on screen".to_string());
+                } else {
+                    self.mode = Mode::Hints {
+                        typed: String::new(),
+                        hints,
+                    };
+                }
+            }
+            Action::HintChar(ch) => self.hint_char(ch, renderer, width, viewport_height)?,
+            Action::ExitHints => self.mode = Mode::Normal,
+            Action::PickerSubmit => {
+                self.picker_submit(renderer, width, viewport_height)?;
+            }
+            Action::SwitchPane => {
+                self.switch_pane();
+                self.clamp_scroll(viewport_height);
+            }
+            Action::ResizePane(direction, delta) => self.resize_pane(direction, delta),
+            Action::BeginPending(key) => self.mode = Mode::Pending { key },
+            Action::CancelPending => self.mode = Mode::Normal,
+            Action::ToggleZen => self.zen = !self.zen,
+            Action::ToggleScrollLock => self.toggle_scroll_lock(),
+            Action::RepeatSearch => self.repeat_search(viewport_height),
+            Action::RepeatCommand => {
+                let Some(line) = self.last_command.clone() else {
+                    self.status_message = Some("No command to repeat".to_string());
+                    return Ok(UpdateOutcome::Continue);
+                };
+                return self.execute_command(
+                    parse_command(&line),
+                    renderer,
+                    width,
+                    viewport_height,
+                );
+            }
+            Action::ToggleSource => self.toggle_source(renderer),
+            Action::NextGroup | Action::PrevGroup => {
+                let count = self.groups.len();
+                let index = if action == Action::NextGroup {
+                    (self.group + 1) % count
+                } else {
+                    (self.group + count - 1) % count
+                };
+                self.switch_group(index, renderer, width, viewport_height)?;
+            }
+            Action::NextOption | Action::PrevOption => {
+                self.mode = Mode::Normal;
+                let options = self.active_page().map(ManPage::options);
+                let forward = action == Action::NextOption;
+                self.jump_between(options, forward, "options", viewport_height);
+            }
+            Action::ToggleFold | Action::FoldAll
+                if self.active_page().is_some_and(ManPage::is_spooled) =>
+            {
+                self.mode = Mode::Normal;
+                self.status_message = Some("Folding is not available with --low-mem".to_string());
+            }
+            Action::ToggleFold => {
+                self.mode = Mode::Normal;
+                self.toggle_fold(viewport_height);
+            }
+            Action::FoldAll | Action::UnfoldAll => {
+                self.mode = Mode::Normal;
+                if let Some(page) = self.active_page_mut() {
+                    if action == Action::FoldAll {
+                        page.fold_all();
+                    } else {
+                        page.unfold_all();
+                    }
+                }
+                self.clamp_scroll(viewport_height);
+            }
+            Action::NextSection | Action::PrevSection => {
+                self.mode = Mode::Normal;
+                let headings = self.active_page().map(ManPage::headings);
+                let forward = action == Action::NextSection;
+                self.jump_between(headings, forward, "sections", viewport_height);
+            }
+            Action::ConfirmAccept => self.confirm_accept(renderer, width, viewport_height),
+            Action::ConfirmReject => self.confirm_reject(),
+            Action::RetryRender => self.retry_render(renderer, width, viewport_height),
+            Action::Refresh => self.refresh_active(renderer, width, viewport_height),
             Action::CommandSubmit => {
                 let line = match std::mem::replace(&mut self.mode, Mode::Normal) {
                     Mode::Command { line } => line,
                     Mode::Normal => String::new(),
                     Mode::Help => String::new(),
                     Mode::Search { line, .. } => line,
+                    Mode::Picker { .. }
+                    | Mode::Visual { .. }
+                    | Mode::Confirm { .. }
+                    | Mode::Pending { .. }
+                    | Mode::Count { .. }
+                    | Mode::Matches { .. }
+                    | Mode::Cursor { .. }
+                    | Mode::Hints { .. } => String::new(),
                 };
                 let command = parse_command(&line);
+                tracing::debug!(line, ?command, "parsed command");
+                if !matches!(command, ParsedCommand::Empty | ParsedCommand::Unknown(_)) {
+                    self.last_command = Some(line.trim().to_string());
+                }
                 return self.execute_command(command, renderer, width, viewport_height);
             }
         }
@@ -201,7 +1051,64 @@ impl App {
         width: u16,
         viewport_height: usize,
     ) -> Result<(), RenderError> {
-        self.open_pages_internal(topics, section, renderer, width, viewport_height)
+        let result = self.open_pages_internal(topics, section, renderer, width, viewport_height);
+        self.spool_pages(viewport_height);
+        self.show_render_warning();
+        result
+    }
+
+    pub fn open_all_sections(
+        &mut self,
+        topics: Vec<String>,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        let result = self.open_every_section(topics, renderer, width, viewport_height);
+        self.spool_pages(viewport_height);
+        result
+    }
+
+    fn open_every_section(
+        &mut self,
+        topics: Vec<String>,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        let first = self.tabs.len();
+        for topic in topics {
+            let sections = renderer.sections(&topic).unwrap_or_default();
+            if sections.is_empty() {
+                self.open_pages_internal(vec![topic], None, renderer, width, viewport_height)?;
+                continue;
+            }
+            for section in sections {
+                self.open_pages_internal(
+                    vec![topic.clone()],
+                    Some(section),
+                    renderer,
+                    width,
+                    viewport_height,
+                )?;
+            }
+        }
+        if self.tabs.len() > first && self.mode == Mode::Normal {
+            self.active = first;
+        }
+        Ok(())
+    }
+
+    pub fn open_text(
+        &mut self,
+        name: impl Into<String>,
+        section: Option<String>,
+        lines: Vec<String>,
+        viewport_height: usize,
+    ) {
+        self.tabs.push(ManPage::with_lines(name, section, lines));
+        self.active = self.tabs.len() - 1;
+        self.clamp_scroll(viewport_height);
     }
 
     pub fn resize_active(
@@ -210,37 +1117,130 @@ impl App {
         width: u16,
         viewport_height: usize,
     ) -> Result<(), RenderError> {
+        if self.layout.is_some() {
+            return self.render_panes(renderer, width, viewport_height);
+        }
         let Some(page) = self.active_page_mut() else {
             return Ok(());
         };
         page.ensure_render(renderer, width)?;
         self.clamp_scroll(viewport_height);
+        self.set_prerender_width(width);
+        self.spool_pages(viewport_height);
         Ok(())
     }
 
-    pub fn scroll_up(&mut self, amount: usize) {
-        let Some(page) = self.active_page_mut() else {
-            return;
-        };
-        let current = page.scroll;
-        page.scroll = current.saturating_sub(amount);
+    fn set_prerender_width(&mut self, width: u16) {
+        if self.prerender_width != Some(width) {
+            self.prerender_width = Some(width);
+            self.prerender_skipped.clear();
+        }
     }
 
-    pub fn scroll_down(&mut self, amount: usize, viewport_height: usize) {
-        let max_scroll = self.max_scroll(viewport_height);
-        let Some(page) = self.active_page_mut() else {
-            return;
-        };
-        let next = (page.scroll + amount).min(max_scroll);
-        page.scroll = next;
+    fn show_render_warning(&mut self) {
+        if self.status_message.is_none()
+            && let Some(warning) = self.active_page_mut().and_then(ManPage::take_warning)
+        {
+            self.status_message = Some(warning);
+        }
     }
 
-    pub fn page_up(&mut self, viewport_height: usize) {
-        self.scroll_up(viewport_height);
+    pub fn streaming(&self) -> bool {
+        self.tabs.iter().any(ManPage::is_streaming)
     }
 
-    pub fn page_down(&mut self, viewport_height: usize) {
-        self.scroll_down(viewport_height, viewport_height);
+    pub fn receive_streams(&mut self, viewport_height: usize) -> bool {
+        let mut changed = false;
+        for page in self.tabs.iter_mut().filter(|page| page.is_streaming()) {
+            match page.receive_stream(Duration::ZERO) {
+                Ok(received) => changed |= received,
+                Err(err) => {
+                    tracing::warn!(page = page.name(), "render failed: {err}");
+                    page.show_error(error_message(err));
+                    changed = true;
+                }
+            }
+        }
+        if changed && self.has_tabs() {
+            self.clamp_scroll(viewport_height);
+            self.show_render_warning();
+            self.enforce_memory_budget();
+            self.spool_pages(viewport_height);
+        }
+        changed
+    }
+
+    pub fn prerender_pending(&self) -> bool {
+        self.next_prerender().is_some()
+    }
+
+    pub fn prerender_next(&mut self, renderer: &dyn ManRenderer) -> bool {
+        if self.streaming() {
+            return false;
+        }
+        let Some((index, width)) = self.next_prerender() else {
+            return false;
+        };
+        let page = &mut self.tabs[index];
+        match page.start_render(renderer, width) {
+            Ok(true) => {}
+            Ok(false) => self.prerender_skipped.push(page_key(page)),
+            Err(err) => {
+                tracing::warn!(page = page.name(), "prerender failed: {err}");
+                page.show_error(error_message(err));
+            }
+        }
+        true
+    }
+
+    fn next_prerender(&self) -> Option<(usize, u16)> {
+        let width = self.prerender_width?;
+        let visible = self.visible_tabs();
+        self.tabs
+            .iter()
+            .enumerate()
+            .find(|(index, page)| {
+                !visible.contains(index)
+                    && !self.prerender_skipped.contains(&page_key(page))
+                    && page.line_count() > 0
+                    && page.needs_render(width)
+            })
+            .map(|(index, _)| (index, width))
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        let current = page.scroll;
+        page.scroll = current.saturating_sub(amount);
+    }
+
+    pub fn scroll_down(&mut self, amount: usize, viewport_height: usize) {
+        let max_scroll = self.max_scroll(viewport_height);
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        let next = (page.scroll + amount).min(max_scroll);
+        page.scroll = next;
+    }
+
+    fn scroll_right(&mut self, amount: usize, viewport_height: usize) {
+        let area = self.focused_area(self.screen_width, viewport_height);
+        let text_width = usize::from(area.width.saturating_sub(self.gutter_width()));
+        let widest = self.active_page().map_or(0, ManPage::widest_line);
+        let max_column = widest.saturating_sub(text_width);
+        if let Some(page) = self.active_page_mut() {
+            page.column = (page.column + amount).min(max_column.max(page.column));
+        }
+    }
+
+    pub fn page_up(&mut self, viewport_height: usize) {
+        self.scroll_up(viewport_height);
+    }
+
+    pub fn page_down(&mut self, viewport_height: usize) {
+        self.scroll_down(viewport_height, viewport_height);
     }
 
     pub fn half_page_up(&mut self, viewport_height: usize) {
@@ -266,6 +1266,13 @@ impl App {
         }
     }
 
+    pub fn go_to_line(&mut self, line: usize, viewport_height: usize) {
+        let max_scroll = self.max_scroll(viewport_height);
+        if let Some(page) = self.active_page_mut() {
+            page.scroll = line.saturating_sub(1).min(max_scroll);
+        }
+    }
+
     pub fn clamp_scroll(&mut self, viewport_height: usize) {
         let max_scroll = self.max_scroll(viewport_height);
         let Some(page) = self.active_page_mut() else {
@@ -288,6 +1295,243 @@ impl App {
         lines.saturating_sub(visible)
     }
 
+    fn visible_tabs(&self) -> Vec<usize> {
+        self.layout
+            .as_ref()
+            .map(PaneLayout::tabs)
+            .unwrap_or_else(|| vec![self.active])
+    }
+
+    fn spool_pages(&mut self, viewport_height: usize) {
+        if !self.low_memory {
+            return;
+        }
+        let visible = self.visible_tabs();
+        for (index, page) in self.tabs.iter_mut().enumerate() {
+            let rows = if visible.contains(&index) {
+                page.scroll.saturating_sub(viewport_height)..page.scroll + viewport_height * 2
+            } else {
+                0..0
+            };
+            if let Err(err) = page.load_window(rows) {
+                tracing::warn!(page = page.name(), "could not spool page lines: {err}");
+            }
+        }
+    }
+
+    fn enforce_memory_budget(&mut self) {
+        let Some(budget) = self.memory_budget else {
+            return;
+        };
+        let mut usage = self.memory_usage();
+        if usage <= budget {
+            return;
+        }
+        let visible = self.visible_tabs();
+        let mut idle: Vec<usize> = (0..self.tabs.len())
+            .filter(|index| !visible.contains(index) && self.tabs[*index].line_count() > 0)
+            .collect();
+        idle.sort_by_key(|index| self.tabs[*index].last_active);
+        for index in idle {
+            if usage <= budget {
+                break;
+            }
+            let page = &mut self.tabs[index];
+            usage -= page.memory_usage();
+            page.release_render();
+            usage += page.memory_usage();
+        }
+    }
+
+    fn pane_geometry(&self, width: u16, viewport_height: usize) -> Vec<(usize, u16, usize)> {
+        let Some(layout) = self.layout.as_ref() else {
+            return Vec::new();
+        };
+        let screen_width = if self.screen_width == 0 {
+            width
+        } else {
+            self.screen_width
+        };
+        let height = u16::try_from(viewport_height).unwrap_or(u16::MAX);
+        layout
+            .tabs()
+            .into_iter()
+            .zip(layout.areas(screen_width, height))
+            .map(|(tab, area)| {
+                let pane_width = width.min(area.width).max(MIN_CONTENT_WIDTH);
+                (tab, pane_width, usize::from(area.height))
+            })
+            .collect()
+    }
+
+    fn focused_geometry(&self, width: u16, viewport_height: usize) -> (u16, usize) {
+        let Some(layout) = self.layout.as_ref() else {
+            return (width, viewport_height);
+        };
+        self.pane_geometry(width, viewport_height)
+            .get(layout.focus())
+            .map(|(_, width, height)| (*width, *height))
+            .unwrap_or((width, viewport_height))
+    }
+
+    fn render_panes(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        let panes = self.pane_geometry(width, viewport_height);
+        for &(tab, pane_width, pane_height) in &panes {
+            if let Some(page) = self.tabs.get_mut(tab) {
+                page.ensure_render(renderer, pane_width)?;
+                let max_scroll = page.line_count().saturating_sub(pane_height.max(1));
+                page.scroll = page.scroll.min(max_scroll);
+            }
+        }
+        let focus = self.layout.as_ref().map_or(0, PaneLayout::focus);
+        if let Some(&(_, pane_width, _)) = panes.get(focus) {
+            self.set_prerender_width(pane_width);
+        }
+        Ok(())
+    }
+
+    fn toggle_scroll_lock(&mut self) {
+        self.scroll_lock = !self.scroll_lock;
+        self.status_message = Some(if self.scroll_lock {
+            "Scroll lock on: split panes scroll together".to_string()
+        } else {
+            "Scroll lock off".to_string()
+        });
+    }
+
+    pub fn scroll_lock(&self) -> bool {
+        self.scroll_lock
+    }
+
+    fn sync_scroll(&mut self, width: u16, viewport_height: usize) {
+        let Some(focus) = self.layout.as_ref().map(PaneLayout::focus) else {
+            return;
+        };
+        let panes = self.pane_geometry(width, viewport_height);
+        let Some((_, _, height)) = panes.get(focus).copied() else {
+            return;
+        };
+        let Some(page) = self.active_page() else {
+            return;
+        };
+        let max_scroll = page.line_count().saturating_sub(height.max(1));
+        let scroll = page.scroll.min(max_scroll);
+        for (tab, _, pane_height) in panes {
+            if tab == self.active {
+                continue;
+            }
+            if let Some(page) = self.tabs.get_mut(tab) {
+                let other_max = page.line_count().saturating_sub(pane_height.max(1));
+                page.scroll = (scroll * other_max + max_scroll / 2)
+                    .checked_div(max_scroll)
+                    .unwrap_or(0);
+            }
+        }
+    }
+
+    fn sync_layout(&mut self, before: usize) {
+        let Some(layout) = self.layout.as_mut() else {
+            return;
+        };
+        if self.tabs.len() < 2 {
+            self.layout = None;
+            return;
+        }
+        let focus = layout.focus();
+        layout.set_tab(focus, self.active);
+        let tabs = layout.tabs();
+        if let Some(pane) =
+            (0..tabs.len()).find(|pane| *pane != focus && tabs[*pane] == self.active)
+        {
+            if before != self.active && before < self.tabs.len() && !tabs.contains(&before) {
+                layout.set_tab(pane, before);
+            } else {
+                layout.close_pane(pane);
+            }
+        }
+        if layout.pane_count() < 2 {
+            self.layout = None;
+        }
+    }
+
+    fn forget_tab(&mut self, index: usize) {
+        if let Some(layout) = self.layout.as_mut() {
+            layout.forget_tab(index);
+        }
+    }
+
+    fn switch_pane(&mut self) {
+        if let Some(layout) = self.layout.as_mut() {
+            self.active = layout.focus_next();
+        }
+    }
+
+    fn close_pane(&mut self) {
+        let Some(layout) = self.layout.as_mut() else {
+            return;
+        };
+        layout.close_focused();
+        self.active = layout.focused_tab();
+        if layout.pane_count() < 2 {
+            self.layout = None;
+        }
+    }
+
+    fn resize_pane(&mut self, direction: SplitDirection, delta: i16) {
+        let resized = self
+            .layout
+            .as_mut()
+            .is_some_and(|layout| layout.resize(direction, delta));
+        if !resized {
+            self.status_message = Some("No split to resize in that direction".to_string());
+        }
+    }
+
+    fn split_view(
+        &mut self,
+        direction: SplitDirection,
+        topics: Vec<String>,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        if topics.is_empty() {
+            let shown = self
+                .layout
+                .as_ref()
+                .map(PaneLayout::tabs)
+                .unwrap_or_else(|| vec![self.active]);
+            let count = self.tabs.len();
+            let Some(other) = (1..count)
+                .map(|offset| (self.active + offset) % count)
+                .find(|tab| !shown.contains(tab))
+            else {
+                self.status_message = Some("Open another page to split the view".to_string());
+                return Ok(());
+            };
+            self.layout
+                .get_or_insert_with(|| PaneLayout::new(self.active))
+                .split(direction, other);
+            self.active = other;
+            return Ok(());
+        }
+        let previous = self.has_tabs().then_some(self.active);
+        self.open_pages_internal(topics, None, renderer, width, viewport_height)?;
+        if let Some(previous) = previous
+            && previous != self.active
+        {
+            self.layout
+                .get_or_insert_with(|| PaneLayout::new(previous))
+                .split(direction, self.active);
+        }
+        Ok(())
+    }
+
     fn active_page(&self) -> Option<&ManPage> {
         self.tabs.get(self.active)
     }
@@ -297,21 +1541,85 @@ impl App {
     }
 
     fn enter_command_mode(&mut self) {
+        self.prompt_cursor = None;
         self.mode = Mode::Command {
             line: String::new(),
         };
     }
 
-    fn command_char(&mut self, value: char) {
-        if let Mode::Command { line } = &mut self.mode {
-            line.push(value);
+    pub fn picker_preview(&self) -> Option<(&str, &[String])> {
+        self.picker_preview
+            .as_ref()
+            .map(|(title, lines)| (title.as_str(), lines.as_slice()))
+    }
+
+    pub fn set_page_index(&mut self, entries: Vec<PageEntry>) {
+        self.page_index = PageIndex::new(entries);
+    }
+
+    pub fn page_index(&self) -> &PageIndex {
+        &self.page_index
+    }
+
+    pub fn prompt_cursor(&self) -> usize {
+        let len = match &self.mode {
+            Mode::Command { line } | Mode::Search { line, .. } => line.chars().count(),
+            _ => return 0,
+        };
+        self.prompt_cursor.map_or(len, |cursor| cursor.min(len))
+    }
+
+    fn edit_prompt(&mut self, edit: LineEdit, viewport_height: usize) {
+        let cursor = self.prompt_cursor();
+        let incremental = !self.active_page().is_some_and(ManPage::is_spooled);
+        let (line, search) = match &mut self.mode {
+            Mode::Command { line } => (line, false),
+            Mode::Search { line, .. } => (line, true),
+            _ => return,
+        };
+        let before = line.len();
+        self.prompt_cursor = Some(apply_edit(line, cursor, edit));
+        if search && line.len() != before {
+            let query = line.clone();
+            self.search_recall = None;
+            if incremental {
+                self.apply_search(&query, viewport_height);
+            }
         }
     }
 
-    fn command_backspace(&mut self) {
-        if let Mode::Command { line } = &mut self.mode {
-            line.pop();
+    fn complete_command(&mut self) {
+        let Mode::Command { line } = &self.mode else {
+            return;
+        };
+        let Some((head, prefix)) = line.rsplit_once(' ') else {
+            return;
+        };
+        if prefix.is_empty()
+            || !matches!(
+                head.split_whitespace().next(),
+                Some("man" | "split" | "vsplit" | "vs" | "hsplit" | "sp")
+            )
+        {
+            return;
         }
+        let completed = match self.page_index.complete(prefix).as_slice() {
+            [] => return,
+            [name] => format!("{head} {name} "),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.to_string(), |common, name| {
+                    common
+                        .chars()
+                        .zip(name.chars())
+                        .take_while(|(a, b)| a == b)
+                        .map(|(a, _)| a)
+                        .collect()
+                });
+                format!("{head} {common}")
+            }
+        };
+        self.prompt_cursor = None;
+        self.mode = Mode::Command { line: completed };
     }
 
     fn enter_search_mode(&mut self) {
@@ -319,41 +1627,59 @@ impl App {
             return;
         };
         let previous = page.search_query().map(|value| value.to_string());
+        self.search_recall = None;
+        self.prompt_cursor = None;
         self.mode = Mode::Search {
             line: String::new(),
             previous,
         };
     }
 
-    fn search_char(&mut self, value: char, viewport_height: usize) {
-        let query = match &mut self.mode {
-            Mode::Search { line, .. } => {
-                line.push(value);
-                line.clone()
-            }
+    fn search_submit(&mut self, viewport_height: usize) {
+        let query = match &self.mode {
+            Mode::Search { line, .. } => line.clone(),
             _ => return,
         };
         self.apply_search(&query, viewport_height);
+        self.mode = Mode::Normal;
+        self.record_search(query);
     }
 
-    fn search_backspace(&mut self, viewport_height: usize) {
-        let query = match &mut self.mode {
-            Mode::Search { line, .. } => {
-                line.pop();
-                line.clone()
-            }
-            _ => return,
-        };
-        self.apply_search(&query, viewport_height);
+    fn record_search(&mut self, query: String) {
+        if query.trim().is_empty() {
+            return;
+        }
+        if let Some(searches) = &self.searches
+            && let Err(err) = searches.record(&query)
+        {
+            self.status_message = Some(format!("Could not record search: {err}"));
+        }
+        self.search_history.retain(|existing| *existing != query);
+        self.search_history.push(query);
     }
 
-    fn search_submit(&mut self, viewport_height: usize) {
-        let query = match &self.mode {
-            Mode::Search { line, .. } => line.clone(),
-            _ => return,
+    fn recall_search(&mut self, older: bool, viewport_height: usize) {
+        let Mode::Search { line, .. } = &mut self.mode else {
+            return;
+        };
+        let count = self.search_history.len();
+        let recall = match self.search_recall.take() {
+            None if older && count > 0 => Some((count - 1, line.clone())),
+            None => None,
+            Some((index, draft)) if older => Some((index.saturating_sub(1), draft)),
+            Some((index, draft)) if index + 1 < count => Some((index + 1, draft)),
+            Some((_, draft)) => {
+                *line = draft;
+                None
+            }
         };
+        if let Some((index, _)) = &recall {
+            *line = self.search_history[*index].clone();
+        }
+        let query = line.clone();
+        self.search_recall = recall;
+        self.prompt_cursor = None;
         self.apply_search(&query, viewport_height);
-        self.mode = Mode::Normal;
     }
 
     fn search_cancel(&mut self, viewport_height: usize) {
@@ -369,484 +1695,4366 @@ impl App {
         self.mode = Mode::Normal;
     }
 
-    fn search_next(&mut self, viewport_height: usize) {
+    fn search_step(&mut self, forward: bool, viewport_height: usize) {
+        let wrap = self.search_wrap;
         let Some(page) = self.active_page_mut() else {
             return;
         };
-        if let Some(line) = page.next_match_line() {
-            self.center_on_line(line, viewport_height);
+        let step = if forward {
+            page.next_match_line(wrap)
+        } else {
+            page.previous_match_line(wrap)
+        };
+        match step {
+            Some(MatchStep::Moved(line)) => self.center_on_line(line, viewport_height),
+            Some(MatchStep::Wrapped(line)) => {
+                self.center_on_line(line, viewport_height);
+                self.status_message = Some(if forward {
+                    "Search wrapped to top".to_string()
+                } else {
+                    "Search wrapped to bottom".to_string()
+                });
+            }
+            Some(MatchStep::Stopped) => {
+                self.status_message = Some(if forward {
+                    "Search hit bottom".to_string()
+                } else {
+                    "Search hit top".to_string()
+                });
+            }
+            None => {}
         }
     }
 
-    fn search_prev(&mut self, viewport_height: usize) {
-        let Some(page) = self.active_page_mut() else {
-            return;
-        };
-        if let Some(line) = page.previous_match_line() {
-            self.center_on_line(line, viewport_height);
+    fn repeat_search(&mut self, viewport_height: usize) {
+        match self.search_history.last().cloned() {
+            Some(query) => self.apply_search(&query, viewport_height),
+            None => self.status_message = Some("No search to repeat".to_string()),
         }
     }
 
     fn search_clear(&mut self) {
         if let Some(page) = self.active_page_mut() {
             page.clear_search();
+            page.set_search_scope(None);
         }
     }
 
-    fn switch_tab_left(
-        &mut self,
-        renderer: &dyn ManRenderer,
-        width: u16,
-        viewport_height: usize,
-    ) -> Result<(), RenderError> {
-        if self.tabs.is_empty() {
-            return Ok(());
+    fn search_toggle_scope(&mut self, viewport_height: usize) {
+        let Mode::Search { line, .. } = &self.mode else {
+            return;
+        };
+        let query = line.clone();
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        let scope = match page.search_scope() {
+            Some(_) => None,
+            None => page.section_at(page.scroll),
+        };
+        page.set_search_scope(scope);
+        if !query.is_empty() {
+            self.apply_search(&query, viewport_height);
         }
-        if self.active == 0 {
-            self.active = self.tabs.len() - 1;
-        } else {
-            self.active -= 1;
+    }
+
+    fn yank_line(&mut self) {
+        let scroll = self.scroll();
+        let Some(line) = self.line_range(scroll..scroll + 1).first().cloned() else {
+            return;
+        };
+        self.effects.push(Effect::Copy(line.trim_end().to_string()));
+        self.status_message = Some("Yanked 1 line".to_string());
+    }
+
+    fn enter_visual(&mut self) {
+        if self.line_count() == 0 {
+            return;
         }
-        if let Some(page) = self.active_page_mut() {
-            page.ensure_render(renderer, width)?;
+        let line = self.scroll();
+        self.mode = Mode::Visual {
+            anchor: line,
+            cursor: line,
+        };
+    }
+
+    fn visual_move(&mut self, forward: bool, viewport_height: usize) {
+        let last = self.line_count().saturating_sub(1);
+        let Mode::Visual { cursor, .. } = &mut self.mode else {
+            return;
+        };
+        *cursor = if forward {
+            (*cursor + 1).min(last)
+        } else {
+            cursor.saturating_sub(1)
+        };
+        let cursor = *cursor;
+        self.reveal_line(cursor, viewport_height);
+    }
+
+    fn reveal_line(&mut self, line: usize, viewport_height: usize) {
+        let visible = viewport_height.max(1);
+        if line < self.scroll() {
+            self.scroll_to_line(line, viewport_height);
+        } else if line >= self.scroll() + visible {
+            self.scroll_to_line(line + 1 - visible, viewport_height);
         }
-        self.clamp_scroll(viewport_height);
-        Ok(())
     }
 
-    fn switch_tab_right(
+    fn enter_cursor(&mut self) {
+        let scroll = self.scroll();
+        let lines = self.line_range(scroll..scroll + 1);
+        let Some(line) = lines.first() else {
+            return;
+        };
+        let column = line.chars().take_while(|ch| ch.is_whitespace()).count();
+        let column = column.min(line.chars().count().saturating_sub(1));
+        self.mode = Mode::Cursor {
+            row: self.scroll(),
+            column,
+        };
+    }
+
+    fn move_cursor(&mut self, motion: CursorMotion, viewport_height: usize) {
+        let Mode::Cursor { row, column } = self.mode else {
+            return;
+        };
+        let first = row.saturating_sub(viewport_height);
+        let lines = self.line_range(first..row + viewport_height + 1);
+        let (row, column) = apply_motion(&lines, (row - first, column), motion);
+        let row = first + row;
+        drop(lines);
+        self.mode = Mode::Cursor { row, column };
+        self.reveal_line(row, viewport_height);
+    }
+
+    fn link_hints(&self, viewport_height: usize) -> Vec<LinkHint> {
+        let scroll = self.scroll();
+        collect_hints(&self.line_range(scroll..scroll + viewport_height), scroll)
+    }
+
+    fn hint_char(
         &mut self,
+        ch: char,
         renderer: &dyn ManRenderer,
         width: u16,
         viewport_height: usize,
     ) -> Result<(), RenderError> {
-        if self.tabs.is_empty() {
+        let Mode::Hints { typed, hints } = &mut self.mode else {
             return Ok(());
+        };
+        typed.push(ch);
+        let typed = typed.clone();
+        let hints = hints.clone();
+        let mut candidates = hints.iter().filter(|hint| hint.label.starts_with(&typed));
+        match (candidates.next(), candidates.next()) {
+            (Some(hint), None) if hint.label == typed => {
+                let target = hint.target.clone();
+                self.mode = Mode::Normal;
+                self.open_pages_internal(vec![target], None, renderer, width, viewport_height)
+            }
+            (Some(_), _) => Ok(()),
+            (None, _) => {
+                self.mode = Mode::Normal;
+                self.status_message = Some(format!("No hint {typed}"));
+                Ok(())
+            }
         }
-        self.active = (self.active + 1) % self.tabs.len();
-        if let Some(page) = self.active_page_mut() {
-            page.ensure_render(renderer, width)?;
+    }
+
+    fn word_under_cursor(&self) -> Option<String> {
+        let Mode::Cursor { row, column } = self.mode else {
+            return None;
+        };
+        word_at(self.line_range(row..row + 1).first()?, column)
+    }
+
+    fn cursor_yank(&mut self) {
+        match self.word_under_cursor() {
+            Some(word) => {
+                self.status_message = Some(format!("Yanked {word}"));
+                self.effects.push(Effect::Copy(word));
+            }
+            None => self.status_message = Some("No word under the cursor".to_string()),
         }
-        self.clamp_scroll(viewport_height);
-        Ok(())
     }
 
-    fn execute_command(
+    fn cursor_open(
         &mut self,
-        command: ParsedCommand,
         renderer: &dyn ManRenderer,
         width: u16,
         viewport_height: usize,
-    ) -> Result<UpdateOutcome, RenderError> {
-        match command {
-            ParsedCommand::Man { topics, section } => {
-                self.open_pages_internal(topics, section, renderer, width, viewport_height)?;
-                Ok(UpdateOutcome::Continue)
-            }
-            ParsedCommand::Help => {
-                self.mode = Mode::Help;
-                Ok(UpdateOutcome::Continue)
-            }
-            ParsedCommand::Quit => Ok(UpdateOutcome::Quit),
-            ParsedCommand::Wipe => {
-                if self.tabs.is_empty() {
-                    return Ok(UpdateOutcome::Continue);
-                }
-                self.tabs.remove(self.active);
-                if self.tabs.is_empty() {
-                    self.active = 0;
-                    return Ok(UpdateOutcome::Continue);
-                }
-                if self.active >= self.tabs.len() {
-                    self.active = self.tabs.len() - 1;
-                }
-                if let Some(page) = self.active_page_mut() {
-                    page.ensure_render(renderer, width)?;
-                }
-                self.clamp_scroll(viewport_height);
-                Ok(UpdateOutcome::Continue)
-            }
-            ParsedCommand::Empty => Ok(UpdateOutcome::Continue),
-            ParsedCommand::Unknown(command) => {
-                self.status_message = Some(format!("Unknown command '{command}'"));
-                Ok(UpdateOutcome::Continue)
-            }
-        }
+    ) -> Result<(), RenderError> {
+        let Mode::Cursor { row, column } = self.mode else {
+            return Ok(());
+        };
+        let target = self.line_range(row..row + 1).first().and_then(|line| {
+            let offset = line
+                .char_indices()
+                .nth(column)
+                .map_or(line.len(), |(index, _)| index);
+            find_references(line)
+                .into_iter()
+                .find(|reference| (reference.start..reference.end).contains(&offset))
+                .map(|reference| reference.target())
+        });
+        let Some(target) = target else {
+            self.status_message = Some("No reference under the cursor".to_string());
+            return Ok(());
+        };
+        self.mode = Mode::Normal;
+        self.open_pages_internal(vec![target], None, renderer, width, viewport_height)
     }
 
-    fn apply_search(&mut self, line: &str, viewport_height: usize) {
-        let Some(page) = self.active_page_mut() else {
-            return;
+    fn selected_lines(&self) -> Vec<String> {
+        let Some((start, end)) = self.selection() else {
+            return Vec::new();
         };
-        let query = line.to_string();
-        let start_line = page.scroll;
-        page.update_search(Some(query), start_line);
-        if let Some(match_line) = page.current_match_line() {
-            self.center_on_line(match_line, viewport_height);
-        }
+        self.line_range(start..end + 1).into_owned()
     }
 
-    fn center_on_line(&mut self, line: usize, viewport_height: usize) {
-        let half = viewport_height / 2;
-        let max_scroll = self.max_scroll(viewport_height);
-        let desired = line.saturating_sub(half).min(max_scroll);
-        if let Some(page) = self.active_page_mut() {
-            page.scroll = desired;
+    fn visual_yank(&mut self) {
+        let selected = self.selected_lines();
+        if selected.is_empty() {
+            return;
         }
+        let count = selected.len();
+        let text = selected
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.effects.push(Effect::Copy(text));
+        self.status_message = Some(match count {
+            1 => "Yanked 1 line".to_string(),
+            _ => format!("Yanked {count} lines"),
+        });
+        self.mode = Mode::Normal;
     }
 
-    fn open_pages_internal(
+    fn visual_open(
         &mut self,
-        topics: Vec<String>,
-        section: Option<String>,
         renderer: &dyn ManRenderer,
         width: u16,
         viewport_height: usize,
     ) -> Result<(), RenderError> {
-        let mut last_error = None;
-        for topic in topics {
-            self.tabs.push(ManPage::new(topic, section.clone()));
-            self.active = self.tabs.len() - 1;
-            if let Some(page) = self.active_page_mut()
-                && let Err(err) = page.ensure_render(renderer, width)
-            {
-                self.tabs.remove(self.active);
-                if self.active >= self.tabs.len() && !self.tabs.is_empty() {
-                    self.active = self.tabs.len() - 1;
-                }
-                if let RenderError::CommandFailed(message) = err {
-                    last_error = Some(message);
-                    continue;
-                }
-                return Err(err);
+        let target = self
+            .selected_lines()
+            .iter()
+            .flat_map(|line| find_references(line))
+            .map(|reference| reference.target())
+            .next();
+        self.mode = Mode::Normal;
+        match target {
+            Some(target) => {
+                self.open_pages_internal(vec![target], None, renderer, width, viewport_height)
+            }
+            None => {
+                self.status_message = Some("No reference in selection".to_string());
+                Ok(())
             }
         }
-        if let Some(message) = last_error {
-            self.status_message = Some(message);
+    }
+
+    fn lookup_selection(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<UpdateOutcome, RenderError> {
+        let text = if matches!(self.mode, Mode::Visual { .. }) {
+            Some(self.selected_lines().join(" "))
+        } else {
+            self.text_selection
+                .take()
+                .filter(|selection| selection.tab == self.active && !selection.is_empty())
+                .map(|selection| self.selection_text(selection))
+        };
+        self.mode = Mode::Normal;
+        let Some(text) = text else {
+            self.status_message = Some("Select a word first, or use c and K".to_string());
+            return Ok(UpdateOutcome::Continue);
+        };
+        if let Some(reference) = find_references(&text).first() {
+            self.open_pages_internal(
+                vec![reference.target()],
+                None,
+                renderer,
+                width,
+                viewport_height,
+            )?;
+            return Ok(UpdateOutcome::Continue);
         }
-        if !self.tabs.is_empty() {
-            self.clamp_scroll(viewport_height);
+        let word = text
+            .char_indices()
+            .position(|(_, ch)| ch.is_alphanumeric() || ch == '_')
+            .and_then(|column| word_at(&text, column));
+        match word {
+            Some(word) => {
+                let command = ParsedCommand::Man {
+                    topics: vec![word],
+                    section: None,
+                };
+                self.execute_command(command, renderer, width, viewport_height)
+            }
+            None => {
+                self.status_message = Some("No word in the selection".to_string());
+                Ok(UpdateOutcome::Continue)
+            }
         }
-        Ok(())
     }
-}
-
-fn should_clear_status(action: &Action) -> bool {
-    !matches!(action, Action::Resize(_, _) | Action::Quit)
-}
 
-fn parse_command(line: &str) -> ParsedCommand {
-    let trimmed = line.trim();
-    if trimmed.is_empty() {
-        return ParsedCommand::Empty;
-    }
-    let mut parts = trimmed.split_whitespace();
-    let command = match parts.next() {
-        Some(value) => value,
-        None => return ParsedCommand::Empty,
-    };
-    match command {
-        "man" => {
-            let args: Vec<&str> = parts.collect();
-            match args.as_slice() {
-                [topic] => ParsedCommand::Man {
-                    topics: vec![(*topic).to_string()],
-                    section: None,
-                },
-                [_, ..] => {
-                    let interpretation = classify_args(&args).unwrap_or_else(|_| {
-                        ArgsInterpretation::Pages(
-                            args.iter().map(|value| value.to_string()).collect(),
-                        )
-                    });
-                    match interpretation {
-                        ArgsInterpretation::SectionAndPages { section, pages } => {
-                            if pages.is_empty() {
-                                ParsedCommand::Unknown(command.to_string())
-                            } else {
-                                ParsedCommand::Man {
-                                    topics: pages,
-                                    section: Some(section),
-                                }
-                            }
-                        }
-                        ArgsInterpretation::Pages(pages) => {
-                            if pages.is_empty() {
-                                ParsedCommand::Unknown(command.to_string())
-                            } else {
-                                ParsedCommand::Man {
-                                    topics: pages,
-                                    section: None,
-                                }
-                            }
-                        }
-                    }
-                }
-                _ => ParsedCommand::Unknown(command.to_string()),
+    fn picker_move(&mut self, forward: bool, viewport_height: usize) {
+        if let Mode::Picker {
+            entries,
+            selected,
+            offset,
+            ..
+        } = &mut self.mode
+        {
+            if entries.is_empty() {
+                return;
             }
+            *selected = if forward {
+                (*selected + 1) % entries.len()
+            } else {
+                (*selected + entries.len() - 1) % entries.len()
+            };
+            *offset = picker_offset(*offset, *selected, viewport_height.saturating_sub(2));
         }
-        "help" | "h" => ParsedCommand::Help,
-        "quit" | "q" => ParsedCommand::Quit,
-        "wipe" | "w" => ParsedCommand::Wipe,
-        _ => ParsedCommand::Unknown(command.to_string()),
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::cell::Cell;
-    use std::process::{Command, Stdio};
+    fn refresh_picker_preview(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) {
+        let Mode::Picker {
+            entries, selected, ..
+        } = &self.mode
+        else {
+            self.picker_preview = None;
+            self.preview_cache.clear();
+            return;
+        };
+        let Some(entry) = entries.get(*selected) else {
+            self.picker_preview = None;
+            return;
+        };
+        let key = match &entry.section {
+            Some(section) => format!("{}({section})", entry.name),
+            None => entry.name.clone(),
+        };
+        if self
+            .picker_preview
+            .as_ref()
+            .is_some_and(|(title, _)| *title == key)
+        {
+            return;
+        }
+        let open = self.tabs.iter().find(|page| {
+            page.name() == entry.name
+                && page.section() == entry.section.as_deref()
+                && page.line_count() > 0
+        });
+        let lines = match open {
+            Some(page) => page.line_range(0..viewport_height).into_owned(),
+            None => match self.preview_cache.get(&key) {
+                Some(lines) => lines.clone(),
+                None => {
+                    let preview_width = (width / 2).saturating_sub(2).max(20);
+                    let lines =
+                        match renderer.render(&entry.name, entry.section.as_deref(), preview_width)
+                        {
+                            Ok(lines) => lines.into_iter().take(viewport_height).collect(),
+                            Err(err) => vec![error_message(err)],
+                        };
+                    self.preview_cache.insert(key.clone(), lines.clone());
+                    lines
+                }
+            },
+        };
+        self.picker_preview = Some((key, lines));
+    }
 
-    fn man_available() -> bool {
-        Command::new("man")
-            .arg("-w")
-            .arg("man")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .map(|status| status.success())
-            .unwrap_or(false)
+    fn picker_submit(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        let Mode::Picker {
+            entries, selected, ..
+        } = std::mem::replace(&mut self.mode, Mode::Normal)
+        else {
+            return Ok(());
+        };
+        let Some(entry) = entries.into_iter().nth(selected) else {
+            return Ok(());
+        };
+        self.open_pages_internal(
+            vec![entry.name],
+            entry.section,
+            renderer,
+            width,
+            viewport_height,
+        )?;
+        if let Some(line) = entry.line {
+            let row = self.active_page().map_or(line, |page| page.row_for(line));
+            self.scroll_to_line(row, viewport_height);
+        }
+        Ok(())
     }
 
-    struct StubRenderer {
-        calls: Cell<usize>,
+    fn toggle_matches(&mut self) {
+        if matches!(self.mode, Mode::Matches { .. }) {
+            self.mode = Mode::Normal;
+            return;
+        }
+        let Some(page) = self.active_page() else {
+            return;
+        };
+        if page.search_matches().is_empty() {
+            self.status_message = Some("No search matches".to_string());
+            return;
+        }
+        self.mode = Mode::Matches {
+            selected: page.search_index().unwrap_or(0),
+        };
     }
 
-    impl StubRenderer {
-        fn new() -> Self {
-            Self {
-                calls: Cell::new(0),
-            }
+    fn move_match_selection(&mut self, forward: bool, viewport_height: usize) {
+        let Mode::Matches { selected } = self.mode else {
+            return;
+        };
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        let last = page.search_matches().len().saturating_sub(1);
+        let selected = if forward {
+            (selected + 1).min(last)
+        } else {
+            selected.saturating_sub(1)
+        };
+        let line = page.select_match(selected);
+        self.mode = Mode::Matches { selected };
+        if let Some(line) = line {
+            self.center_on_line(line, viewport_height);
         }
     }
 
-    impl ManRenderer for StubRenderer {
-        fn render(
-            &self,
-            name: &str,
-            _section: Option<&str>,
-            width: u16,
-        ) -> Result<Vec<String>, RenderError> {
-            let next = self.calls.get() + 1;
-            self.calls.set(next);
-            let line = format!("{name}:{width}");
-            Ok(vec![line; 50])
+    fn confirm_accept(&mut self, renderer: &dyn ManRenderer, width: u16, viewport_height: usize) {
+        let Mode::Confirm { topic, program } = std::mem::replace(&mut self.mode, Mode::Normal)
+        else {
+            return;
+        };
+        match renderer.render_help(&program, width) {
+            Ok(lines) => self.open_text(format!("{topic} --help"), None, lines, viewport_height),
+            Err(RenderError::CommandFailed(message)) => self.status_message = Some(message),
+            Err(err) => self.status_message = Some(err.to_string()),
         }
     }
 
-    struct LinesRenderer {
-        lines: Vec<String>,
+    fn confirm_reject(&mut self) {
+        if let Mode::Confirm { topic, .. } = std::mem::replace(&mut self.mode, Mode::Normal) {
+            let message = format!("No manual entry for {topic}");
+            let mut page = ManPage::new(topic, None);
+            page.set_low_memory(self.low_memory);
+            page.show_error(message.clone());
+            self.tabs.push(page);
+            self.active = self.tabs.len() - 1;
+            self.status_message = Some(message);
+        }
     }
 
-    impl LinesRenderer {
-        fn new(lines: Vec<String>) -> Self {
-            Self { lines }
+    fn retry_render(&mut self, renderer: &dyn ManRenderer, width: u16, viewport_height: usize) {
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        if page.error().is_none() {
+            return;
+        }
+        page.clear_error();
+        match page.ensure_render(renderer, width) {
+            Ok(()) => {
+                self.status_message = Some(format!("Rendered {}", self.title()));
+                self.compare_with_snapshot();
+                self.record_history();
+                self.restore_position(viewport_height);
+            }
+            Err(err) => {
+                let message = error_message(err);
+                page.show_error(message.clone());
+                self.status_message = Some(message);
+            }
         }
     }
 
-    impl ManRenderer for LinesRenderer {
-        fn render(
-            &self,
-            _name: &str,
-            _section: Option<&str>,
-            _width: u16,
-        ) -> Result<Vec<String>, RenderError> {
-            Ok(self.lines.clone())
+    fn refresh_active(&mut self, renderer: &dyn ManRenderer, width: u16, viewport_height: usize) {
+        let title = self.title();
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        if page.is_fixed() {
+            self.status_message = Some("This page cannot be refreshed".to_string());
+            return;
+        }
+        if page.error().is_some() {
+            self.retry_render(renderer, width, viewport_height);
+            return;
+        }
+        page.release_render();
+        match page.ensure_render(renderer, width) {
+            Ok(()) => {
+                self.clamp_scroll(viewport_height);
+                self.status_message = Some(format!("Refreshed {title}"));
+            }
+            Err(err) => {
+                let message = error_message(err);
+                page.show_error(message.clone());
+                self.status_message = Some(message);
+            }
+        }
+    }
+
+    fn switch_tab_left(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        if self.tabs.is_empty() {
+            return Ok(());
+        }
+        if self.active == 0 {
+            self.active = self.tabs.len() - 1;
+        } else {
+            self.active -= 1;
+        }
+        if let Some(page) = self.active_page_mut() {
+            page.ensure_render(renderer, width)?;
+        }
+        self.clamp_scroll(viewport_height);
+        Ok(())
+    }
+
+    fn switch_tab_right(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        if self.tabs.is_empty() {
+            return Ok(());
+        }
+        self.active = (self.active + 1) % self.tabs.len();
+        if let Some(page) = self.active_page_mut() {
+            page.ensure_render(renderer, width)?;
+        }
+        self.clamp_scroll(viewport_height);
+        Ok(())
+    }
+
+    fn localize_mouse(&self, action: Action, width: u16, viewport_height: usize) -> Option<Action> {
+        let (column, row) = match action {
+            Action::MousePress(column, row)
+            | Action::MouseDrag(column, row)
+            | Action::MouseRelease(column, row) => (column, row),
+            _ => return Some(action),
+        };
+        let screen_width = if self.screen_width == 0 {
+            width
+        } else {
+            self.screen_width
+        };
+        let area = self.focused_area(screen_width, viewport_height);
+        let left = area.x + self.gutter_width();
+        let top = area.y + u16::from(!self.zen);
+        let inside = (left..area.x + area.width).contains(&column)
+            && (top..top + area.height).contains(&row);
+        if !inside && matches!(action, Action::MousePress(..)) {
+            return None;
+        }
+        let column = column.saturating_sub(left);
+        let row = row.saturating_sub(top).min(area.height.saturating_sub(1));
+        Some(match action {
+            Action::MousePress(..) => Action::MousePress(column, row),
+            Action::MouseDrag(..) => Action::MouseDrag(column, row),
+            _ => Action::MouseRelease(column, row),
+        })
+    }
+
+    fn focused_area(&self, screen_width: u16, viewport_height: usize) -> PaneArea {
+        let height = u16::try_from(viewport_height).unwrap_or(u16::MAX);
+        self.layout
+            .as_ref()
+            .and_then(|layout| {
+                layout
+                    .areas(screen_width, height)
+                    .get(layout.focus())
+                    .copied()
+            })
+            .unwrap_or(PaneArea {
+                x: 0,
+                y: 0,
+                width: screen_width,
+                height,
+            })
+    }
+
+    fn gutter_width(&self) -> u16 {
+        if self.changed_lines().is_empty() {
+            0
+        } else {
+            2
+        }
+    }
+
+    fn text_position(&self, column: u16, row: u16) -> Option<TextPosition> {
+        let last = self.line_count().checked_sub(1)?;
+        let line = (self.scroll() + usize::from(row)).min(last);
+        let column = self.column() + usize::from(column);
+        let text = self.line_range(line..line + 1);
+        Some((line, char_at_column(text.first()?, column)))
+    }
+
+    fn selection_text(&self, selection: TextSelection) -> String {
+        let rows = selection.rows();
+        selection.text(&self.line_range(rows.clone()), rows.start)
+    }
+
+    fn extend_text_selection(&mut self, column: u16, row: u16) {
+        let position = self.text_position(column, row);
+        let active = self.active;
+        if let Some(position) = position
+            && let Some(selection) = self
+                .text_selection
+                .as_mut()
+                .filter(|selection| selection.tab == active)
+        {
+            selection.cursor = position;
+        }
+    }
+
+    fn copy_text_selection(&mut self) {
+        let Some(selection) = self
+            .text_selection
+            .filter(|selection| selection.tab == self.active && !selection.is_empty())
+        else {
+            self.text_selection = None;
+            return;
+        };
+        let text = self.selection_text(selection);
+        let count = text.chars().count();
+        self.effects.push(Effect::Copy(text));
+        self.status_message = Some(format!("Copied {count} characters"));
+    }
+
+    fn focus_tab(
+        &mut self,
+        index: usize,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        if index >= self.tabs.len() {
+            self.status_message = Some(format!("No tab {}", index + 1));
+            return Ok(());
+        }
+        self.active = index;
+        if let Some(page) = self.active_page_mut() {
+            page.ensure_render(renderer, width)?;
+        }
+        self.clamp_scroll(viewport_height);
+        Ok(())
+    }
+
+    fn pin_active(&mut self, pinned: bool) {
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        page.pinned = pinned;
+        let title = page_key(page);
+        let from = self.active;
+        let to = self
+            .tabs
+            .iter()
+            .enumerate()
+            .filter(|(index, page)| *index != from && page.pinned)
+            .count();
+        let page = self.tabs.remove(from);
+        self.tabs.insert(to, page);
+        self.active = to;
+        if let Some(layout) = self.layout.as_mut() {
+            layout.move_tab(from, to);
+        }
+        self.save_pins();
+        self.status_message = Some(if pinned {
+            format!("Pinned {title}")
+        } else {
+            format!("Unpinned {title}")
+        });
+    }
+
+    fn rename_active(&mut self, label: Option<String>) {
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        let title = page_key(page);
+        let message = match &label {
+            Some(label) => format!("Renamed {title} to {label}"),
+            None => format!("Restored the title of {title}"),
+        };
+        page.label = label;
+        self.status_message = Some(message);
+    }
+
+    fn wipe_unpinned(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        let before = self.tabs.len();
+        self.layout = None;
+        for index in (0..self.tabs.len()).rev() {
+            if self.tabs[index].pinned {
+                continue;
+            }
+            self.remember_position(index);
+            self.tabs.remove(index);
+            if self.active > index {
+                self.active -= 1;
+            }
+        }
+        let closed = before - self.tabs.len();
+        self.status_message = Some(match closed {
+            1 => "Closed 1 tab".to_string(),
+            closed => format!("Closed {closed} tabs"),
+        });
+        if self.tabs.is_empty() {
+            self.active = 0;
+            return Ok(());
+        }
+        self.active = self.active.min(self.tabs.len() - 1);
+        if let Some(page) = self.active_page_mut() {
+            page.ensure_render(renderer, width)?;
+        }
+        self.clamp_scroll(viewport_height);
+        Ok(())
+    }
+
+    fn save_pins(&mut self) {
+        let Some(pins) = self.pins.as_ref() else {
+            return;
+        };
+        let pages: Vec<String> = self
+            .tabs
+            .iter()
+            .chain(self.groups.iter().flat_map(|group| group.tabs.iter()))
+            .filter(|page| page.pinned && !page.is_fixed())
+            .map(page_key)
+            .collect();
+        if let Err(err) = pins.save(&pages) {
+            self.status_message = Some(format!("Could not save pinned tabs: {err}"));
+        }
+    }
+
+    pub fn open_pinned(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        let pages = self
+            .pins
+            .as_ref()
+            .map(PinnedPages::pages)
+            .unwrap_or_default();
+        for page in pages {
+            let before = self.tabs.len();
+            self.open_pages_internal(vec![page], None, renderer, width, viewport_height)?;
+            if self.tabs.len() > before
+                && let Some(page) = self.tabs.last_mut()
+            {
+                page.pinned = true;
+            }
+        }
+        Ok(())
+    }
+
+    fn group_index(&mut self, name: String) -> usize {
+        match self.groups.iter().position(|group| group.name == name) {
+            Some(index) => index,
+            None => {
+                self.groups.push(TabGroup::new(name));
+                self.groups.len() - 1
+            }
+        }
+    }
+
+    fn move_tab_to_group(
+        &mut self,
+        name: String,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        if self.tabs.is_empty() {
+            return Ok(());
+        }
+        let index = self.group_index(name);
+        if index == self.group {
+            self.status_message = Some(format!("Tab is already in group {}", self.group_name()));
+            return Ok(());
+        }
+        self.forget_tab(self.active);
+        let page = self.tabs.remove(self.active);
+        let title = page_key(&page);
+        let target = &mut self.groups[index];
+        target.tabs.push(page);
+        target.active = target.tabs.len() - 1;
+        self.status_message = Some(format!("Moved {title} to group {}", target.name));
+        if self.tabs.is_empty() {
+            self.active = 0;
+            self.layout = None;
+            return Ok(());
+        }
+        self.active = self.active.min(self.tabs.len() - 1);
+        self.sync_layout(self.active);
+        if let Some(page) = self.active_page_mut() {
+            page.ensure_render(renderer, width)?;
+        }
+        self.clamp_scroll(viewport_height);
+        Ok(())
+    }
+
+    fn toggle_tab_split(&mut self) {
+        let Some(page) = self.active_page() else {
+            return;
+        };
+        let title = page_key(page);
+        if self.layout.is_some() {
+            self.close_pane();
+            self.status_message = Some(format!("Moved {title} out of the split"));
+            return;
+        }
+        let Some(other) = (0..self.tabs.len())
+            .filter(|tab| *tab != self.active)
+            .max_by_key(|tab| self.tabs[*tab].last_active)
+        else {
+            self.status_message = Some("Open another page to split the view".to_string());
+            return;
+        };
+        let mut layout = PaneLayout::new(other);
+        layout.split(SplitDirection::Horizontal, self.active);
+        self.layout = Some(layout);
+        self.status_message = Some(format!("Moved {title} into a split"));
+    }
+
+    fn switch_group(
+        &mut self,
+        index: usize,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        if index == self.group || index >= self.groups.len() {
+            return Ok(());
+        }
+        for tab in 0..self.tabs.len() {
+            self.remember_position(tab);
+        }
+        let current = &mut self.groups[self.group];
+        current.tabs = std::mem::take(&mut self.tabs);
+        current.active = self.active;
+        current.layout = self.layout.take();
+        let target = &mut self.groups[index];
+        self.tabs = std::mem::take(&mut target.tabs);
+        self.active = target.active;
+        self.layout = target.layout.take();
+        self.group = index;
+        self.status_message = Some(format!("Group {}", self.group_name()));
+        if let Some(page) = self.active_page_mut() {
+            page.ensure_render(renderer, width)?;
+        }
+        if !self.tabs.is_empty() {
+            self.clamp_scroll(viewport_height);
+        }
+        Ok(())
+    }
+
+    fn execute_command(
+        &mut self,
+        command: ParsedCommand,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<UpdateOutcome, RenderError> {
+        match command {
+            ParsedCommand::Man { topics, section } => {
+                if let ([topic], None) = (topics.as_slice(), section.as_ref()) {
+                    let entries = renderer.lookup(topic).unwrap_or_default();
+                    if entries.len() > 1 {
+                        self.mode = Mode::Picker {
+                            title: format!("Select a section for {topic}"),
+                            entries: entries.into_iter().map(PickerEntry::from).collect(),
+                            selected: 0,
+                            offset: 0,
+                        };
+                        return Ok(UpdateOutcome::Continue);
+                    }
+                }
+                self.open_pages_internal(topics, section, renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::ManAll(topics) => {
+                self.open_every_section(topics, renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Help => {
+                self.mode = Mode::Help;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Quit => Ok(UpdateOutcome::Quit),
+            ParsedCommand::History => {
+                self.show_history();
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Wipe => {
+                if self.tabs.is_empty() {
+                    return Ok(UpdateOutcome::Continue);
+                }
+                if self.tabs[self.active].pinned {
+                    self.status_message = Some("This tab is pinned; :unpin it first".to_string());
+                    return Ok(UpdateOutcome::Continue);
+                }
+                self.remember_position(self.active);
+                self.forget_tab(self.active);
+                self.tabs.remove(self.active);
+                if self.tabs.is_empty() {
+                    self.active = 0;
+                    return Ok(UpdateOutcome::Continue);
+                }
+                if self.active >= self.tabs.len() {
+                    self.active = self.tabs.len() - 1;
+                }
+                if let Some(page) = self.active_page_mut() {
+                    page.ensure_render(renderer, width)?;
+                }
+                self.clamp_scroll(viewport_height);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::WipeAll => {
+                self.wipe_unpinned(renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Pin(pinned) => {
+                self.pin_active(pinned);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Width(page_width) => {
+                let title = self.title();
+                let Some(page) = self.active_page_mut() else {
+                    self.status_message = Some("No page to resize".to_string());
+                    return Ok(UpdateOutcome::Continue);
+                };
+                page.set_width(page_width);
+                self.status_message = Some(match page_width {
+                    Some(page_width) => format!("{title} is rendered {page_width} columns wide"),
+                    None => format!("{title} follows the window width"),
+                });
+                self.resize_active(renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Rename(label) => {
+                self.rename_active(label);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::MkSession(name) => {
+                self.save_session(&name);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Session(name) => {
+                self.open_session(&name, renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Shell(command) => {
+                match renderer.run_command(&command, width) {
+                    Ok(lines) => {
+                        self.open_text(format!("!{command}"), None, lines, viewport_height)
+                    }
+                    Err(RenderError::CommandFailed(message)) => self.status_message = Some(message),
+                    Err(err) => self.status_message = Some(err.to_string()),
+                }
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Cheat(topic) => {
+                let Some(cheats) = &self.cheats else {
+                    self.status_message = Some("cheat.sh is not available".to_string());
+                    return Ok(UpdateOutcome::Continue);
+                };
+                match cheats.render(&topic, None, width) {
+                    Ok(lines) => {
+                        self.open_text(format!("cheat.sh/{topic}"), None, lines, viewport_height)
+                    }
+                    Err(RenderError::CommandFailed(message)) => self.status_message = Some(message),
+                    Err(err) => self.status_message = Some(err.to_string()),
+                }
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Bookmark(name) => {
+                self.add_bookmark(name);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Bookmarks => {
+                self.show_bookmarks();
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Apropos(keyword) => {
+                self.show_apropos(&keyword, renderer);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Find(query) => {
+                self.find_pages(&query);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Split { direction, topics } => {
+                self.split_view(direction, topics, renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Only => {
+                self.layout = None;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Close => {
+                self.close_pane();
+                self.clamp_scroll(viewport_height);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Tab(index) => {
+                self.focus_tab(index, renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::TabNext => {
+                self.switch_tab_right(renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::TabPrev => {
+                self.switch_tab_left(renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Goto(anchor) => {
+                self.goto_anchor(&anchor, viewport_height);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Option(flag) => {
+                self.goto_option(&flag, viewport_height);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Diff(target) => {
+                self.diff_with(&target, renderer, width, viewport_height);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Export(path) => {
+                self.export_active(&path, renderer);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::External { tool, source } => {
+                self.open_external(tool, source, renderer);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::ReloadConfig => {
+                self.effects.push(Effect::ReloadConfig);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Theme(variant) => {
+                self.effects.push(Effect::SetTheme(variant));
+                self.status_message = Some(
+                    match variant {
+                        ThemeVariant::Auto => "Following the terminal background",
+                        ThemeVariant::Dark => "Using the dark theme",
+                        ThemeVariant::Light => "Using the light theme",
+                    }
+                    .to_string(),
+                );
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Matches => {
+                self.toggle_matches();
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Refresh => {
+                self.refresh_active(renderer, width, viewport_height);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Debug => {
+                self.debug_overlay = !self.debug_overlay;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Zen => {
+                self.zen = !self.zen;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Line(line) => {
+                self.go_to_line(line, viewport_height);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::ScrollLock => {
+                self.toggle_scroll_lock();
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Source => {
+                self.toggle_source(renderer);
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Group(Some(name)) => {
+                let index = self.group_index(name);
+                self.switch_group(index, renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::MoveTab(name) => {
+                self.move_tab_to_group(name, renderer, width, viewport_height)?;
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::TabToSplit => {
+                self.toggle_tab_split();
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Group(None) => {
+                let names: Vec<String> = self
+                    .groups
+                    .iter()
+                    .enumerate()
+                    .map(|(index, group)| {
+                        if index == self.group {
+                            format!("[{}]", group.name)
+                        } else {
+                            group.name.clone()
+                        }
+                    })
+                    .collect();
+                self.status_message = Some(format!("Groups: {}", names.join(" ")));
+                Ok(UpdateOutcome::Continue)
+            }
+            ParsedCommand::Empty => Ok(UpdateOutcome::Continue),
+            ParsedCommand::Unknown(command) => {
+                self.status_message = Some(format!("Unknown command '{command}'"));
+                Ok(UpdateOutcome::Continue)
+            }
+        }
+    }
+
+    fn diff_with(
+        &mut self,
+        target: &str,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) {
+        let Some(page) = self.active_page() else {
+            self.status_message = Some("No page to compare".to_string());
+            return;
+        };
+        let other = parse_page_ref(target);
+        let lines = match renderer.render(&other.name, other.section.as_deref(), width) {
+            Ok(lines) => lines,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot diff with {target}: {err}"));
+                return;
+            }
+        };
+        let title = format!("{} vs {target}", page_key(page));
+        let diff = diff_lines(&page.full_lines(), &lines);
+        let (added, removed) = diff_counts(&diff);
+        self.tabs.push(ManPage::with_diff(title, diff));
+        self.active = self.tabs.len() - 1;
+        self.clamp_scroll(viewport_height);
+        self.status_message = Some(format!("{added} lines added, {removed} removed"));
+    }
+
+    fn export_active(&mut self, path: &str, renderer: &dyn ManRenderer) {
+        if !self.has_tabs() {
+            self.status_message = Some("No page to export".to_string());
+            return;
+        }
+        let path = Path::new(path);
+        let title = self.title();
+        let contents = match typeset_device(path) {
+            Some(device) => match self.typeset_active(device, renderer) {
+                Ok(contents) => contents,
+                Err(message) => {
+                    self.status_message = Some(message);
+                    return;
+                }
+            },
+            None => export_lines(&title, &self.source_lines(), ExportFormat::from_path(path))
+                .into_bytes(),
+        };
+        self.status_message = Some(match std::fs::write(path, contents) {
+            Ok(()) => format!("Exported {title} to {}", path.display()),
+            Err(err) => format!("Export failed: {err}"),
+        });
+    }
+
+    fn typeset_active(&self, device: &str, renderer: &dyn ManRenderer) -> Result<Vec<u8>, String> {
+        let title = self.title();
+        let missing = || format!("No source file found for {title}");
+        let page = self.active_page().ok_or_else(missing)?;
+        if page.is_fixed() || page.error().is_some() {
+            return Err(missing());
+        }
+        let path = renderer
+            .locate(page.name(), page.section())
+            .map_err(|err| err.to_string())?
+            .ok_or_else(missing)?;
+        let source = read_page_source(&path).map_err(|err| err.to_string())?;
+        renderer
+            .typeset(source, device)
+            .map_err(|err| format!("Export failed: {err}"))
+    }
+
+    fn toggle_source(&mut self, renderer: &dyn ManRenderer) {
+        let title = self.title();
+        let Some(page) = self.active_page_mut() else {
+            self.status_message = Some("No page to show".to_string());
+            return;
+        };
+        if page.show_formatted() {
+            return;
+        }
+        if page.is_fixed() || page.error().is_some() {
+            self.status_message = Some(format!("No source file found for {title}"));
+            return;
+        }
+        let source = match renderer.locate(page.name(), page.section()) {
+            Ok(Some(path)) => read_page_source(&path),
+            Ok(None) => {
+                self.status_message = Some(format!("No source file found for {title}"));
+                return;
+            }
+            Err(err) => {
+                self.status_message = Some(err.to_string());
+                return;
+            }
+        };
+        match source {
+            Ok(bytes) => page.show_source(clean_formatted_text(&String::from_utf8_lossy(&bytes))),
+            Err(err) => self.status_message = Some(err.to_string()),
+        }
+    }
+
+    pub fn showing_source(&self) -> bool {
+        self.active_page().is_some_and(ManPage::is_source)
+    }
+
+    pub fn loading(&self) -> bool {
+        self.active_page().is_some_and(ManPage::is_streaming)
+    }
+
+    fn open_external(&mut self, tool: ExternalTool, source: bool, renderer: &dyn ManRenderer) {
+        let Some(page) = self.active_page() else {
+            self.status_message = Some("No page to open".to_string());
+            return;
+        };
+        if source {
+            match renderer.locate(page.name(), page.section()) {
+                Ok(Some(path)) => self.effects.push(Effect::OpenExternal {
+                    tool,
+                    path,
+                    temporary: false,
+                }),
+                Ok(None) => {
+                    self.status_message =
+                        Some(format!("No source file found for {}", self.title()));
+                }
+                Err(err) => self.status_message = Some(err.to_string()),
+            }
+            return;
+        }
+        let contents = export_lines(&self.title(), &self.source_lines(), ExportFormat::Text);
+        let written = create_temp_file(&format!("{}.txt", sanitize_file_name(&self.title())))
+            .and_then(|(path, mut file)| {
+                file.write_all(contents.as_bytes())?;
+                Ok(path)
+            });
+        match written {
+            Ok(path) => self.effects.push(Effect::OpenExternal {
+                tool,
+                path,
+                temporary: true,
+            }),
+            Err(err) => {
+                self.status_message = Some(format!("Could not write a temporary file: {err}"))
+            }
+        }
+    }
+
+    fn apply_search(&mut self, line: &str, viewport_height: usize) {
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        let query = line.to_string();
+        let start_line = page.scroll;
+        page.update_search(Some(query), start_line);
+        if let Some(match_line) = page.current_match_line() {
+            self.center_on_line(match_line, viewport_height);
+        }
+    }
+
+    fn show_history(&mut self) {
+        let entries: Vec<PickerEntry> = self
+            .history
+            .as_ref()
+            .map(History::entries)
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .map(|entry| {
+                let page = parse_page_ref(&entry.page);
+                PickerEntry {
+                    name: page.name,
+                    section: page.section,
+                    description: format_timestamp(entry.timestamp),
+                    line: None,
+                }
+            })
+            .collect();
+        if entries.is_empty() {
+            self.status_message = Some("History is empty".to_string());
+            return;
+        }
+        self.mode = Mode::Picker {
+            title: "Reopen a page from history".to_string(),
+            entries,
+            selected: 0,
+            offset: 0,
+        };
+    }
+
+    fn save_session(&mut self, name: &str) {
+        let kept: Vec<usize> = (0..self.tabs.len())
+            .filter(|index| !self.tabs[*index].is_fixed())
+            .collect();
+        if kept.is_empty() {
+            self.status_message = Some("No pages to save in a session".to_string());
+            return;
+        }
+        let session_index = |tab: usize| kept.iter().position(|kept| *kept == tab);
+        let session = Session {
+            pages: kept
+                .iter()
+                .map(|index| {
+                    let page = &self.tabs[*index];
+                    (page_key(page), page.source_line(page.scroll))
+                })
+                .collect(),
+            active: session_index(self.active).unwrap_or(0),
+            layout: self
+                .layout
+                .as_ref()
+                .and_then(|layout| PaneLayout::from_spec(&layout.spec(), session_index))
+                .map(|layout| layout.spec()),
+        };
+        let Some(sessions) = self.sessions.as_ref() else {
+            self.status_message = Some("Sessions are not available".to_string());
+            return;
+        };
+        self.status_message = Some(match sessions.save(name, &session) {
+            Ok(()) => format!("Saved session {name}"),
+            Err(err) => format!("Could not save session: {err}"),
+        });
+    }
+
+    pub fn open_session(
+        &mut self,
+        name: &str,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        let Some(session) = self
+            .sessions
+            .as_ref()
+            .and_then(|sessions| sessions.load(name))
+        else {
+            self.status_message = Some(format!("No session named {name}"));
+            return Ok(());
+        };
+        let mut opened = Vec::new();
+        for (page, line) in session.pages {
+            self.open_pages_internal(vec![page], None, renderer, width, viewport_height)?;
+            self.scroll_to_line(line, viewport_height);
+            opened.push(self.active);
+        }
+        if let Some(&active) = opened.get(session.active) {
+            self.active = active;
+        }
+        if let Some(layout) = session
+            .layout
+            .and_then(|spec| PaneLayout::from_spec(&spec, |index| opened.get(index).copied()))
+        {
+            self.active = layout.focused_tab();
+            self.layout = Some(layout);
+        }
+        Ok(())
+    }
+
+    fn add_bookmark(&mut self, name: String) {
+        let Some(page) = self.active_page() else {
+            self.status_message = Some("No page to bookmark".to_string());
+            return;
+        };
+        if page.is_fixed() {
+            self.status_message = Some("This page cannot be reopened later".to_string());
+            return;
+        }
+        let bookmark = Bookmark {
+            name,
+            page: self.title(),
+            line: page.source_line(page.scroll),
+        };
+        let Some(bookmarks) = self.bookmarks.as_ref() else {
+            self.status_message = Some("Bookmarks are not available".to_string());
+            return;
+        };
+        self.status_message = Some(match bookmarks.set(bookmark.clone()) {
+            Ok(()) => format!("Bookmarked {} as {}", bookmark.page, bookmark.name),
+            Err(err) => format!("Could not save bookmark: {err}"),
+        });
+    }
+
+    fn show_bookmarks(&mut self) {
+        let entries: Vec<PickerEntry> = self
+            .bookmarks
+            .as_ref()
+            .map(Bookmarks::list)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|bookmark| {
+                let page = parse_page_ref(&bookmark.page);
+                PickerEntry {
+                    name: page.name,
+                    section: page.section,
+                    description: format!("{} (line {})", bookmark.name, bookmark.line + 1),
+                    line: Some(bookmark.line),
+                }
+            })
+            .collect();
+        if entries.is_empty() {
+            self.status_message = Some("No bookmarks".to_string());
+            return;
+        }
+        self.mode = Mode::Picker {
+            title: "Jump to a bookmark".to_string(),
+            entries,
+            selected: 0,
+            offset: 0,
+        };
+    }
+
+    fn show_apropos(&mut self, keyword: &str, renderer: &dyn ManRenderer) {
+        let entries = match renderer.apropos(keyword) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.status_message = Some(format!("apropos failed: {}", error_message(err)));
+                return;
+            }
+        };
+        if entries.is_empty() {
+            self.status_message = Some(format!("Nothing appropriate for {keyword}"));
+            return;
+        }
+        self.mode = Mode::Picker {
+            title: format!("Pages about {keyword}"),
+            entries: entries.into_iter().map(PickerEntry::from).collect(),
+            selected: 0,
+            offset: 0,
+        };
+    }
+
+    fn find_pages(&mut self, query: &str) {
+        if self.page_index.is_empty() {
+            self.status_message = Some("The page index is not ready yet".to_string());
+            return;
+        }
+        let entries: Vec<PickerEntry> = self
+            .page_index
+            .find(query, FIND_LIMIT)
+            .into_iter()
+            .cloned()
+            .map(PickerEntry::from)
+            .collect();
+        if entries.is_empty() {
+            self.status_message = Some(format!("No pages match {query}"));
+            return;
+        }
+        self.mode = Mode::Picker {
+            title: format!("Pages matching {query}"),
+            entries,
+            selected: 0,
+            offset: 0,
+        };
+    }
+
+    fn remember_position(&mut self, index: usize) {
+        let Some(page) = self.tabs.get(index) else {
+            return;
+        };
+        if page.is_fixed() || page.error().is_some() {
+            return;
+        }
+        let key = page_key(page);
+        let scroll = page.source_line(page.scroll);
+        if let Some(positions) = self.positions.as_mut() {
+            positions.set(&key, scroll);
+        }
+    }
+
+    fn restore_position(&mut self, viewport_height: usize) {
+        let Some(line) = self
+            .positions
+            .as_ref()
+            .and_then(|positions| positions.get(&self.title()))
+        else {
+            return;
+        };
+        self.scroll_to_line(line, viewport_height);
+    }
+
+    fn record_history(&mut self) {
+        let page = self.title();
+        let Some(history) = self.history.as_ref() else {
+            return;
+        };
+        if let Err(err) = history.record(&page) {
+            self.status_message = Some(format!("Could not record history: {err}"));
+        }
+    }
+
+    fn compare_with_snapshot(&mut self) {
+        let key = self.title();
+        let Some(store) = self.snapshots.as_ref() else {
+            return;
+        };
+        let Some(page) = self.tabs.get_mut(self.active) else {
+            return;
+        };
+        if page.is_fixed() {
+            return;
+        }
+        if let Some(previous) = store.load(&key) {
+            page.set_baseline(&previous);
+        }
+        if let Err(err) = store.save(&key, &page.source_lines()) {
+            self.status_message = Some(format!("Could not save snapshot: {err}"));
+        }
+    }
+
+    fn shared_page_index(&self, topic: &str, section: Option<&str>) -> Option<usize> {
+        self.tabs.iter().position(|page| {
+            page.name() != topic
+                && (section.is_none() || page.section() == section)
+                && page.aliases().iter().skip(1).any(|alias| alias == topic)
+        })
+    }
+
+    fn jump_to_alias(&mut self, topic: &str, viewport_height: usize) {
+        let Some(page) = self.active_page() else {
+            return;
+        };
+        let aliases = page.aliases();
+        let Some(primary) = aliases.first() else {
+            return;
+        };
+        if primary == topic || !aliases.iter().any(|alias| alias == topic) {
+            return;
+        }
+        let message = format!("{topic} is documented in {primary}");
+        if let Some(line) = page.alias_line(topic) {
+            self.scroll_to_line(line, viewport_height);
+        }
+        self.status_message = Some(message);
+    }
+
+    fn goto_anchor(&mut self, anchor: &str, viewport_height: usize) {
+        let Some(page) = self.active_page() else {
+            return;
+        };
+        match page.anchor_line(anchor) {
+            Some(line) => self.scroll_to_line(line, viewport_height),
+            None => {
+                self.status_message = Some(format!("No anchor '{anchor}' in {}", self.title()));
+            }
+        }
+    }
+
+    fn goto_option(&mut self, flag: &str, viewport_height: usize) {
+        let Some(page) = self.active_page() else {
+            return;
+        };
+        match page.option_line(flag) {
+            Some(line) => self.center_on_line(line, viewport_height),
+            None => {
+                self.status_message = Some(format!("No option '{flag}' in {}", self.title()));
+            }
+        }
+    }
+
+    fn toggle_fold(&mut self, viewport_height: usize) {
+        let Some(page) = self.active_page_mut() else {
+            return;
+        };
+        if page.toggle_fold().is_none() {
+            self.status_message = Some("No section to fold here".to_string());
+        }
+        self.clamp_scroll(viewport_height);
+    }
+
+    fn jump_between(
+        &mut self,
+        targets: Option<Vec<usize>>,
+        forward: bool,
+        kind: &str,
+        viewport_height: usize,
+    ) {
+        let (Some(targets), Some(current)) = (targets, self.active_page().map(|page| page.scroll))
+        else {
+            return;
+        };
+        let max_scroll = self.max_scroll(viewport_height);
+        let target = if forward {
+            targets
+                .into_iter()
+                .find(|line| *line > current)
+                .filter(|_| current < max_scroll)
+        } else {
+            targets.into_iter().rev().find(|line| *line < current)
+        };
+        match target {
+            Some(line) => self.scroll_to_line(line, viewport_height),
+            None => self.status_message = Some(format!("No more {kind}")),
+        }
+    }
+
+    fn scroll_to_line(&mut self, line: usize, viewport_height: usize) {
+        let max_scroll = self.max_scroll(viewport_height);
+        if let Some(page) = self.active_page_mut() {
+            page.scroll = line.min(max_scroll);
+        }
+    }
+
+    fn center_on_line(&mut self, line: usize, viewport_height: usize) {
+        let half = viewport_height / 2;
+        let max_scroll = self.max_scroll(viewport_height);
+        let desired = line.saturating_sub(half).min(max_scroll);
+        let area = self.focused_area(self.screen_width, viewport_height);
+        let text_width = usize::from(area.width.saturating_sub(self.gutter_width()));
+        if let Some(page) = self.active_page_mut() {
+            page.scroll = desired;
+            page.reveal_match(line, text_width);
+        }
+    }
+
+    fn open_pages_internal(
+        &mut self,
+        topics: Vec<String>,
+        section: Option<String>,
+        renderer: &dyn ManRenderer,
+        width: u16,
+        viewport_height: usize,
+    ) -> Result<(), RenderError> {
+        let mut last_error = None;
+        for topic in topics {
+            let PageRef {
+                name: topic,
+                section: topic_section,
+                anchor,
+            } = parse_page_ref(&topic);
+            let section = topic_section.or_else(|| section.clone());
+            if let Some(index) = self.shared_page_index(&topic, section.as_deref()) {
+                self.active = index;
+                if let Some(page) = self.active_page_mut() {
+                    page.ensure_render(renderer, width)?;
+                }
+                self.jump_to_alias(&topic, viewport_height);
+                if let Some(anchor) = anchor {
+                    self.goto_anchor(&anchor, viewport_height);
+                }
+                continue;
+            }
+            let mut page = ManPage::new(topic.clone(), section.clone());
+            page.set_low_memory(self.low_memory);
+            self.tabs.push(page);
+            self.active = self.tabs.len() - 1;
+            let rendered = match self.active_page_mut() {
+                Some(page) => page.ensure_render(renderer, width),
+                None => Ok(()),
+            };
+            if let Err(err) = rendered {
+                tracing::warn!(topic, section, "render failed: {err}");
+                if matches!(err, RenderError::CommandFailed(_))
+                    && section.is_none()
+                    && self.mode == Mode::Normal
+                    && let Some(program) = renderer.help_command(&topic)
+                {
+                    self.tabs.remove(self.active);
+                    if self.active >= self.tabs.len() && !self.tabs.is_empty() {
+                        self.active = self.tabs.len() - 1;
+                    }
+                    self.mode = Mode::Confirm { topic, program };
+                    continue;
+                }
+                let message = error_message(err);
+                if let Some(page) = self.active_page_mut() {
+                    page.show_error(message.clone());
+                }
+                last_error = Some(match self.page_index.suggest(&topic) {
+                    Some(name) => format!("{message}. Did you mean {name}?"),
+                    None => message,
+                });
+                continue;
+            }
+            self.compare_with_snapshot();
+            self.record_history();
+            self.restore_position(viewport_height);
+            self.jump_to_alias(&topic, viewport_height);
+            if let Some(anchor) = anchor {
+                self.goto_anchor(&anchor, viewport_height);
+            }
+        }
+        if let Some(message) = last_error {
+            self.status_message = Some(message);
+        }
+        if !self.tabs.is_empty() {
+            self.clamp_scroll(viewport_height);
+        }
+        Ok(())
+    }
+}
+
+fn error_message(err: RenderError) -> String {
+    match err {
+        RenderError::CommandFailed(message) => message,
+        err => err.to_string(),
+    }
+}
+
+fn is_watched_page(page: &ManPage) -> bool {
+    !page.is_fixed() && is_local_page(page.name())
+}
+
+fn page_key(page: &ManPage) -> String {
+    match page.section() {
+        Some(section) => format!("{}({})", page.name(), section),
+        None => page.name().to_string(),
+    }
+}
+
+pub fn picker_offset(offset: usize, selected: usize, visible: usize) -> usize {
+    offset.clamp(selected.saturating_sub(visible.max(1) - 1), selected)
+}
+
+fn sanitize_file_name(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect()
+}
+
+fn should_clear_status(action: &Action) -> bool {
+    !matches!(action, Action::Resize(_, _) | Action::Quit)
+}
+
+fn typeset_device(path: &Path) -> Option<&'static str> {
+    match path
+        .extension()
+        .and_then(|value| value.to_str())
+        .map(|value| value.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("pdf") => Some("pdf"),
+        Some("ps") => Some("ps"),
+        _ => None,
+    }
+}
+
+fn parse_command(line: &str) -> ParsedCommand {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return ParsedCommand::Empty;
+    }
+    if let Some(shell) = trimmed.strip_prefix('!') {
+        let shell = shell.trim();
+        return if shell.is_empty() {
+            ParsedCommand::Unknown("!".to_string())
+        } else {
+            ParsedCommand::Shell(shell.to_string())
+        };
+    }
+    if let Ok(line) = trimmed.parse::<usize>() {
+        return ParsedCommand::Line(line);
+    }
+    let mut parts = trimmed.split_whitespace();
+    let command = match parts.next() {
+        Some(value) => value,
+        None => return ParsedCommand::Empty,
+    };
+    match command {
+        "man" => {
+            let args: Vec<&str> = parts.collect();
+            match args.as_slice() {
+                ["-a", topics @ ..] if !topics.is_empty() => {
+                    ParsedCommand::ManAll(topics.iter().map(|topic| topic.to_string()).collect())
+                }
+                [topic] => ParsedCommand::Man {
+                    topics: vec![(*topic).to_string()],
+                    section: None,
+                },
+                [_, ..] => {
+                    let interpretation = classify_args(&args).unwrap_or_else(|_| {
+                        ArgsInterpretation::Pages(
+                            args.iter().map(|value| value.to_string()).collect(),
+                        )
+                    });
+                    match interpretation {
+                        ArgsInterpretation::SectionAndPages { section, pages } => {
+                            if pages.is_empty() {
+                                ParsedCommand::Unknown(command.to_string())
+                            } else {
+                                ParsedCommand::Man {
+                                    topics: pages,
+                                    section: Some(section),
+                                }
+                            }
+                        }
+                        ArgsInterpretation::Pages(pages) => {
+                            if pages.is_empty() {
+                                ParsedCommand::Unknown(command.to_string())
+                            } else {
+                                ParsedCommand::Man {
+                                    topics: pages,
+                                    section: None,
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => ParsedCommand::Unknown(command.to_string()),
+            }
+        }
+        "help" | "h" => ParsedCommand::Help,
+        "quit" | "q" => ParsedCommand::Quit,
+        "wipe" | "w" => match parts.next() {
+            Some("all") => ParsedCommand::WipeAll,
+            _ => ParsedCommand::Wipe,
+        },
+        "pin" => ParsedCommand::Pin(true),
+        "unpin" => ParsedCommand::Pin(false),
+        "width" => match parts.next() {
+            None | Some("auto") => ParsedCommand::Width(None),
+            Some(value) => match value.parse::<u16>() {
+                Ok(width) if width >= MIN_CONTENT_WIDTH && parts.next().is_none() => {
+                    ParsedCommand::Width(Some(width))
+                }
+                _ => ParsedCommand::Unknown(command.to_string()),
+            },
+        },
+        "rename" => {
+            let title = trimmed[command.len()..].trim();
+            ParsedCommand::Rename((!title.is_empty()).then(|| title.to_string()))
+        }
+        "history" => ParsedCommand::History,
+        "mksession" | "session" => {
+            let name = trimmed[command.len()..].trim();
+            if name.is_empty() {
+                ParsedCommand::Unknown(command.to_string())
+            } else if command == "mksession" {
+                ParsedCommand::MkSession(name.to_string())
+            } else {
+                ParsedCommand::Session(name.to_string())
+            }
+        }
+        "cheat" => {
+            let topic = trimmed[command.len()..].trim();
+            if topic.is_empty() {
+                ParsedCommand::Unknown(command.to_string())
+            } else {
+                ParsedCommand::Cheat(topic.to_string())
+            }
+        }
+        "bookmark" => {
+            let name = trimmed[command.len()..].trim();
+            if name.is_empty() {
+                ParsedCommand::Unknown(command.to_string())
+            } else {
+                ParsedCommand::Bookmark(name.to_string())
+            }
+        }
+        "bookmarks" => ParsedCommand::Bookmarks,
+        "apropos" => {
+            let keyword = trimmed[command.len()..].trim();
+            if keyword.is_empty() {
+                ParsedCommand::Unknown(command.to_string())
+            } else {
+                ParsedCommand::Apropos(keyword.to_string())
+            }
+        }
+        "split" | "vsplit" | "vs" => ParsedCommand::Split {
+            direction: SplitDirection::Horizontal,
+            topics: parts.map(|part| part.to_string()).collect(),
+        },
+        "hsplit" | "sp" => ParsedCommand::Split {
+            direction: SplitDirection::Vertical,
+            topics: parts.map(|part| part.to_string()).collect(),
+        },
+        "only" => ParsedCommand::Only,
+        "close" => ParsedCommand::Close,
+        "tab" => match trimmed[command.len()..].trim().parse::<usize>() {
+            Ok(number) if number > 0 => ParsedCommand::Tab(number - 1),
+            _ => ParsedCommand::Unknown(command.to_string()),
+        },
+        "tabn" | "tabnext" => ParsedCommand::TabNext,
+        "tabp" | "tabprevious" => ParsedCommand::TabPrev,
+        "goto" => {
+            let anchor = trimmed[command.len()..].trim();
+            if anchor.is_empty() {
+                ParsedCommand::Unknown(command.to_string())
+            } else {
+                ParsedCommand::Goto(anchor.to_string())
+            }
+        }
+        "edit" | "pager" => {
+            let tool = if command == "edit" {
+                ExternalTool::Editor
+            } else {
+                ExternalTool::Pager
+            };
+            match parts.next() {
+                None => ParsedCommand::External {
+                    tool,
+                    source: false,
+                },
+                Some("source") => ParsedCommand::External { tool, source: true },
+                Some(_) => ParsedCommand::Unknown(command.to_string()),
+            }
+        }
+        "option" | "opt" => match (parts.next(), parts.next()) {
+            (Some(flag), None) => ParsedCommand::Option(flag.to_string()),
+            _ => ParsedCommand::Unknown(command.to_string()),
+        },
+        "diff" => {
+            let target = trimmed[command.len()..].trim();
+            if target.is_empty() {
+                ParsedCommand::Unknown(command.to_string())
+            } else {
+                ParsedCommand::Diff(target.to_string())
+            }
+        }
+        "reload-config" => ParsedCommand::ReloadConfig,
+        "theme" => match (parts.next(), parts.next()) {
+            (Some("auto"), None) => ParsedCommand::Theme(ThemeVariant::Auto),
+            (Some("dark"), None) => ParsedCommand::Theme(ThemeVariant::Dark),
+            (Some("light"), None) => ParsedCommand::Theme(ThemeVariant::Light),
+            _ => ParsedCommand::Unknown(command.to_string()),
+        },
+        "refresh" => ParsedCommand::Refresh,
+        "matches" => ParsedCommand::Matches,
+        "find" => {
+            let query = trimmed[command.len()..].trim();
+            if query.is_empty() {
+                ParsedCommand::Unknown(command.to_string())
+            } else {
+                ParsedCommand::Find(query.to_string())
+            }
+        }
+        "debug" => ParsedCommand::Debug,
+        "zen" => ParsedCommand::Zen,
+        "scrollbind" | "scb" => ParsedCommand::ScrollLock,
+        "source" => ParsedCommand::Source,
+        "group" => {
+            let name = trimmed[command.len()..].trim();
+            ParsedCommand::Group((!name.is_empty()).then(|| name.to_string()))
+        }
+        "movetab" => {
+            let name = trimmed[command.len()..].trim();
+            if name.is_empty() {
+                ParsedCommand::Unknown(command.to_string())
+            } else {
+                ParsedCommand::MoveTab(name.to_string())
+            }
+        }
+        "tab-to-split" => ParsedCommand::TabToSplit,
+        "export" => {
+            let path = trimmed[command.len()..].trim();
+            if path.is_empty() {
+                ParsedCommand::Unknown(command.to_string())
+            } else {
+                ParsedCommand::Export(path.to_string())
+            }
+        }
+        _ => ParsedCommand::Unknown(command.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use render::RenderChunk;
+    use std::cell::{Cell, RefCell};
+    use std::fs;
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc::{self, Receiver};
+    use store::TempDir;
+
+    fn man_available() -> bool {
+        Command::new("man")
+            .arg("-w")
+            .arg("man")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    struct StubRenderer {
+        calls: Cell<usize>,
+    }
+
+    impl StubRenderer {
+        fn new() -> Self {
+            Self {
+                calls: Cell::new(0),
+            }
+        }
+    }
+
+    impl ManRenderer for StubRenderer {
+        fn render(
+            &self,
+            name: &str,
+            _section: Option<&str>,
+            width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            let next = self.calls.get() + 1;
+            self.calls.set(next);
+            let line = format!("{name}:{width}");
+            Ok(vec![line; 50])
+        }
+    }
+
+    struct LinesRenderer {
+        lines: Vec<String>,
+    }
+
+    impl LinesRenderer {
+        fn new(lines: Vec<String>) -> Self {
+            Self { lines }
+        }
+    }
+
+    impl ManRenderer for LinesRenderer {
+        fn render(
+            &self,
+            _name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Ok(self.lines.clone())
+        }
+    }
+
+    struct SourceRenderer {
+        lines: Vec<String>,
+        source: PathBuf,
+    }
+
+    impl ManRenderer for SourceRenderer {
+        fn render(
+            &self,
+            _name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Ok(self.lines.clone())
+        }
+
+        fn locate(
+            &self,
+            _name: &str,
+            _section: Option<&str>,
+        ) -> Result<Option<PathBuf>, RenderError> {
+            Ok(Some(self.source.clone()))
+        }
+
+        fn typeset(&self, source: Vec<u8>, device: &str) -> Result<Vec<u8>, RenderError> {
+            let mut output = format!("%{device}\n").into_bytes();
+            output.extend(source);
+            Ok(output)
+        }
+    }
+
+    struct FailingRenderer;
+
+    impl ManRenderer for FailingRenderer {
+        fn render(
+            &self,
+            _name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Err(RenderError::CommandFailed(
+                "No manual entry for seek".to_string(),
+            ))
+        }
+    }
+
+    struct StreamingRenderer {
+        stream: RefCell<Option<Receiver<RenderChunk>>>,
+    }
+
+    impl ManRenderer for StreamingRenderer {
+        fn render(
+            &self,
+            _name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Ok(Vec::new())
+        }
+
+        fn render_stream(
+            &self,
+            _name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Option<Receiver<RenderChunk>>, RenderError> {
+            Ok(self.stream.borrow_mut().take())
+        }
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            parse_command("man ls"),
+            ParsedCommand::Man {
+                topics: vec!["ls".to_string()],
+                section: None,
+            }
+        );
+        if man_available() {
+            assert_eq!(
+                parse_command("man 2 read"),
+                ParsedCommand::Man {
+                    topics: vec!["read".to_string()],
+                    section: Some("2".to_string()),
+                }
+            );
+        }
+        assert_eq!(parse_command("quit"), ParsedCommand::Quit);
+        assert_eq!(parse_command("q"), ParsedCommand::Quit);
+        assert_eq!(parse_command("wipe"), ParsedCommand::Wipe);
+        assert_eq!(parse_command("w"), ParsedCommand::Wipe);
+        assert_eq!(parse_command("help"), ParsedCommand::Help);
+        assert_eq!(parse_command("h"), ParsedCommand::Help);
+        assert_eq!(
+            parse_command("edit source"),
+            ParsedCommand::External {
+                tool: ExternalTool::Editor,
+                source: true,
+            }
+        );
+        assert_eq!(
+            parse_command("pager"),
+            ParsedCommand::External {
+                tool: ExternalTool::Pager,
+                source: false,
+            }
+        );
+        assert_eq!(
+            parse_command("goto RETURN VALUE"),
+            ParsedCommand::Goto("RETURN VALUE".to_string())
+        );
+        assert_eq!(
+            parse_command("export /tmp/my page.md"),
+            ParsedCommand::Export("/tmp/my page.md".to_string())
+        );
+        assert_eq!(
+            parse_command("export"),
+            ParsedCommand::Unknown("export".to_string())
+        );
+        assert_eq!(
+            parse_command("opt --recursive"),
+            ParsedCommand::Option("--recursive".to_string())
+        );
+        assert_eq!(
+            parse_command("option"),
+            ParsedCommand::Unknown("option".to_string())
+        );
+        assert_eq!(
+            parse_command("diff open(3p)"),
+            ParsedCommand::Diff("open(3p)".to_string())
+        );
+        assert_eq!(parse_command("reload-config"), ParsedCommand::ReloadConfig);
+        assert_eq!(
+            parse_command("movetab syscalls"),
+            ParsedCommand::MoveTab("syscalls".to_string())
+        );
+        assert_eq!(
+            parse_command("movetab"),
+            ParsedCommand::Unknown("movetab".to_string())
+        );
+        assert_eq!(parse_command("tab-to-split"), ParsedCommand::TabToSplit);
+        assert_eq!(parse_command("debug"), ParsedCommand::Debug);
+        assert_eq!(parse_command("refresh"), ParsedCommand::Refresh);
+        assert_eq!(parse_command("tab 3"), ParsedCommand::Tab(2));
+        assert_eq!(
+            parse_command("tab 0"),
+            ParsedCommand::Unknown("tab".to_string())
+        );
+        assert_eq!(parse_command(" 42 "), ParsedCommand::Line(42));
+        assert_eq!(parse_command("tabn"), ParsedCommand::TabNext);
+        assert_eq!(parse_command("tabp"), ParsedCommand::TabPrev);
+        assert_eq!(parse_command(""), ParsedCommand::Empty);
+        assert_eq!(
+            parse_command("bogus"),
+            ParsedCommand::Unknown("bogus".to_string())
+        );
+    }
+
+    #[test]
+    fn switches_tabs_and_preserves_scroll() {
+        let renderer = StubRenderer::new();
+        let mut app = App::new("open", None);
+        let width: u16 = 80;
+        let height: usize = 10;
+        app.update(
+            Action::Resize(width, height as u16),
+            &renderer,
+            width,
+            height,
+        )
+        .unwrap();
+        app.update(Action::ScrollDown(3), &renderer, width, height)
+            .unwrap();
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "man ls".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.tabs.len(), 2);
+        assert_eq!(app.active, 1);
+        assert_eq!(app.scroll(), 0);
+
+        app.update(Action::TabLeft, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.active, 0);
+        assert_eq!(app.scroll(), 3);
+
+        app.update(Action::TabRight, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.active, 1);
+
+        app.update(Action::FocusTab(0), &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.active, 0);
+        app.update(Action::FocusTab(5), &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.active, 0);
+        assert_eq!(app.status_message(), Some("No tab 6"));
+    }
+
+    #[test]
+    fn search_centers_and_navigates() {
+        let mut lines = Vec::new();
+        for idx in 0..40 {
+            if idx == 10 || idx == 30 {
+                lines.push(format!("foo line {idx}"));
+            } else {
+                lines.push(format!("line {idx}"));
+            }
+        }
+        let renderer = LinesRenderer::new(lines);
+        let mut app = App::new("open", None);
+        let width: u16 = 80;
+        let height: usize = 10;
+        app.update(
+            Action::Resize(width, height as u16),
+            &renderer,
+            width,
+            height,
+        )
+        .unwrap();
+
+        app.update(Action::EnterSearchMode, &renderer, width, height)
+            .unwrap();
+        for ch in "foo".chars() {
+            app.update(Action::SearchChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::SearchSubmit, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.scroll(), 5);
+
+        app.update(Action::SearchNext, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.scroll(), 25);
+
+        app.update(Action::SearchNext, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.scroll(), 5);
+        assert_eq!(app.status_message(), Some("Search wrapped to top"));
+
+        app.set_search_wrap(false);
+        app.update(Action::SearchPrev, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.scroll(), 5);
+        assert_eq!(app.status_message(), Some("Search hit top"));
+
+        app.update(Action::SearchClear, &renderer, width, height)
+            .unwrap();
+        let scroll = app.scroll();
+        app.update(Action::SearchNext, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.scroll(), scroll);
+    }
+
+    #[test]
+    fn search_scrolls_horizontally_to_reveal_match() {
+        let mut lines: Vec<String> = (0..20).map(|idx| format!("line {idx}")).collect();
+        lines[3] = format!("{}needle", " ".repeat(70));
+        lines[12] = "needle".to_string();
+        let renderer = LinesRenderer::new(lines);
+        let mut app = App::new("open", None);
+        let height: usize = 10;
+        app.update(Action::Resize(40, height as u16), &renderer, 80, height)
+            .unwrap();
+        app.update(Action::EnterSearchMode, &renderer, 80, height)
+            .unwrap();
+        for ch in "needle".chars() {
+            app.update(Action::SearchChar(ch), &renderer, 80, height)
+                .unwrap();
+        }
+        app.update(Action::SearchSubmit, &renderer, 80, height)
+            .unwrap();
+        assert_eq!(app.column(), 50);
+
+        app.update(Action::SearchNext, &renderer, 80, height)
+            .unwrap();
+        assert_eq!(app.column(), 0);
+    }
+
+    #[test]
+    fn scrolls_sideways_up_to_the_widest_line() {
+        let mut lines: Vec<String> = (0..5).map(|idx| format!("row {idx}")).collect();
+        lines[2] = format!("| {} |", "cell ".repeat(10));
+        let renderer = LinesRenderer::new(lines);
+        let mut app = App::new("ascii", None);
+        app.update(Action::Resize(40, 10), &renderer, 40, 10)
+            .unwrap();
+        app.update(Action::ScrollRight(8), &renderer, 40, 10)
+            .unwrap();
+        assert_eq!(app.column(), 8);
+        app.update(Action::ScrollRight(8), &renderer, 40, 10)
+            .unwrap();
+        assert_eq!(app.column(), 14);
+        app.update(Action::ScrollLeft(20), &renderer, 40, 10)
+            .unwrap();
+        assert_eq!(app.column(), 0);
+    }
+
+    #[test]
+    fn search_history_persists_and_recalls_queries() {
+        let dir = TempDir::new("searches").expect("temp dir");
+        let searches = SearchHistory::new(dir.join("searches"));
+        searches.record("EINTR").expect("record");
+        let renderer = LinesRenderer::new(vec!["EINTR errno".to_string(); 3]);
+        let mut app = App::new("open", None);
+        app.track_searches(searches.clone());
+        let mut send = |action| app.update(action, &renderer, 80, 10).unwrap();
+        send(Action::EnterSearchMode);
+        send(Action::SearchChar('e'));
+        send(Action::SearchChar('r'));
+        send(Action::SearchBackspace);
+        send(Action::SearchBackspace);
+        for ch in "errno".chars() {
+            send(Action::SearchChar(ch));
+        }
+        send(Action::SearchSubmit);
+        assert_eq!(searches.queries(), vec!["EINTR", "errno"]);
+
+        send(Action::EnterSearchMode);
+        send(Action::SearchChar('x'));
+        send(Action::SearchHistoryPrev);
+        send(Action::SearchHistoryPrev);
+        send(Action::SearchHistoryPrev);
+        assert!(matches!(app.mode(), Mode::Search { line, .. } if line == "EINTR"));
+        assert_eq!(app.search_query(), Some("EINTR"));
+        let mut send = |action| app.update(action, &renderer, 80, 10).unwrap();
+        send(Action::SearchHistoryNext);
+        send(Action::SearchHistoryNext);
+        assert!(matches!(app.mode(), Mode::Search { line, .. } if line == "x"));
+    }
+
+    #[test]
+    fn jumps_to_numbered_lines() {
+        let renderer = StubRenderer::new();
+        let mut app = App::new("ls", None);
+        app.resize_active(&renderer, 80, 10).unwrap();
+        let send = |app: &mut App, action| app.update(action, &renderer, 80, 10).unwrap();
+        send(&mut app, Action::CountDigit(1));
+        send(&mut app, Action::CountDigit(2));
+        assert_eq!(app.mode(), &Mode::Count { count: 12 });
+        send(&mut app, Action::GoToLine(12));
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.scroll(), 11);
+        send(&mut app, Action::CountDigit(3));
+        send(&mut app, Action::ScrollDown(1));
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.scroll(), 12);
+
+        app.execute_command(parse_command("1000"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.scroll(), 40);
+        app.execute_command(parse_command("0"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.scroll(), 0);
+    }
+
+    #[test]
+    fn repeats_the_last_command_and_search() {
+        let renderer = StubRenderer::new();
+        let mut app = App::new("ls", None);
+        app.resize_active(&renderer, 80, 10).unwrap();
+        let send = |app: &mut App, action| app.update(action, &renderer, 80, 10).unwrap();
+        send(&mut app, Action::RepeatCommand);
+        assert_eq!(app.status_message(), Some("No command to repeat"));
+        send(&mut app, Action::RepeatSearch);
+        assert_eq!(app.status_message(), Some("No search to repeat"));
+
+        send(&mut app, Action::EnterCommandMode);
+        for ch in " man cp ".chars() {
+            send(&mut app, Action::CommandChar(ch));
+        }
+        send(&mut app, Action::CommandSubmit);
+        send(&mut app, Action::EnterCommandMode);
+        for ch in "bogus".chars() {
+            send(&mut app, Action::CommandChar(ch));
+        }
+        send(&mut app, Action::CommandSubmit);
+        send(&mut app, Action::TabLeft);
+        send(&mut app, Action::RepeatCommand);
+        assert_eq!(app.title(), "cp");
+        assert_eq!(app.tabs().len(), 3);
+
+        send(&mut app, Action::EnterSearchMode);
+        for ch in "cp".chars() {
+            send(&mut app, Action::SearchChar(ch));
+        }
+        send(&mut app, Action::SearchSubmit);
+        send(&mut app, Action::SearchClear);
+        assert_eq!(app.search_query(), None);
+        send(&mut app, Action::FocusTab(0));
+        send(&mut app, Action::RepeatSearch);
+        assert_eq!(app.title(), "ls");
+        assert_eq!(app.search_query(), Some("cp"));
+    }
+
+    #[test]
+    fn tab_groups_keep_their_own_tabs() {
+        let renderer = StubRenderer::new();
+        let width: u16 = 80;
+        let height: usize = 10;
+        let mut app = App::new("ls", None);
+        app.resize_active(&renderer, width, height).unwrap();
+        let command = |app: &mut App, line: &str| {
+            app.update(Action::EnterCommandMode, &renderer, width, height)
+                .unwrap();
+            for ch in line.chars() {
+                app.update(Action::CommandChar(ch), &renderer, width, height)
+                    .unwrap();
+            }
+            app.update(Action::CommandSubmit, &renderer, width, height)
+                .unwrap();
+        };
+        command(&mut app, "group syscalls");
+        assert_eq!(app.group_name(), "syscalls");
+        assert!(app.tabs.is_empty());
+        command(&mut app, "man read write");
+        app.update(Action::TabLeft, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.title(), "read");
+
+        app.update(Action::NextGroup, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.group_name(), "main");
+        assert_eq!(app.title(), "ls");
+        assert_eq!(app.tabs.len(), 1);
+        app.update(Action::PrevGroup, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.title(), "read");
+        assert_eq!(app.tabs.len(), 2);
+        command(&mut app, "group");
+        assert_eq!(app.status_message(), Some("Groups: main [syscalls]"));
+    }
+
+    #[test]
+    fn pinned_tabs_move_to_the_front_and_survive_wipes() {
+        let renderer = StubRenderer::new();
+        let width: u16 = 80;
+        let height: usize = 10;
+        let dir = TempDir::new("pins").expect("temp dir");
+        let path = dir.join("pinned");
+        let mut app = App::empty();
+        app.track_pins(PinnedPages::new(&path));
+        app.open_pages(
+            vec!["ls".to_string(), "errno(3)".to_string(), "cp".to_string()],
+            None,
+            &renderer,
+            width,
+            height,
+        )
+        .unwrap();
+        let command = |app: &mut App, line: &str| {
+            app.update(Action::EnterCommandMode, &renderer, width, height)
+                .unwrap();
+            for ch in line.chars() {
+                app.update(Action::CommandChar(ch), &renderer, width, height)
+                    .unwrap();
+            }
+            app.update(Action::CommandSubmit, &renderer, width, height)
+                .unwrap();
+        };
+        app.update(Action::TabLeft, &renderer, width, height)
+            .unwrap();
+        command(&mut app, "pin");
+        assert_eq!(app.active_index(), 0);
+        assert_eq!(app.title(), "errno(3)");
+        assert_eq!(PinnedPages::new(&path).pages(), vec!["errno(3)"]);
+
+        command(&mut app, "wipe");
+        assert_eq!(app.tabs.len(), 3);
+        assert_eq!(
+            app.status_message(),
+            Some("This tab is pinned; :unpin it first")
+        );
+        command(&mut app, "wipe all");
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(app.status_message(), Some("Closed 2 tabs"));
+
+        let mut restored = App::empty();
+        restored.track_pins(PinnedPages::new(&path));
+        restored.open_pinned(&renderer, width, height).unwrap();
+        assert_eq!(restored.title(), "errno(3)");
+        assert!(restored.tabs[0].pinned);
+    }
+
+    #[test]
+    fn named_sessions_restore_tabs_layout_and_positions() {
+        let lines: Vec<String> = (0..50).map(|idx| format!("line {idx}")).collect();
+        let renderer = LinesRenderer::new(lines);
+        let width: u16 = 80;
+        let height: usize = 10;
+        let dir = TempDir::new("sessions").expect("temp dir");
+        let mut app = App::empty();
+        app.track_sessions(SessionStore::new(dir.path()));
+        app.open_pages(
+            vec!["ls".to_string(), "errno(3)".to_string(), "cp".to_string()],
+            None,
+            &renderer,
+            width,
+            height,
+        )
+        .unwrap();
+        let command = |app: &mut App, line: &str| {
+            app.update(Action::EnterCommandMode, &renderer, width, height)
+                .unwrap();
+            for ch in line.chars() {
+                app.update(Action::CommandChar(ch), &renderer, width, height)
+                    .unwrap();
+            }
+            app.update(Action::CommandSubmit, &renderer, width, height)
+                .unwrap();
+        };
+        command(&mut app, "tab 2");
+        app.update(Action::ScrollDown(12), &renderer, width, height)
+            .unwrap();
+        command(&mut app, "vsplit");
+        command(&mut app, "mksession work");
+        assert_eq!(app.status_message(), Some("Saved session work"));
+
+        let mut restored = App::empty();
+        restored.track_sessions(SessionStore::new(dir.path()));
+        restored
+            .open_session("work", &renderer, width, height)
+            .unwrap();
+        let titles: Vec<String> = restored.tabs.iter().map(page_key).collect();
+        assert_eq!(titles, vec!["ls", "errno(3)", "cp"]);
+        assert_eq!(restored.title(), "cp");
+        assert_eq!(restored.layout().map(PaneLayout::tabs), Some(vec![1, 2]));
+        assert_eq!(restored.tabs[1].scroll, 12);
+
+        command(&mut restored, "session home");
+        assert_eq!(restored.status_message(), Some("No session named home"));
+    }
+
+    #[test]
+    fn cursor_mode_acts_on_the_word_under_the_cursor() {
+        let lines = vec!["NAME".to_string(), "   see read(2) and close".to_string()];
+        let renderer = LinesRenderer::new(lines);
+        let width: u16 = 80;
+        let height: usize = 10;
+        let mut app = App::new("open", None);
+        app.resize_active(&renderer, width, height).unwrap();
+        let send = |app: &mut App, action| {
+            app.update(action, &renderer, width, height).unwrap();
+        };
+        send(&mut app, Action::EnterCursor);
+        assert_eq!(app.mode(), &Mode::Cursor { row: 0, column: 0 });
+        send(&mut app, Action::MoveCursor(CursorMotion::WordForward));
+        send(&mut app, Action::MoveCursor(CursorMotion::WordForward));
+        assert_eq!(app.mode(), &Mode::Cursor { row: 1, column: 7 });
+        send(&mut app, Action::CursorYank);
+        assert_eq!(app.drain_effects(), vec![Effect::Copy("read".to_string())]);
+        send(&mut app, Action::MoveCursor(CursorMotion::LineEnd));
+        send(&mut app, Action::CursorOpen);
+        assert_eq!(app.status_message(), Some("No reference under the cursor"));
+        send(&mut app, Action::MoveCursor(CursorMotion::WordBack));
+        send(&mut app, Action::MoveCursor(CursorMotion::WordBack));
+        send(&mut app, Action::MoveCursor(CursorMotion::WordBack));
+        send(&mut app, Action::CursorOpen);
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.title(), "read(2)");
+
+        send(&mut app, Action::TabLeft);
+        send(&mut app, Action::EnterCursor);
+        send(&mut app, Action::MoveCursor(CursorMotion::Down));
+        send(&mut app, Action::MoveCursor(CursorMotion::LineEnd));
+        send(&mut app, Action::CursorLookup);
+        assert_eq!(app.title(), "close");
+        assert_eq!(app.tabs.len(), 3);
+    }
+
+    #[test]
+    fn toggles_between_formatted_page_and_roff_source() {
+        let dir = TempDir::new("source").expect("temp dir");
+        let source = dir.join("open.1");
+        fs::write(
+            &source,
+            ".TH OPEN 2\n.SH NAME\nopen \\- open a file\n.SH ERRORS\n.B EINTR\n",
+        )
+        .expect("write");
+        let mut lines: Vec<String> = (0..20).map(|idx| format!("line {idx}")).collect();
+        lines[15] = "EINTR".to_string();
+        let renderer = SourceRenderer { lines, source };
+        let mut app = App::new("open", Some("2".to_string()));
+        app.resize_active(&renderer, 80, 5).unwrap();
+        let send = |app: &mut App, action| {
+            app.update(action, &renderer, 80, 5).unwrap();
+        };
+        send(&mut app, Action::ScrollDown(10));
+        send(&mut app, Action::ToggleSource);
+        assert!(app.showing_source());
+        assert_eq!(app.lines()[0], ".TH OPEN 2");
+        assert_eq!(app.scroll(), 0);
+        send(&mut app, Action::EnterSearchMode);
+        for ch in "EINTR".chars() {
+            send(&mut app, Action::SearchChar(ch));
+        }
+        send(&mut app, Action::SearchSubmit);
+        assert_eq!(app.tabs()[0].current_match_line(), Some(4));
+        send(&mut app, Action::Resize(60, 6));
+        assert_eq!(app.lines().len(), 5);
+
+        send(&mut app, Action::ToggleSource);
+        assert!(!app.showing_source());
+        assert_eq!(app.scroll(), 10);
+        assert_eq!(app.tabs()[0].current_match_line(), Some(15));
+
+        let renderer = LinesRenderer::new(vec!["text".to_string()]);
+        app.update(Action::ToggleSource, &renderer, 80, 5).unwrap();
+        assert_eq!(
+            app.status_message(),
+            Some("No source file found for open(2)")
+        );
+    }
+
+    #[test]
+    fn exports_pdf_and_postscript_from_the_page_source() {
+        let dir = TempDir::new("typeset").expect("temp dir");
+        let source = dir.join("open.1");
+        fs::write(&source, ".TH OPEN 2\n").expect("write");
+        let renderer = SourceRenderer {
+            lines: vec!["OPEN(2)".to_string()],
+            source,
+        };
+        let mut app = App::new("open", Some("2".to_string()));
+        app.resize_active(&renderer, 80, 5).unwrap();
+        for (name, expected) in [("PDF", "%pdf\n.TH OPEN 2\n"), ("ps", "%ps\n.TH OPEN 2\n")] {
+            let path = dir.join(format!("open.{name}"));
+            let command = format!("export {}", path.display());
+            app.execute_command(parse_command(&command), &renderer, 80, 5)
+                .unwrap();
+            assert_eq!(fs::read_to_string(&path).expect("read"), expected);
+            assert_eq!(
+                app.status_message(),
+                Some(format!("Exported open(2) to {}", path.display()).as_str())
+            );
+            fs::remove_file(&path).expect("remove");
+        }
+
+        let path = dir.join("open.pdf");
+        let renderer = LinesRenderer::new(vec!["text".to_string()]);
+        let command = format!("export {}", path.display());
+        app.execute_command(parse_command(&command), &renderer, 80, 5)
+            .unwrap();
+        assert_eq!(
+            app.status_message(),
+            Some("No source file found for open(2)")
+        );
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn huge_pages_are_readable_while_they_stream_in() {
+        let (sender, receiver) = mpsc::channel();
+        let renderer = StreamingRenderer {
+            stream: RefCell::new(Some(receiver)),
+        };
+        let lines = |range: std::ops::Range<usize>| {
+            RenderChunk::Lines(range.map(|idx| format!("line {idx}")).collect())
+        };
+        sender.send(lines(0..20)).unwrap();
+        let mut app = App::new("bash", None);
+        app.resize_active(&renderer, 80, 10).unwrap();
+        assert_eq!(app.line_count(), 20);
+        assert!(app.loading());
+        app.update(Action::ScrollDown(5), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.scroll(), 5);
+        assert!(!app.receive_streams(10));
+
+        sender.send(lines(20..5000)).unwrap();
+        sender
+            .send(RenderChunk::Warning("bash is not UTF-8".to_string()))
+            .unwrap();
+        sender.send(RenderChunk::Done(Ok(()))).unwrap();
+        assert!(app.receive_streams(10));
+        assert_eq!(app.line_count(), 5000);
+        assert!(!app.loading());
+        assert!(!app.streaming());
+        assert_eq!(app.scroll(), 5);
+        assert_eq!(app.status_message(), Some("bash is not UTF-8"));
+    }
+
+    #[test]
+    fn shows_render_warnings_in_the_status_line() {
+        struct LegacyRenderer {
+            warned: Cell<bool>,
+        }
+
+        impl ManRenderer for LegacyRenderer {
+            fn render(
+                &self,
+                name: &str,
+                _section: Option<&str>,
+                _width: u16,
+            ) -> Result<Vec<String>, RenderError> {
+                self.warned.set(name == "latin");
+                Ok(vec![format!("{name} caf\u{fffd}")])
+            }
+
+            fn take_warning(&self) -> Option<String> {
+                self.warned
+                    .replace(false)
+                    .then(|| "latin is not UTF-8".to_string())
+            }
+        }
+
+        let renderer = LegacyRenderer {
+            warned: Cell::new(false),
+        };
+        let mut app = App::empty();
+        app.open_pages(vec!["latin".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.status_message(), Some("latin is not UTF-8"));
+        assert_eq!(app.lines()[0], "latin caf\u{fffd}");
+        app.update(Action::ScrollDown(1), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.status_message(), None);
+    }
+
+    #[test]
+    fn man_all_opens_a_tab_per_section() {
+        let renderer = SectionsRenderer;
+        let mut app = App::new("ls", None);
+        app.resize_active(&renderer, 80, 10).unwrap();
+        app.update(Action::EnterCommandMode, &renderer, 80, 10)
+            .unwrap();
+        for ch in "man -a open".chars() {
+            app.update(Action::CommandChar(ch), &renderer, 80, 10)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, 80, 10)
+            .unwrap();
+        let titles: Vec<String> = app.tabs().iter().map(page_key).collect();
+        assert_eq!(titles, vec!["ls", "open(1)", "open(3)"]);
+        assert_eq!(app.title(), "open(1)");
+        assert_eq!(app.lines()[0], "open:1");
+    }
+
+    #[test]
+    fn hint_labels_open_references_in_new_tabs() {
+        let lines = vec![
+            "NAME".to_string(),
+            "   see read(2) and close(2)".to_string(),
+            "   also write(2)".to_string(),
+        ];
+        let renderer = LinesRenderer::new(lines);
+        let width: u16 = 80;
+        let height: usize = 2;
+        let mut app = App::new("open", None);
+        app.resize_active(&renderer, width, height).unwrap();
+        let send = |app: &mut App, action| {
+            app.update(action, &renderer, width, height).unwrap();
+        };
+        send(&mut app, Action::EnterHints);
+        let Mode::Hints { hints, .. } = app.mode() else {
+            panic!("expected hint mode");
+        };
+        let targets: Vec<&str> = hints.iter().map(|hint| hint.target.as_str()).collect();
+        assert_eq!(targets, vec!["read(2)", "close(2)"]);
+        send(&mut app, Action::HintChar('s'));
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.title(), "close(2)");
+        assert_eq!(app.tabs.len(), 2);
+
+        send(&mut app, Action::TabLeft);
+        send(&mut app, Action::EnterHints);
+        send(&mut app, Action::HintChar('x'));
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.status_message(), Some("No hint x"));
+    }
+
+    #[test]
+    fn wipe_closes_active_tab_and_handles_empty() {
+        let renderer = StubRenderer::new();
+        let width: u16 = 80;
+        let height: usize = 10;
+        let mut app = App::empty();
+        app.update(
+            Action::Resize(width, height as u16),
+            &renderer,
+            width,
+            height,
+        )
+        .unwrap();
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "wipe".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.tabs.len(), 0);
+
+        let mut app = App::new("open", None);
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "man ls".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.tabs.len(), 2);
+        assert_eq!(app.active, 1);
+
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "w".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(app.active, 0);
+        assert_eq!(app.title(), "open");
+    }
+
+    #[test]
+    fn edits_prompt_lines_at_the_cursor() {
+        let renderer = StubRenderer::new();
+        let mut app = App::new("open", None);
+        app.resize_active(&renderer, 80, 10).unwrap();
+        let mut send = |action| app.update(action, &renderer, 80, 10).unwrap();
+        send(Action::EnterCommandMode);
+        for ch in "man ls".chars() {
+            send(Action::CommandChar(ch));
+        }
+        send(Action::EditLine(LineEdit::Home));
+        send(Action::EditLine(LineEdit::KillToEnd));
+        for ch in "split".chars() {
+            send(Action::CommandChar(ch));
+        }
+        send(Action::EditLine(LineEdit::Left));
+        send(Action::CommandBackspace);
+        assert!(matches!(app.mode(), Mode::Command { line } if line == "splt"));
+        assert_eq!(app.prompt_cursor(), 3);
+
+        let mut send = |action| app.update(action, &renderer, 80, 10).unwrap();
+        send(Action::CommandCancel);
+        send(Action::EnterSearchMode);
+        for ch in "line 4".chars() {
+            send(Action::SearchChar(ch));
+        }
+        send(Action::EditLine(LineEdit::Left));
+        send(Action::SearchChar('2'));
+        assert_eq!(app.search_query(), Some("line 24"));
+        app.update(Action::EditLine(LineEdit::DeleteWord), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.search_query(), Some("line 4"));
+        assert_eq!(app.prompt_cursor(), 5);
+    }
+
+    #[test]
+    fn match_list_jumps_to_selected_match() {
+        let renderer = StubRenderer::new();
+        let mut app = App::new("open", None);
+        app.resize_active(&renderer, 80, 10).unwrap();
+        let mut send = |action| app.update(action, &renderer, 80, 10).unwrap();
+        send(Action::ToggleMatches);
+        send(Action::EnterSearchMode);
+        for ch in "open".chars() {
+            send(Action::SearchChar(ch));
+        }
+        send(Action::SearchSubmit);
+        send(Action::ToggleMatches);
+        for _ in 0..30 {
+            send(Action::MatchesDown);
+        }
+        send(Action::MatchesUp);
+        assert_eq!(app.mode(), &Mode::Matches { selected: 29 });
+        assert_eq!(app.scroll(), 24);
+        assert_eq!(app.tabs[0].current_match_line(), Some(29));
+        app.update(Action::ToggleMatches, &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.scroll(), 24);
+    }
+
+    #[test]
+    fn man_command_sets_status_on_missing_page() {
+        let renderer = FailingRenderer;
+        let width: u16 = 80;
+        let height: usize = 10;
+        let mut app = App::new("open", None);
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "man seek".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.tabs.len(), 2);
+        assert_eq!(app.active, 1);
+        assert_eq!(app.tabs[1].error(), Some("No manual entry for seek"));
+        assert_eq!(app.status_message(), Some("No manual entry for seek"));
+    }
+
+    struct FlakyRenderer {
+        installed: Cell<bool>,
+    }
+
+    impl ManRenderer for FlakyRenderer {
+        fn render(
+            &self,
+            name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            if self.installed.get() {
+                Ok(vec![format!("{name} page")])
+            } else {
+                Err(RenderError::CommandFailed(format!(
+                    "No manual entry for {name}"
+                )))
+            }
+        }
+    }
+
+    #[test]
+    fn failed_page_stays_open_until_retried() {
+        let renderer = FlakyRenderer {
+            installed: Cell::new(false),
+        };
+        let mut app = App::empty();
+        app.open_pages(
+            vec!["seek".to_string()],
+            Some("2".to_string()),
+            &renderer,
+            80,
+            10,
+        )
+        .unwrap();
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(app.lines()[0], "Could not render seek(2)");
+        assert_eq!(app.lines()[2], "    No manual entry for seek");
+
+        app.update(Action::Resize(60, 10), &renderer, 60, 10)
+            .unwrap();
+        app.update(Action::RetryRender, &renderer, 60, 10).unwrap();
+        assert_eq!(app.tabs[0].error(), Some("No manual entry for seek"));
+
+        renderer.installed.set(true);
+        app.update(Action::RetryRender, &renderer, 60, 10).unwrap();
+        assert_eq!(app.tabs[0].error(), None);
+        assert_eq!(app.lines(), ["seek page".to_string()]);
+        assert_eq!(app.status_message(), Some("Rendered seek(2)"));
+
+        app.update(Action::Refresh, &renderer, 60, 10).unwrap();
+        assert_eq!(app.tabs[0].render_stats().renders, 2);
+        assert_eq!(app.status_message(), Some("Refreshed seek(2)"));
+    }
+
+    fn printf_lines() -> Vec<String> {
+        let mut lines = vec![
+            "NAME".to_string(),
+            "       printf, fprintf, sprintf - formatted output conversion".to_string(),
+            String::new(),
+            "SYNOPSIS".to_string(),
+        ];
+        for idx in 0..20 {
+            lines.push(format!("       filler {idx}"));
+        }
+        lines.push("       int sprintf(char *restrict str,".to_string());
+        for idx in 0..20 {
+            lines.push(format!("       more {idx}"));
+        }
+        lines
+    }
+
+    #[test]
+    fn man_alias_jumps_within_shared_page() {
+        let renderer = LinesRenderer::new(printf_lines());
+        let width: u16 = 80;
+        let height: usize = 10;
+        let mut app = App::new("printf", Some("3".to_string()));
+        app.resize_active(&renderer, width, height).unwrap();
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "man sprintf".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(app.scroll(), 24);
+        assert_eq!(
+            app.status_message(),
+            Some("sprintf is documented in printf")
+        );
+
+        let mut app = App::empty();
+        app.open_pages(vec!["sprintf".to_string()], None, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(app.title(), "sprintf");
+        assert_eq!(app.scroll(), 24);
+    }
+
+    struct SectionsRenderer;
+
+    impl ManRenderer for SectionsRenderer {
+        fn render(
+            &self,
+            name: &str,
+            section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Ok(vec![format!("{name}:{}", section.unwrap_or("-"))])
+        }
+
+        fn lookup(&self, name: &str) -> Result<Vec<PageEntry>, RenderError> {
+            Ok(["1", "3"]
+                .iter()
+                .map(|section| PageEntry {
+                    name: name.to_string(),
+                    section: section.to_string(),
+                    description: String::new(),
+                })
+                .collect())
+        }
+
+        fn apropos(&self, keyword: &str) -> Result<Vec<PageEntry>, RenderError> {
+            Ok([("ls", "1"), (keyword, "3")]
+                .iter()
+                .map(|(name, section)| PageEntry {
+                    name: name.to_string(),
+                    section: section.to_string(),
+                    description: String::new(),
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn apropos_previews_the_highlighted_page() {
+        let renderer = SectionsRenderer;
+        let width: u16 = 80;
+        let height: usize = 10;
+        let mut app = App::new("printf", Some("3".to_string()));
+        app.resize_active(&renderer, width, height).unwrap();
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "apropos printf".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, width, height)
+            .unwrap();
+        assert!(matches!(app.mode(), Mode::Picker { .. }));
+        assert_eq!(
+            app.picker_preview(),
+            Some(("ls(1)", ["ls:1".to_string()].as_slice()))
+        );
+
+        app.update(Action::PickerDown, &renderer, width, height)
+            .unwrap();
+        assert_eq!(
+            app.picker_preview(),
+            Some(("printf(3)", ["printf:3".to_string()].as_slice()))
+        );
+        app.update(Action::PickerCancel, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.picker_preview(), None);
+        assert_eq!(app.tabs.len(), 1);
+    }
+
+    #[test]
+    fn page_index_completes_finds_and_suggests() {
+        let renderer = FailingRenderer;
+        let width: u16 = 80;
+        let height: usize = 10;
+        let mut app = App::empty();
+        app.set_page_index(
+            [
+                ("printf", "1"),
+                ("printf", "3"),
+                ("pr", "1"),
+                ("sprintf", "3"),
+            ]
+            .iter()
+            .map(|(name, section)| PageEntry {
+                name: name.to_string(),
+                section: section.to_string(),
+                description: String::new(),
+            })
+            .collect(),
+        );
+        assert_eq!(app.page_index().len(), 4);
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "man 3 spr".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandComplete, &renderer, width, height)
+            .unwrap();
+        assert_eq!(
+            app.mode(),
+            &Mode::Command {
+                line: "man 3 sprintf ".to_string()
+            }
+        );
+        app.update(Action::CommandCancel, &renderer, width, height)
+            .unwrap();
+
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "find prf".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, width, height)
+            .unwrap();
+        let Mode::Picker { entries, .. } = app.mode() else {
+            panic!("expected finder picker");
+        };
+        assert_eq!(entries.len(), 3);
+        app.update(Action::PickerCancel, &renderer, width, height)
+            .unwrap();
+
+        app.open_pages(vec!["prinf".to_string()], None, &renderer, width, height)
+            .unwrap();
+        assert!(
+            app.status_message()
+                .is_some_and(|message| message.ends_with("Did you mean printf?"))
+        );
+    }
+
+    #[test]
+    fn ambiguous_topic_opens_section_picker() {
+        let renderer = SectionsRenderer;
+        let width: u16 = 80;
+        let height: usize = 10;
+        let mut app = App::empty();
+        app.update(Action::EnterCommandMode, &renderer, width, height)
+            .unwrap();
+        for ch in "man printf".chars() {
+            app.update(Action::CommandChar(ch), &renderer, width, height)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, width, height)
+            .unwrap();
+        assert!(matches!(app.mode(), Mode::Picker { .. }));
+        assert!(app.tabs.is_empty());
+
+        app.update(Action::PickerDown, &renderer, width, height)
+            .unwrap();
+        app.update(Action::PickerSubmit, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.title(), "printf(3)");
+        assert_eq!(app.lines(), ["printf:3".to_string()]);
+    }
+
+    struct HelpRenderer;
+
+    impl ManRenderer for HelpRenderer {
+        fn render(
+            &self,
+            name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Err(RenderError::CommandFailed(format!(
+                "No manual entry for {name}"
+            )))
+        }
+
+        fn help_command(&self, name: &str) -> Option<PathBuf> {
+            (name == "tool").then(|| PathBuf::from("/usr/bin/tool"))
+        }
+
+        fn render_help(&self, program: &Path, width: u16) -> Result<Vec<String>, RenderError> {
+            Ok(vec![format!("usage: {}:{width}", program.display())])
+        }
+
+        fn run_command(&self, command: &str, width: u16) -> Result<Vec<String>, RenderError> {
+            match command {
+                "false" => Err(RenderError::CommandFailed(
+                    "false printed nothing (exit status: 1)".to_string(),
+                )),
+                _ => Ok((0..30)
+                    .map(|idx| format!("{command}:{width} {idx}"))
+                    .collect()),
+            }
+        }
+    }
+
+    #[test]
+    fn cheat_sheets_open_as_tabs() {
+        let dir = TempDir::new("cheat").expect("temp dir");
+        std::fs::write(dir.join("tar.txt"), "# extract\ntar xf a.tar").unwrap();
+        let renderer = StubRenderer::new();
+        let mut app = App::empty();
+        app.execute_command(parse_command("cheat tar"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.status_message(), Some("cheat.sh is not available"));
+        app.track_cheats(CheatRenderer::new(dir.path()).with_url("http://127.0.0.1:9"));
+        app.execute_command(parse_command("cheat tar"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.title(), "cheat.sh/tar");
+        assert_eq!(app.lines(), ["# extract", "tar xf a.tar"]);
+        app.execute_command(parse_command("cheat rsync"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(
+            app.status_message(),
+            Some("cheat.sh is unreachable and rsync is not cached")
+        );
+    }
+
+    #[test]
+    fn shell_commands_open_read_only_tabs() {
+        let renderer = HelpRenderer;
+        let mut app = App::empty();
+        assert_eq!(
+            parse_command("! rustc --explain E0502 "),
+            ParsedCommand::Shell("rustc --explain E0502".to_string())
+        );
+        assert_eq!(parse_command("!"), ParsedCommand::Unknown("!".to_string()));
+        app.execute_command(parse_command("!rustc --explain E0502"), &renderer, 72, 10)
+            .unwrap();
+        assert_eq!(app.title(), "!rustc --explain E0502");
+        assert_eq!(app.lines()[0], "rustc --explain E0502:72 0");
+        app.update(Action::ScrollDown(5), &renderer, 72, 10)
+            .unwrap();
+        assert_eq!(app.scroll(), 5);
+
+        app.execute_command(parse_command("!false"), &renderer, 72, 10)
+            .unwrap();
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(
+            app.status_message(),
+            Some("false printed nothing (exit status: 1)")
+        );
+    }
+
+    #[test]
+    fn overrides_the_render_width_per_tab() {
+        let renderer = StubRenderer::new();
+        let mut app = App::empty();
+        app.open_pages(
+            vec!["ls".to_string(), "tar".to_string()],
+            None,
+            &renderer,
+            100,
+            10,
+        )
+        .unwrap();
+        assert_eq!(parse_command("width 72"), ParsedCommand::Width(Some(72)));
+        assert_eq!(parse_command("width auto"), ParsedCommand::Width(None));
+        assert_eq!(
+            parse_command("width 10"),
+            ParsedCommand::Unknown("width".to_string())
+        );
+        app.execute_command(parse_command("width 72"), &renderer, 100, 10)
+            .unwrap();
+        assert_eq!(app.lines()[0], "tar:72");
+        assert_eq!(
+            app.status_message(),
+            Some("tar is rendered 72 columns wide")
+        );
+
+        app.update(Action::TabLeft, &renderer, 120, 10).unwrap();
+        assert_eq!(app.lines()[0], "ls:120");
+        app.update(Action::TabRight, &renderer, 120, 10).unwrap();
+        assert_eq!(app.lines()[0], "tar:72");
+
+        app.execute_command(parse_command("width"), &renderer, 120, 10)
+            .unwrap();
+        assert_eq!(app.lines()[0], "tar:120");
+    }
+
+    #[test]
+    fn renames_tabs_without_changing_the_topic() {
+        let renderer = StubRenderer::new();
+        let mut app = App::new("errno", Some("3".to_string()));
+        assert_eq!(
+            parse_command("rename  EINTR notes "),
+            ParsedCommand::Rename(Some("EINTR notes".to_string()))
+        );
+        app.execute_command(parse_command("rename EINTR notes"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.tabs[0].label.as_deref(), Some("EINTR notes"));
+        assert_eq!(app.tabs[0].name(), "errno");
+        assert_eq!(
+            app.status_message(),
+            Some("Renamed errno(3) to EINTR notes")
+        );
+        app.execute_command(parse_command("rename"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.tabs[0].label, None);
+        assert_eq!(app.status_message(), Some("Restored the title of errno(3)"));
+    }
+
+    #[test]
+    fn missing_page_offers_help_output() {
+        let renderer = HelpRenderer;
+        let mut app = App::empty();
+        app.open_pages(vec!["tool".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(
+            app.mode(),
+            &Mode::Confirm {
+                topic: "tool".to_string(),
+                program: PathBuf::from("/usr/bin/tool"),
+            }
+        );
+        assert!(app.tabs.is_empty());
+
+        app.update(Action::ConfirmAccept, &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.title(), "tool --help");
+        assert_eq!(app.lines(), ["usage: /usr/bin/tool:80".to_string()]);
+
+        app.open_pages(vec!["tool".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        app.update(Action::ConfirmReject, &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.status_message(), Some("No manual entry for tool"));
+        assert_eq!(app.tabs[1].error(), Some("No manual entry for tool"));
+        app.open_pages(vec!["other".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.mode(), &Mode::Normal);
+    }
+
+    #[test]
+    fn history_records_pages_and_reopens_them() {
+        let dir = TempDir::new("history").expect("temp dir");
+        let renderer = StubRenderer::new();
+        let mut app = App::empty();
+        app.track_history(History::new(dir.join("history")));
+        app.execute_command(parse_command("history"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.status_message(), Some("History is empty"));
+
+        app.open_pages(
+            vec!["ls".to_string(), "printf(3)".to_string()],
+            None,
+            &renderer,
+            80,
+            10,
+        )
+        .unwrap();
+        app.execute_command(parse_command("history"), &renderer, 80, 10)
+            .unwrap();
+        let Mode::Picker { entries, .. } = app.mode() else {
+            panic!("expected history picker");
+        };
+        let pages: Vec<(&str, Option<&str>)> = entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.section.as_deref()))
+            .collect();
+        assert_eq!(pages, vec![("printf", Some("3")), ("ls", None)]);
+
+        app.update(Action::PickerDown, &renderer, 80, 10).unwrap();
+        app.update(Action::PickerSubmit, &renderer, 80, 10).unwrap();
+        assert_eq!(app.title(), "ls");
+    }
+
+    #[test]
+    fn bookmarks_restore_page_and_line() {
+        let dir = TempDir::new("bookmarks").expect("temp dir");
+        let renderer = StubRenderer::new();
+        let mut app = App::empty();
+        app.track_bookmarks(Bookmarks::new(dir.join("bookmarks")));
+        app.open_pages(vec!["bash(1)".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        app.update(Action::ScrollDown(12), &renderer, 80, 10)
+            .unwrap();
+        app.execute_command(parse_command("bookmark traps"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.status_message(), Some("Bookmarked bash(1) as traps"));
+
+        let mut app = App::empty();
+        app.track_bookmarks(Bookmarks::new(dir.join("bookmarks")));
+        app.execute_command(parse_command("bookmarks"), &renderer, 80, 10)
+            .unwrap();
+        app.update(Action::PickerSubmit, &renderer, 80, 10).unwrap();
+        assert_eq!(app.title(), "bash(1)");
+        assert_eq!(app.scroll(), 12);
+    }
+
+    #[test]
+    fn reopened_pages_restore_their_position() {
+        let dir = TempDir::new("positions").expect("temp dir");
+        let renderer = StubRenderer::new();
+        let mut app = App::empty();
+        app.track_positions(PositionStore::new(dir.join("positions")));
+        app.open_pages(vec!["bash(1)".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        app.update(Action::ScrollDown(7), &renderer, 80, 10)
+            .unwrap();
+        app.execute_command(ParsedCommand::Wipe, &renderer, 80, 10)
+            .unwrap();
+        app.open_pages(vec!["bash(1)".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.scroll(), 7);
+        app.update(Action::ScrollDown(3), &renderer, 80, 10)
+            .unwrap();
+        app.save_positions().unwrap();
+
+        let mut app = App::empty();
+        app.track_positions(PositionStore::new(dir.join("positions")));
+        app.open_pages(vec!["bash(1)".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.scroll(), 10);
+    }
+
+    struct FileRenderer;
+
+    impl ManRenderer for FileRenderer {
+        fn render(
+            &self,
+            name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Ok(fs::read_to_string(name)?
+                .lines()
+                .map(|line| line.to_string())
+                .collect())
+        }
+    }
+
+    #[test]
+    fn reloads_local_pages_when_they_change() {
+        let dir = TempDir::new("watch").expect("temp dir");
+        let path = dir.join("tool.1");
+        let write = |text: &str, seconds: u64| {
+            fs::write(&path, text).expect("write");
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| {
+                    file.set_modified(
+                        std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+                    )
+                })
+                .expect("mtime");
+        };
+        write(&"old\n".repeat(30), 1);
+        let name = path.to_string_lossy().into_owned();
+        let renderer = FileRenderer;
+        let mut app = App::new(name.clone(), None);
+        app.resize_active(&renderer, 80, 10).expect("render");
+        app.scroll_down(12, 10);
+        assert!(app.watches_files());
+
+        write(&"new\n".repeat(30), 2);
+        assert!(app.reload_changed(&renderer, 80, 10));
+        assert_eq!(app.lines()[0], "new");
+        assert_eq!(app.scroll(), 12);
+        assert_eq!(
+            app.status_message(),
+            Some(format!("Reloaded {name}").as_str())
+        );
+        assert!(!app.reload_changed(&renderer, 80, 10));
+
+        app.tabs[0].release_render();
+        write(&"newer\n".repeat(30), 3);
+        assert!(app.reload_changed(&renderer, 80, 10));
+        assert_eq!(app.lines()[0], "newer");
+        assert!(!app.reload_changed(&renderer, 80, 10));
+    }
+
+    #[test]
+    fn diff_opens_a_tab_comparing_two_pages() {
+        let renderer = StubRenderer::new();
+        let mut app = App::new("open", Some("2".to_string()));
+        app.resize_active(&renderer, 80, 10).unwrap();
+        app.execute_command(parse_command("diff creat(3p)"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.tabs().len(), 2);
+        assert_eq!(app.title(), "open(2) vs creat(3p)");
+        assert_eq!(app.status_message(), Some("50 lines added, 50 removed"));
+        assert_eq!(app.diff_kinds().len(), 100);
+        assert_eq!(app.diff_kinds()[0], DiffKind::Removed);
+        assert_eq!(app.lines()[0], "- open:80");
+        assert_eq!(app.lines()[50], "+ creat:80");
+    }
+
+    #[test]
+    fn motions_jump_between_options_and_sections() {
+        let lines = [
+            "OPTIONS",
+            "       -a, --all",
+            "              do not ignore entries",
+            "       -l     use a long listing format",
+            "FILES",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        let renderer = LinesRenderer::new(lines);
+        let mut app = App::new("ls", None);
+        app.resize_active(&renderer, 80, 1).unwrap();
+        app.update(Action::BeginPending(']'), &renderer, 80, 1)
+            .unwrap();
+        assert_eq!(app.mode(), &Mode::Pending { key: ']' });
+        app.update(Action::NextOption, &renderer, 80, 1).unwrap();
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.scroll(), 1);
+        app.update(Action::NextOption, &renderer, 80, 1).unwrap();
+        assert_eq!(app.scroll(), 3);
+        app.update(Action::NextOption, &renderer, 80, 1).unwrap();
+        assert_eq!(app.status_message(), Some("No more options"));
+        app.update(Action::PrevOption, &renderer, 80, 1).unwrap();
+        assert_eq!(app.scroll(), 1);
+        app.update(Action::NextSection, &renderer, 80, 1).unwrap();
+        assert_eq!(app.scroll(), 4);
+        app.update(Action::PrevSection, &renderer, 80, 1).unwrap();
+        assert_eq!(app.scroll(), 0);
+        app.update(Action::PrevSection, &renderer, 80, 1).unwrap();
+        assert_eq!(app.status_message(), Some("No more sections"));
+
+        app.execute_command(parse_command("option -l"), &renderer, 80, 1)
+            .unwrap();
+        assert_eq!(app.scroll(), 3);
+        app.execute_command(parse_command("opt --long"), &renderer, 80, 1)
+            .unwrap();
+        assert_eq!(app.status_message(), Some("No option '--long' in ls"));
+    }
+
+    #[test]
+    fn next_section_reports_the_last_screen() {
+        let lines = ["NAME", "  ls", "DESCRIPTION", "  list", "FILES", "  none"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        let renderer = LinesRenderer::new(lines);
+        let mut app = App::new("ls", None);
+        app.resize_active(&renderer, 80, 4).unwrap();
+        app.update(Action::NextSection, &renderer, 80, 4).unwrap();
+        assert_eq!(app.scroll(), 2);
+        assert_eq!(app.status_message(), None);
+        app.update(Action::NextSection, &renderer, 80, 4).unwrap();
+        assert_eq!(app.scroll(), 2);
+        assert_eq!(app.status_message(), Some("No more sections"));
+    }
+
+    #[test]
+    fn folds_sections_under_the_viewport() {
+        let lines = [
+            "NAME",
+            "       ls - list",
+            "ENVIRONMENT",
+            "       LANG",
+            "       TZ",
+            "SEE ALSO",
+            "       dir(1)",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        let renderer = LinesRenderer::new(lines);
+        let mut app = App::new("ls", None);
+        app.resize_active(&renderer, 80, 2).unwrap();
+        app.scroll_down(3, 2);
+        app.update(Action::ToggleFold, &renderer, 80, 2).unwrap();
+        assert_eq!(app.lines().len(), 5);
+        assert_eq!(app.scroll(), 2);
+        assert_eq!(app.fold_markers(), &[(2, 2)]);
+        assert_eq!(app.source_lines().len(), 7);
+
+        app.update(Action::NextSection, &renderer, 80, 2).unwrap();
+        assert_eq!(app.lines()[app.scroll()], "SEE ALSO");
+        app.execute_command(parse_command("goto environment"), &renderer, 80, 2)
+            .unwrap();
+        assert_eq!(app.scroll(), 2);
+
+        app.update(Action::FoldAll, &renderer, 80, 2).unwrap();
+        assert_eq!(app.lines(), ["NAME", "ENVIRONMENT", "SEE ALSO"]);
+        app.update(Action::UnfoldAll, &renderer, 80, 2).unwrap();
+        assert_eq!(app.lines().len(), 7);
+        assert_eq!(app.scroll(), 2);
+    }
+
+    #[test]
+    fn scoped_search_stays_within_the_current_section() {
+        let lines = [
+            "DESCRIPTION",
+            "       the size of the file",
+            "OPTIONS",
+            "       -s     print the allocated size",
+            "       -h     human readable size",
+            "SEE ALSO",
+            "       size(1)",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        let renderer = LinesRenderer::new(lines);
+        let mut app = App::new("ls", None);
+        app.resize_active(&renderer, 80, 1).unwrap();
+        app.scroll_down(3, 1);
+        app.update(Action::EnterSearchMode, &renderer, 80, 1)
+            .unwrap();
+        app.update(Action::SearchToggleScope, &renderer, 80, 1)
+            .unwrap();
+        assert_eq!(app.search_scope(), Some("OPTIONS"));
+        for ch in "size".chars() {
+            app.update(Action::SearchChar(ch), &renderer, 80, 1)
+                .unwrap();
+        }
+        app.update(Action::SearchSubmit, &renderer, 80, 1).unwrap();
+        let lines: Vec<usize> = app.tabs()[0]
+            .search_matches()
+            .iter()
+            .map(|entry| entry.line)
+            .collect();
+        assert_eq!(lines, vec![3, 4]);
+
+        app.update(Action::SearchClear, &renderer, 80, 1).unwrap();
+        assert_eq!(app.search_scope(), None);
+    }
+
+    #[test]
+    fn moved_tabs_keep_their_scroll_and_search() {
+        let renderer = StubRenderer::new();
+        let mut app = App::empty();
+        app.set_screen_width(81);
+        let input = |app: &mut App, actions: Vec<Action>| {
+            for action in actions {
+                app.update(action, &renderer, 80, 10).unwrap();
+            }
+        };
+        let command = |app: &mut App, line: &str| {
+            let mut actions = vec![Action::EnterCommandMode];
+            actions.extend(line.chars().map(Action::CommandChar));
+            actions.push(Action::CommandSubmit);
+            input(app, actions);
+        };
+        command(&mut app, "man read write open");
+        input(&mut app, vec![Action::TabLeft, Action::ScrollDown(7)]);
+        let mut search = vec![Action::EnterSearchMode];
+        search.extend("write".chars().map(Action::SearchChar));
+        search.push(Action::SearchSubmit);
+        input(&mut app, search);
+        assert_eq!(app.search_query(), Some("write"));
+        let scroll = app.scroll();
+
+        command(&mut app, "tab-to-split");
+        assert_eq!(app.status_message(), Some("Moved write into a split"));
+        let layout = app.layout().expect("split");
+        assert_eq!(layout.tabs(), vec![2, 1]);
+        assert_eq!(layout.focused_tab(), 1);
+        assert_eq!(app.scroll(), scroll);
+        assert_eq!(app.search_query(), Some("write"));
+
+        command(&mut app, "tab-to-split");
+        assert_eq!(app.status_message(), Some("Moved write out of the split"));
+        assert!(app.layout().is_none());
+        assert_eq!(app.title(), "open");
+        assert_eq!(app.tabs()[1].scroll, scroll);
+
+        input(&mut app, vec![Action::TabLeft]);
+        command(&mut app, "split");
+        input(&mut app, vec![Action::SwitchPane]);
+        assert_eq!(app.title(), "write");
+        command(&mut app, "movetab syscalls");
+        assert_eq!(app.status_message(), Some("Moved write to group syscalls"));
+        assert_eq!(app.group_name(), "main");
+        assert_eq!(app.tabs().len(), 2);
+        assert!(app.layout().is_none());
+        command(&mut app, "movetab main");
+        assert_eq!(app.status_message(), Some("Tab is already in group main"));
+
+        command(&mut app, "group syscalls");
+        assert_eq!(app.title(), "write");
+        assert_eq!(app.scroll(), scroll);
+        assert_eq!(app.search_query(), Some("write"));
+    }
+
+    #[test]
+    fn split_view_tracks_panes() {
+        let renderer = StubRenderer::new();
+        let mut app = App::empty();
+        app.set_screen_width(81);
+        app.open_pages(vec!["read(2)".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        app.execute_command(parse_command("split"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(
+            app.status_message(),
+            Some("Open another page to split the view")
+        );
+        app.update(Action::EnterCommandMode, &renderer, 80, 10)
+            .unwrap();
+        for ch in "split write(2)".chars() {
+            app.update(Action::CommandChar(ch), &renderer, 80, 10)
+                .unwrap();
+        }
+        app.update(Action::CommandSubmit, &renderer, 80, 10)
+            .unwrap();
+        let layout = app.layout().expect("split");
+        assert_eq!(layout.tabs(), vec![0, 1]);
+        assert_eq!(layout.focus(), 1);
+        assert_eq!(app.tabs()[0].lines()[0], "read:40");
+        assert_eq!(app.tabs()[1].lines()[0], "write:40");
+
+        app.update(Action::ScrollDown(5), &renderer, 80, 10)
+            .unwrap();
+        app.update(Action::SwitchPane, &renderer, 80, 10).unwrap();
+        assert_eq!(app.title(), "read(2)");
+        assert_eq!(app.scroll(), 0);
+        assert_eq!(app.tabs()[1].scroll, 5);
+
+        app.update(
+            Action::ResizePane(SplitDirection::Horizontal, 20),
+            &renderer,
+            80,
+            10,
+        )
+        .unwrap();
+        assert_eq!(app.tabs()[0].lines()[0], "read:56");
+
+        app.update(Action::TabRight, &renderer, 80, 10).unwrap();
+        assert_eq!(app.layout().map(PaneLayout::tabs), Some(vec![1, 0]));
+
+        app.execute_command(ParsedCommand::Wipe, &renderer, 80, 10)
+            .unwrap();
+        app.update(Action::ScrollDown(1), &renderer, 80, 10)
+            .unwrap();
+        assert!(!app.is_split());
+        assert_eq!(app.tabs()[0].lines()[0], "read:80");
+    }
+
+    #[test]
+    fn scroll_lock_moves_other_panes_proportionally() {
+        struct LengthRenderer;
+
+        impl ManRenderer for LengthRenderer {
+            fn render(
+                &self,
+                name: &str,
+                _section: Option<&str>,
+                _width: u16,
+            ) -> Result<Vec<String>, RenderError> {
+                let length = if name == "linux" { 110 } else { 60 };
+                Ok(vec![name.to_string(); length])
+            }
         }
-    }
 
-    struct FailingRenderer;
+        let renderer = LengthRenderer;
+        let mut app = App::empty();
+        app.set_screen_width(81);
+        app.open_pages(vec!["posix".to_string()], None, &renderer, 80, 10)
+            .unwrap();
+        app.execute_command(parse_command("split linux"), &renderer, 80, 10)
+            .unwrap();
+        app.update(Action::ScrollDown(10), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.tabs()[0].scroll, 0);
 
-    impl ManRenderer for FailingRenderer {
-        fn render(
-            &self,
-            _name: &str,
-            _section: Option<&str>,
-            _width: u16,
-        ) -> Result<Vec<String>, RenderError> {
-            Err(RenderError::CommandFailed(
-                "No manual entry for seek".to_string(),
-            ))
-        }
+        assert_eq!(parse_command("scb"), ParsedCommand::ScrollLock);
+        app.execute_command(parse_command("scrollbind"), &renderer, 80, 10)
+            .unwrap();
+        assert!(app.scroll_lock());
+        app.update(Action::ScrollDown(10), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.tabs()[1].scroll, 20);
+        assert_eq!(app.tabs()[0].scroll, 10);
+        app.update(Action::GoBottom, &renderer, 80, 10).unwrap();
+        assert_eq!(app.tabs()[0].scroll, 50);
+
+        app.update(Action::SwitchPane, &renderer, 80, 10).unwrap();
+        app.update(Action::ScrollUp(25), &renderer, 80, 10).unwrap();
+        assert_eq!(app.tabs()[1].scroll, 50);
+
+        app.update(Action::ToggleScrollLock, &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.status_message(), Some("Scroll lock off"));
+        app.update(Action::GoTop, &renderer, 80, 10).unwrap();
+        assert_eq!(app.tabs()[1].scroll, 50);
     }
 
     #[test]
-    fn parses_commands() {
+    fn parses_page_refs_with_anchors() {
         assert_eq!(
-            parse_command("man ls"),
-            ParsedCommand::Man {
-                topics: vec!["ls".to_string()],
+            parse_page_ref("proc(5)#FILES"),
+            PageRef {
+                name: "proc".to_string(),
+                section: Some("5".to_string()),
+                anchor: Some("FILES".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_page_ref("ls#--all"),
+            PageRef {
+                name: "ls".to_string(),
                 section: None,
+                anchor: Some("--all".to_string()),
             }
         );
-        if man_available() {
-            assert_eq!(
-                parse_command("man 2 read"),
-                ParsedCommand::Man {
-                    topics: vec!["read".to_string()],
-                    section: Some("2".to_string()),
-                }
-            );
-        }
-        assert_eq!(parse_command("quit"), ParsedCommand::Quit);
-        assert_eq!(parse_command("q"), ParsedCommand::Quit);
-        assert_eq!(parse_command("wipe"), ParsedCommand::Wipe);
-        assert_eq!(parse_command("w"), ParsedCommand::Wipe);
-        assert_eq!(parse_command("help"), ParsedCommand::Help);
-        assert_eq!(parse_command("h"), ParsedCommand::Help);
-        assert_eq!(parse_command(""), ParsedCommand::Empty);
         assert_eq!(
-            parse_command("bogus"),
-            ParsedCommand::Unknown("bogus".to_string())
+            parse_page_ref("open.2:OPTIONS"),
+            PageRef {
+                name: "open".to_string(),
+                section: Some("2".to_string()),
+                anchor: Some("OPTIONS".to_string()),
+            }
+        );
+        assert_eq!(parse_page_ref("10.0.0.1:ls").name, "10.0.0.1:ls");
+        assert_eq!(parse_page_ref("web1:nginx(8)").name, "web1:nginx");
+        assert_eq!(
+            parse_page_ref("./pages/tool(1).1"),
+            PageRef {
+                name: "./pages/tool(1).1".to_string(),
+                section: None,
+                anchor: None,
+            }
         );
     }
 
     #[test]
-    fn switches_tabs_and_preserves_scroll() {
-        let renderer = StubRenderer::new();
-        let mut app = App::new("open", None);
+    fn opens_page_scrolled_to_anchor() {
+        let mut lines: Vec<String> = (0..30).map(|idx| format!("       line {idx}")).collect();
+        lines[12] = "FILES".to_string();
+        let renderer = LinesRenderer::new(lines);
         let width: u16 = 80;
         let height: usize = 10;
-        app.update(
-            Action::Resize(width, height as u16),
+        let mut app = App::empty();
+        app.open_pages(
+            vec!["proc(5)#files".to_string()],
+            None,
             &renderer,
             width,
             height,
         )
         .unwrap();
-        app.update(Action::ScrollDown(3), &renderer, width, height)
-            .unwrap();
+        assert_eq!(app.title(), "proc(5)");
+        assert_eq!(app.scroll(), 12);
+
+        app.update(Action::GoTop, &renderer, width, height).unwrap();
         app.update(Action::EnterCommandMode, &renderer, width, height)
             .unwrap();
-        for ch in "man ls".chars() {
+        for ch in "goto EXAMPLES".chars() {
             app.update(Action::CommandChar(ch), &renderer, width, height)
                 .unwrap();
         }
         app.update(Action::CommandSubmit, &renderer, width, height)
             .unwrap();
-        assert_eq!(app.tabs.len(), 2);
-        assert_eq!(app.active, 1);
         assert_eq!(app.scroll(), 0);
+        assert_eq!(
+            app.status_message(),
+            Some("No anchor 'EXAMPLES' in proc(5)")
+        );
+    }
 
-        app.update(Action::TabLeft, &renderer, width, height)
+    #[test]
+    fn yank_copies_top_line() {
+        let renderer = LinesRenderer::new(vec!["first".to_string(), "second  ".to_string()]);
+        let width: u16 = 80;
+        let height: usize = 1;
+        let mut app = App::new("open", None);
+        app.resize_active(&renderer, width, height).unwrap();
+        app.update(Action::ScrollDown(1), &renderer, width, height)
             .unwrap();
-        assert_eq!(app.active, 0);
-        assert_eq!(app.scroll(), 3);
+        app.update(Action::Yank, &renderer, width, height).unwrap();
+        assert_eq!(
+            app.drain_effects(),
+            vec![Effect::Copy("second".to_string())]
+        );
+        assert!(app.drain_effects().is_empty());
+        assert_eq!(app.status_message(), Some("Yanked 1 line"));
+    }
 
-        app.update(Action::TabRight, &renderer, width, height)
+    #[test]
+    fn marks_paragraphs_changed_since_last_snapshot() {
+        let dir = TempDir::new("changes").expect("temp dir");
+        let width: u16 = 80;
+        let height: usize = 10;
+        let old = LinesRenderer::new(vec!["NAME".to_string(), "  tool".to_string()]);
+        let mut app = App::empty();
+        app.track_changes(SnapshotStore::new(dir.path()));
+        app.open_pages(vec!["tool".to_string()], None, &old, width, height)
             .unwrap();
-        assert_eq!(app.active, 1);
+        assert!(app.changed_lines().is_empty());
+
+        let new = LinesRenderer::new(vec![
+            "NAME".to_string(),
+            "  tool".to_string(),
+            String::new(),
+            "  --new-flag".to_string(),
+        ]);
+        let mut app = App::empty();
+        app.track_changes(SnapshotStore::new(dir.path()));
+        app.open_pages(vec!["tool".to_string()], None, &new, width, height)
+            .unwrap();
+        assert_eq!(app.changed_lines(), [3]);
     }
 
     #[test]
-    fn search_centers_and_navigates() {
-        let mut lines = Vec::new();
-        for idx in 0..40 {
-            if idx == 10 || idx == 30 {
-                lines.push(format!("foo line {idx}"));
-            } else {
-                lines.push(format!("line {idx}"));
-            }
-        }
+    fn mouse_drag_selects_and_copies_text() {
+        let lines: Vec<String> = (0..20).map(|idx| format!("line {idx}")).collect();
         let renderer = LinesRenderer::new(lines);
         let mut app = App::new("open", None);
+        app.resize_active(&renderer, 80, 5).unwrap();
+        app.update(Action::ScrollDown(2), &renderer, 80, 5).unwrap();
+        app.update(Action::MousePress(2, 1), &renderer, 80, 5)
+            .unwrap();
+        app.update(Action::MouseDrag(3, 2), &renderer, 80, 5)
+            .unwrap();
+        assert_eq!(app.text_selection(), Some(((2, 2), (3, 3))));
+        app.update(Action::MouseRelease(3, 2), &renderer, 80, 5)
+            .unwrap();
+        assert_eq!(
+            app.drain_effects(),
+            vec![Effect::Copy("ne 2\nline".to_string())]
+        );
+        assert_eq!(app.status_message(), Some("Copied 9 characters"));
+
+        app.update(Action::MousePress(0, 0), &renderer, 80, 5)
+            .unwrap();
+        assert_eq!(app.text_selection(), None);
+        app.update(Action::MousePress(2, 3), &renderer, 80, 5)
+            .unwrap();
+        app.update(Action::MouseRelease(2, 3), &renderer, 80, 5)
+            .unwrap();
+        assert_eq!(app.text_selection(), None);
+        assert!(app.drain_effects().is_empty());
+    }
+
+    #[test]
+    fn visual_mode_extends_yanks_and_opens_references() {
+        let mut lines: Vec<String> = (0..20).map(|idx| format!("line {idx}")).collect();
+        lines[2] = "       see read(2) and write(2)".to_string();
+        let renderer = LinesRenderer::new(lines);
         let width: u16 = 80;
-        let height: usize = 10;
-        app.update(
-            Action::Resize(width, height as u16),
-            &renderer,
-            width,
-            height,
-        )
-        .unwrap();
+        let height: usize = 5;
+        let mut app = App::new("open", None);
+        app.resize_active(&renderer, width, height).unwrap();
+        app.update(Action::EnterVisual, &renderer, width, height)
+            .unwrap();
+        app.update(Action::VisualDown, &renderer, width, height)
+            .unwrap();
+        assert_eq!(app.selection(), Some((0, 1)));
+        app.update(Action::VisualYank, &renderer, width, height)
+            .unwrap();
+        assert_eq!(
+            app.drain_effects(),
+            vec![Effect::Copy("line 0\nline 1".to_string())]
+        );
+        assert_eq!(app.mode(), &Mode::Normal);
 
-        app.update(Action::EnterSearchMode, &renderer, width, height)
+        app.update(Action::EnterVisual, &renderer, width, height)
             .unwrap();
-        for ch in "foo".chars() {
-            app.update(Action::SearchChar(ch), &renderer, width, height)
+        for _ in 0..6 {
+            app.update(Action::VisualDown, &renderer, width, height)
                 .unwrap();
         }
-        app.update(Action::SearchSubmit, &renderer, width, height)
+        assert_eq!(app.selection(), Some((0, 6)));
+        assert_eq!(app.scroll(), 2);
+        app.update(Action::VisualOpen, &renderer, width, height)
             .unwrap();
-        assert_eq!(app.scroll(), 5);
+        assert_eq!(app.tabs.len(), 2);
+        assert_eq!(app.title(), "read(2)");
+    }
 
-        app.update(Action::SearchNext, &renderer, width, height)
+    #[test]
+    fn looks_up_the_selected_word() {
+        let lines = vec!["NAME".to_string(), "   close and read(2)".to_string()];
+        let renderer = LinesRenderer::new(lines);
+        let mut app = App::new("open", None);
+        app.resize_active(&renderer, 80, 10).unwrap();
+        let send = |app: &mut App, action| {
+            app.update(action, &renderer, 80, 10).unwrap();
+        };
+        send(&mut app, Action::LookupSelection);
+        assert_eq!(
+            app.status_message(),
+            Some("Select a word first, or use c and K")
+        );
+        send(&mut app, Action::EnterVisual);
+        send(&mut app, Action::VisualDown);
+        send(&mut app, Action::LookupSelection);
+        assert_eq!(app.mode(), &Mode::Normal);
+        assert_eq!(app.title(), "read(2)");
+
+        send(&mut app, Action::TabLeft);
+        send(&mut app, Action::EnterVisual);
+        send(&mut app, Action::LookupSelection);
+        assert_eq!(app.title(), "NAME");
+        assert_eq!(app.tabs.len(), 3);
+    }
+
+    struct ChannelRenderer {
+        streams: Cell<usize>,
+    }
+
+    impl ManRenderer for ChannelRenderer {
+        fn render(
+            &self,
+            name: &str,
+            _section: Option<&str>,
+            width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Ok(vec![format!("{name}:{width}"); 50])
+        }
+
+        fn render_stream(
+            &self,
+            name: &str,
+            _section: Option<&str>,
+            width: u16,
+        ) -> Result<Option<Receiver<RenderChunk>>, RenderError> {
+            if name == "local" {
+                return Ok(None);
+            }
+            self.streams.set(self.streams.get() + 1);
+            let (sender, receiver) = std::sync::mpsc::channel();
+            let _ = sender.send(RenderChunk::Lines(vec![format!("{name}:{width}"); 50]));
+            let _ = sender.send(RenderChunk::Done(Ok(())));
+            Ok(Some(receiver))
+        }
+    }
+
+    #[test]
+    fn prerenders_inactive_tabs_after_resize() {
+        let renderer = ChannelRenderer {
+            streams: Cell::new(0),
+        };
+        let mut app = App::empty();
+        app.open_pages(
+            vec!["open".to_string(), "local".to_string(), "read".to_string()],
+            None,
+            &renderer,
+            80,
+            10,
+        )
+        .unwrap();
+        assert_eq!(app.title(), "read");
+        assert!(!app.prerender_pending());
+        app.update(Action::Resize(60, 10), &renderer, 60, 10)
             .unwrap();
-        assert_eq!(app.scroll(), 25);
+        assert_eq!(renderer.streams.get(), 3);
+        assert!(app.prerender_pending());
 
-        app.update(Action::SearchClear, &renderer, width, height)
+        assert!(app.prerender_next(&renderer));
+        assert!(app.streaming());
+        assert!(!app.prerender_next(&renderer));
+        assert!(app.receive_streams(10));
+        assert!(app.prerender_next(&renderer));
+        assert!(!app.prerender_pending());
+        assert_eq!(renderer.streams.get(), 4);
+        assert_eq!(app.tabs()[0].lines()[0], "open:60");
+        assert_eq!(app.tabs()[1].lines()[0], "local:80");
+
+        app.set_screen_width(81);
+        app.execute_command(parse_command("split open"), &renderer, 60, 10)
             .unwrap();
-        let scroll = app.scroll();
-        app.update(Action::SearchNext, &renderer, width, height)
+        app.update(Action::Resize(100, 10), &renderer, 100, 10)
             .unwrap();
-        assert_eq!(app.scroll(), scroll);
+        let visible = app.layout().expect("split").tabs();
+        let hidden = (0..app.tabs().len())
+            .find(|index| !visible.contains(index) && app.tabs()[*index].name() != "local")
+            .expect("hidden tab");
+        while app.prerender_next(&renderer) {
+            app.receive_streams(10);
+        }
+        assert!(!app.prerender_pending());
+        assert_eq!(
+            app.tabs()[hidden].lines()[0],
+            format!("{}:50", app.tabs()[hidden].name())
+        );
     }
 
     #[test]
-    fn wipe_closes_active_tab_and_handles_empty() {
+    fn prerender_skips_follow_pages_when_tabs_move() {
+        let renderer = ChannelRenderer {
+            streams: Cell::new(0),
+        };
+        let mut app = App::empty();
+        app.open_pages(
+            vec!["local".to_string(), "open".to_string(), "read".to_string()],
+            None,
+            &renderer,
+            80,
+            10,
+        )
+        .unwrap();
+        app.update(Action::Resize(60, 10), &renderer, 60, 10)
+            .unwrap();
+        assert!(app.prerender_next(&renderer));
+        assert!(!app.streaming());
+
+        app.execute_command(parse_command("pin"), &renderer, 60, 10)
+            .unwrap();
+        assert_eq!(app.tabs()[1].name(), "local");
+        assert!(app.prerender_next(&renderer));
+        assert!(app.tabs()[2].is_streaming());
+        assert!(app.receive_streams(10));
+        assert!(!app.prerender_pending());
+    }
+
+    #[test]
+    fn memory_budget_releases_least_recent_tabs() {
         let renderer = StubRenderer::new();
+        let mut app = App::empty();
+        app.open_pages(
+            vec!["open".to_string(), "read".to_string(), "write".to_string()],
+            None,
+            &renderer,
+            80,
+            10,
+        )
+        .unwrap();
+        app.update(Action::TabLeft, &renderer, 80, 10).unwrap();
+        app.update(Action::TabLeft, &renderer, 80, 10).unwrap();
+        let page_size = app.tabs[0].memory_usage();
+        app.set_memory_budget(Some(page_size * 2));
+        app.update(Action::TabRight, &renderer, 80, 10).unwrap();
+        assert_eq!(app.tabs[0].line_count(), 50);
+        assert_eq!(app.tabs[1].line_count(), 50);
+        assert_eq!(app.tabs[2].line_count(), 0);
+
+        app.update(Action::TabRight, &renderer, 80, 10).unwrap();
+        assert_eq!(app.tabs[0].line_count(), 0);
+        assert_eq!(app.tabs[2].line_count(), 50);
+        assert!(app.memory_usage() <= page_size * 2);
+    }
+
+    #[test]
+    fn low_memory_keeps_page_lines_on_disk() {
+        let renderer = LinesRenderer::new((0..100).map(|line| format!("line {line}")).collect());
         let width: u16 = 80;
         let height: usize = 10;
         let mut app = App::empty();
-        app.update(
-            Action::Resize(width, height as u16),
+        app.set_low_memory(true);
+        app.open_pages(
+            vec!["open".to_string(), "read".to_string()],
+            None,
             &renderer,
             width,
             height,
         )
         .unwrap();
-        app.update(Action::EnterCommandMode, &renderer, width, height)
+        assert!(app.tabs.iter().all(ManPage::is_spooled));
+        assert_eq!(app.tabs[0].line_count(), 100);
+        assert!(app.tabs[0].lines().is_empty());
+        assert_eq!(app.window().0, 0);
+        assert_eq!(app.lines().len(), 20);
+        assert_eq!(app.lines()[0], "line 0");
+
+        app.update(Action::ScrollDown(50), &renderer, width, height)
             .unwrap();
-        for ch in "wipe".chars() {
-            app.update(Action::CommandChar(ch), &renderer, width, height)
-                .unwrap();
+        assert_eq!(app.window().0, 40);
+        assert_eq!(app.lines()[10], "line 50");
+        assert_eq!(app.source_lines().len(), 100);
+        assert_eq!(app.source_lines()[0], "line 0");
+        assert_eq!(app.tabs[1].widest_line(), "line 99".len());
+        let mut search = vec![Action::EnterSearchMode];
+        search.extend("95".chars().map(Action::SearchChar));
+        for action in search {
+            app.update(action, &renderer, width, height).unwrap();
         }
-        app.update(Action::CommandSubmit, &renderer, width, height)
+        assert!(app.tabs[1].search_matches().is_empty());
+        app.update(Action::SearchSubmit, &renderer, width, height)
             .unwrap();
-        assert_eq!(app.tabs.len(), 0);
+        assert_eq!(app.tabs[1].search_matches().len(), 1);
+        assert_eq!(app.scroll(), 90);
+        assert_eq!(app.window().0, 80);
+        assert_eq!(app.lines()[15], "line 95");
 
-        let mut app = App::new("open", None);
-        app.update(Action::EnterCommandMode, &renderer, width, height)
+        app.update(Action::TabLeft, &renderer, width, height)
             .unwrap();
-        for ch in "man ls".chars() {
-            app.update(Action::CommandChar(ch), &renderer, width, height)
-                .unwrap();
-        }
-        app.update(Action::CommandSubmit, &renderer, width, height)
+        assert_eq!(app.lines()[0], "line 0");
+        assert!(app.tabs[1].lines().is_empty());
+        assert_eq!(app.tabs[1].scroll, 90);
+        app.update(Action::TabRight, &renderer, width, height)
             .unwrap();
-        assert_eq!(app.tabs.len(), 2);
-        assert_eq!(app.active, 1);
+        assert_eq!(app.lines()[10], "line 90");
+        assert!(app.memory_usage() < 100 * std::mem::size_of::<String>() * 3);
+    }
 
-        app.update(Action::EnterCommandMode, &renderer, width, height)
+    #[test]
+    fn low_memory_streams_pages_into_the_spool() {
+        let (sender, receiver) = mpsc::channel();
+        let renderer = StreamingRenderer {
+            stream: RefCell::new(Some(receiver)),
+        };
+        let page = [
+            "NAME",
+            "       tool - does things",
+            "",
+            "OPTIONS",
+            "       --fast",
+            "              go fast",
+        ];
+        sender
+            .send(RenderChunk::Lines(
+                page.iter().map(|line| line.to_string()).collect(),
+            ))
             .unwrap();
-        for ch in "w".chars() {
-            app.update(Action::CommandChar(ch), &renderer, width, height)
-                .unwrap();
-        }
-        app.update(Action::CommandSubmit, &renderer, width, height)
+        sender
+            .send(RenderChunk::Lines(
+                (0..200)
+                    .map(|line| format!("       filler {line}"))
+                    .collect(),
+            ))
             .unwrap();
-        assert_eq!(app.tabs.len(), 1);
-        assert_eq!(app.active, 0);
-        assert_eq!(app.title(), "open");
+        sender.send(RenderChunk::Done(Ok(()))).unwrap();
+        let mut app = App::new("tool", None);
+        app.set_low_memory(true);
+        app.resize_active(&renderer, 80, 10).unwrap();
+        assert!(!app.streaming());
+        assert!(app.tabs[0].is_spooled());
+        assert_eq!(app.line_count(), 206);
+        assert_eq!(app.lines().len(), 20);
+        assert_eq!(app.tabs[0].headings(), [0, 3]);
+        assert_eq!(app.tabs[0].aliases(), ["tool"]);
+        assert_eq!(app.tabs[0].option_line("fast"), Some(4));
+        assert_eq!(app.tabs[0].section_at(150).as_deref(), Some("OPTIONS"));
+
+        app.execute_command(parse_command("goto --fast"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(app.scroll(), 4);
+        app.update(Action::ToggleFold, &renderer, 80, 10).unwrap();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Folding is not available with --low-mem")
+        );
     }
 
     #[test]
-    fn man_command_sets_status_on_missing_page() {
-        let renderer = FailingRenderer;
+    fn pager_command_writes_rendered_text_to_tempfile() {
+        let renderer = LinesRenderer::new(vec!["NAME".to_string(), "  ls".to_string()]);
         let width: u16 = 80;
         let height: usize = 10;
-        let mut app = App::new("open", None);
+        let mut app = App::new("ls", Some("1".to_string()));
+        app.resize_active(&renderer, width, height).unwrap();
         app.update(Action::EnterCommandMode, &renderer, width, height)
             .unwrap();
-        for ch in "man seek".chars() {
+        for ch in "pager".chars() {
             app.update(Action::CommandChar(ch), &renderer, width, height)
                 .unwrap();
         }
         app.update(Action::CommandSubmit, &renderer, width, height)
             .unwrap();
-        assert_eq!(app.tabs.len(), 1);
-        assert_eq!(app.active, 0);
-        assert_eq!(app.status_message(), Some("No manual entry for seek"));
+        let effects = app.drain_effects();
+        let [
+            Effect::OpenExternal {
+                tool: ExternalTool::Pager,
+                path,
+                temporary: true,
+            },
+        ] = effects.as_slice()
+        else {
+            panic!("unexpected effects: {effects:?}");
+        };
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "NAME\n  ls\n");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn theme_command_requests_a_variant() {
+        let renderer = StubRenderer::new();
+        let mut app = App::empty();
+        assert_eq!(
+            parse_command("theme light"),
+            ParsedCommand::Theme(ThemeVariant::Light)
+        );
+        assert_eq!(
+            parse_command("theme"),
+            ParsedCommand::Unknown("theme".to_string())
+        );
+        assert_eq!(
+            parse_command("theme dark now"),
+            ParsedCommand::Unknown("theme".to_string())
+        );
+        app.execute_command(parse_command("theme auto"), &renderer, 80, 10)
+            .unwrap();
+        assert_eq!(
+            app.drain_effects(),
+            vec![Effect::SetTheme(ThemeVariant::Auto)]
+        );
+        assert_eq!(
+            app.status_message(),
+            Some("Following the terminal background")
+        );
     }
 }
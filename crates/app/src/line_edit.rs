@@ -0,0 +1,98 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEdit {
+    Insert(char),
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    DeleteWord,
+    KillToStart,
+    KillToEnd,
+}
+
+pub(crate) fn apply_edit(line: &mut String, cursor: usize, edit: LineEdit) -> usize {
+    let len = line.chars().count();
+    let cursor = cursor.min(len);
+    match edit {
+        LineEdit::Insert(ch) => {
+            line.insert(byte_index(line, cursor), ch);
+            cursor + 1
+        }
+        LineEdit::Backspace if cursor > 0 => {
+            remove_range(line, cursor - 1, cursor);
+            cursor - 1
+        }
+        LineEdit::Backspace => cursor,
+        LineEdit::Delete => {
+            remove_range(line, cursor, (cursor + 1).min(len));
+            cursor
+        }
+        LineEdit::Left => cursor.saturating_sub(1),
+        LineEdit::Right => (cursor + 1).min(len),
+        LineEdit::Home => 0,
+        LineEdit::End => len,
+        LineEdit::DeleteWord => {
+            let start = word_start(line, cursor);
+            remove_range(line, start, cursor);
+            start
+        }
+        LineEdit::KillToStart => {
+            remove_range(line, 0, cursor);
+            0
+        }
+        LineEdit::KillToEnd => {
+            remove_range(line, cursor, len);
+            cursor
+        }
+    }
+}
+
+fn byte_index(line: &str, cursor: usize) -> usize {
+    line.char_indices()
+        .nth(cursor)
+        .map_or(line.len(), |(index, _)| index)
+}
+
+fn remove_range(line: &mut String, start: usize, end: usize) {
+    let range = byte_index(line, start)..byte_index(line, end);
+    line.replace_range(range, "");
+}
+
+fn word_start(line: &str, cursor: usize) -> usize {
+    let chars: Vec<char> = line.chars().take(cursor).collect();
+    let mut start = chars.len();
+    while start > 0 && chars[start - 1].is_whitespace() {
+        start -= 1;
+    }
+    while start > 0 && !chars[start - 1].is_whitespace() {
+        start -= 1;
+    }
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_at_the_cursor() {
+        let mut line = "man ls".to_string();
+        let cursor = apply_edit(&mut line, 6, LineEdit::Home);
+        let cursor = apply_edit(&mut line, cursor, LineEdit::Right);
+        let cursor = apply_edit(&mut line, cursor, LineEdit::Insert('é'));
+        assert_eq!((line.as_str(), cursor), ("méan ls", 2));
+        let cursor = apply_edit(&mut line, cursor, LineEdit::Backspace);
+        let cursor = apply_edit(&mut line, cursor, LineEdit::Delete);
+        assert_eq!((line.as_str(), cursor), ("mn ls", 1));
+        let cursor = apply_edit(&mut line, cursor, LineEdit::End);
+        let cursor = apply_edit(&mut line, cursor, LineEdit::DeleteWord);
+        assert_eq!((line.as_str(), cursor), ("mn ", 3));
+        let cursor = apply_edit(&mut line, cursor, LineEdit::Left);
+        let cursor = apply_edit(&mut line, cursor, LineEdit::KillToStart);
+        assert_eq!((line.as_str(), cursor), (" ", 0));
+        let cursor = apply_edit(&mut line, cursor, LineEdit::KillToEnd);
+        assert_eq!((line.as_str(), cursor), ("", 0));
+    }
+}
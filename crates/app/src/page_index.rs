@@ -0,0 +1,132 @@
+use render::PageEntry;
+
+#[derive(Debug, Clone, Default)]
+pub struct PageIndex {
+    entries: Vec<PageEntry>,
+}
+
+impl PageIndex {
+    pub fn new(mut entries: Vec<PageEntry>) -> Self {
+        entries.sort_by(|a, b| (&a.name, &a.section).cmp(&(&b.name, &b.section)));
+        entries.dedup_by(|a, b| a.name == b.name && a.section == b.section);
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn complete(&self, prefix: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .filter(|name| name.starts_with(prefix))
+            .collect();
+        names.dedup();
+        names
+    }
+
+    pub fn find(&self, query: &str, limit: usize) -> Vec<&PageEntry> {
+        let query = query.to_lowercase();
+        let mut ranked: Vec<(usize, &PageEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                fuzzy_rank(&entry.name.to_lowercase(), &query).map(|rank| (rank, entry))
+            })
+            .collect();
+        ranked.sort_by_key(|(rank, entry)| (*rank, entry.name.len()));
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    pub fn suggest(&self, name: &str) -> Option<&str> {
+        let limit = (name.chars().count() / 3).clamp(1, 2);
+        self.entries
+            .iter()
+            .filter(|entry| entry.name != name)
+            .map(|entry| (edit_distance(&entry.name, name), entry.name.as_str()))
+            .filter(|(distance, _)| *distance <= limit)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, name)| name)
+    }
+}
+
+fn fuzzy_rank(name: &str, query: &str) -> Option<usize> {
+    if name == query {
+        Some(0)
+    } else if name.starts_with(query) {
+        Some(1)
+    } else if name.contains(query) {
+        Some(2)
+    } else {
+        let mut chars = name.chars();
+        query
+            .chars()
+            .all(|wanted| chars.any(|ch| ch == wanted))
+            .then_some(3)
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitute.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(names: &[(&str, &str)]) -> PageIndex {
+        PageIndex::new(
+            names
+                .iter()
+                .map(|(name, section)| PageEntry {
+                    name: name.to_string(),
+                    section: section.to_string(),
+                    description: String::new(),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn completes_finds_and_suggests_names() {
+        let index = index(&[
+            ("printf", "3"),
+            ("printf", "1"),
+            ("sprintf", "3"),
+            ("print", "1"),
+            ("lsblk", "8"),
+        ]);
+        assert_eq!(index.len(), 5);
+        assert_eq!(index.complete("pri"), vec!["print", "printf"]);
+        let found: Vec<String> = index
+            .find("prf", 10)
+            .iter()
+            .map(|entry| format!("{}({})", entry.name, entry.section))
+            .collect();
+        assert_eq!(found, vec!["printf(1)", "printf(3)", "sprintf(3)"]);
+        assert_eq!(index.find("printf", 1)[0].name, "printf");
+        assert_eq!(index.suggest("prntf"), Some("printf"));
+        assert_eq!(index.suggest("lsbk"), Some("lsblk"));
+        assert_eq!(index.suggest("grep"), None);
+    }
+}
@@ -0,0 +1,70 @@
+pub type TextPosition = (usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextSelection {
+    pub tab: usize,
+    pub anchor: TextPosition,
+    pub cursor: TextPosition,
+}
+
+impl TextSelection {
+    pub fn new(tab: usize, position: TextPosition) -> Self {
+        Self {
+            tab,
+            anchor: position,
+            cursor: position,
+        }
+    }
+
+    pub fn ordered(&self) -> (TextPosition, TextPosition) {
+        (self.anchor.min(self.cursor), self.anchor.max(self.cursor))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchor == self.cursor
+    }
+
+    pub fn rows(&self) -> std::ops::Range<usize> {
+        let ((first, _), (last, _)) = self.ordered();
+        first..last + 1
+    }
+
+    pub fn text(&self, lines: &[String], first_row: usize) -> String {
+        let ((first, start), (last, end)) = self.ordered();
+        (first_row..)
+            .zip(lines)
+            .take_while(|(row, _)| *row <= last)
+            .skip_while(|(row, _)| *row < first)
+            .map(|(row, line)| {
+                let from = if row == first { start } else { 0 };
+                let to = if row == last { end + 1 } else { usize::MAX };
+                let text: String = line.chars().skip(from).take(to - from).collect();
+                text.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_text_between_positions() {
+        let lines: Vec<String> = ["NAME", "     ls - list   ", "     directory contents"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        let selection = TextSelection {
+            tab: 0,
+            anchor: (2, 14),
+            cursor: (1, 10),
+        };
+        assert_eq!(selection.ordered(), ((1, 10), (2, 14)));
+        assert_eq!(selection.rows(), 1..3);
+        assert_eq!(selection.text(&lines, 0), "list\n     directory");
+        assert_eq!(selection.text(&lines[1..], 1), "list\n     directory");
+        assert_eq!(TextSelection::new(0, (1, 5)).text(&lines, 0), "l");
+    }
+}
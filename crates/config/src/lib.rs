@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Deserializer};
+
+const FILE_NAME: &str = "config.toml";
+const MIN_TICK_MS: u64 = 50;
+const MIN_WIDTH: u16 = 15;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Man,
+    Online,
+}
+
+impl std::str::FromStr for Backend {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "man" => Ok(Backend::Man),
+            "online" => Ok(Backend::Online),
+            _ => Err(ConfigError::Invalid(format!(
+                "unknown backend {value:?} (expected man or online)"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapPreset {
+    #[default]
+    Vim,
+    #[serde(alias = "emacs")]
+    Less,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Background {
+    #[default]
+    Auto,
+    Dark,
+    Light,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub startup_page: Option<String>,
+    pub renderer: Backend,
+    #[serde(deserialize_with = "section_list")]
+    pub section_order: Vec<String>,
+    pub roff_options: Option<String>,
+    pub memory_budget_mb: Option<usize>,
+    pub scroll_step: usize,
+    pub tick_ms: u64,
+    pub width: Option<u16>,
+    pub highlight_code: bool,
+    pub window_title: bool,
+    pub restore_position: bool,
+    pub watch_config: bool,
+    pub ipc: bool,
+    pub mouse: bool,
+    pub keymap: KeymapPreset,
+    pub keys: BTreeMap<String, String>,
+    pub leader: String,
+    pub chords: BTreeMap<String, String>,
+    pub theme: BTreeMap<String, String>,
+    pub highlights: BTreeMap<String, String>,
+    pub search: SearchConfig,
+    pub display: DisplayConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchConfig {
+    pub wrap: bool,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self { wrap: true }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    pub high_contrast: bool,
+    pub reverse_video: bool,
+    pub ascii: bool,
+    pub background: Background,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            high_contrast: false,
+            reverse_video: true,
+            ascii: false,
+            background: Background::Auto,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            startup_page: None,
+            renderer: Backend::Man,
+            section_order: Vec::new(),
+            roff_options: None,
+            memory_budget_mb: None,
+            scroll_step: 1,
+            tick_ms: 500,
+            width: None,
+            highlight_code: true,
+            window_title: true,
+            restore_position: true,
+            watch_config: false,
+            ipc: false,
+            mouse: true,
+            keymap: KeymapPreset::Vim,
+            keys: BTreeMap::new(),
+            leader: "\\".to_string(),
+            chords: BTreeMap::new(),
+            theme: BTreeMap::new(),
+            highlights: BTreeMap::new(),
+            search: SearchConfig::default(),
+            display: DisplayConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "{err}"),
+            ConfigError::Parse { line, message } => write!(f, "line {line}: {message}"),
+            ConfigError::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(value: io::Error) -> Self {
+        ConfigError::Io(value)
+    }
+}
+
+fn section_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Sections {
+        List(Vec<String>),
+        Path(String),
+    }
+    Ok(match Sections::deserialize(deserializer)? {
+        Sections::List(sections) => sections,
+        Sections::Path(path) => path
+            .split(':')
+            .filter(|section| !section.is_empty())
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    store::config_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+pub fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).ok()?.modified().ok()
+}
+
+pub fn load_default() -> Result<Config, ConfigError> {
+    match config_path() {
+        Some(path) => load(&path),
+        None => Ok(Config::default()),
+    }
+}
+
+pub fn load(path: &Path) -> Result<Config, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn parse(text: &str) -> Result<Config, ConfigError> {
+    let config: Config = toml::from_str(text).map_err(|err| ConfigError::Parse {
+        line: err
+            .span()
+            .map_or(1, |span| text[..span.start].matches('\n').count() + 1),
+        message: err.message().trim().to_string(),
+    })?;
+    config.validate()?;
+    Ok(config)
+}
+
+impl Config {
+    fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |message: String| Err(ConfigError::Invalid(message));
+        if self.scroll_step == 0 {
+            return invalid("scroll_step must be at least 1".to_string());
+        }
+        if self.tick_ms < MIN_TICK_MS {
+            return invalid(format!("tick_ms must be at least {MIN_TICK_MS}"));
+        }
+        if self.memory_budget_mb == Some(0) {
+            return invalid("memory_budget_mb must be at least 1".to_string());
+        }
+        if let Some(width) = self.width
+            && width < MIN_WIDTH
+        {
+            return invalid(format!("width must be at least {MIN_WIDTH}"));
+        }
+        if let Some(section) = self.section_order.iter().find(|section| {
+            section.is_empty()
+                || !section
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        }) {
+            return invalid(format!("section_order: invalid section {section:?}"));
+        }
+        if self
+            .startup_page
+            .as_ref()
+            .is_some_and(|page| page.trim().is_empty())
+        {
+            return invalid("startup_page must not be empty".to_string());
+        }
+        if self.highlights.keys().any(|term| term.trim().is_empty()) {
+            return invalid("highlights: terms must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::TempDir;
+
+    #[test]
+    fn parses_every_setting() {
+        let config = parse(
+            r##"
+startup_page = "2 open"
+renderer = "online"
+section_order = ["3", "2", "1"]
+roff_options = "-P -c"
+memory_budget_mb = 64
+scroll_step = 3
+tick_ms = 250
+width = 100
+highlight_code = false
+keymap = "emacs"
+leader = "space"
+
+[keys]
+J = "scroll-down"
+"C-n" = "tab-right"
+
+[chords]
+t = "tab-right"
+
+[theme]
+option = "magenta"
+keyword = "#ffaa00"
+
+[highlights]
+EINTR = "red bold"
+deprecated = "black on yellow"
+
+[search]
+wrap = false
+
+[display]
+high_contrast = true
+reverse_video = false
+ascii = true
+background = "light"
+"##,
+        )
+        .expect("parse");
+        assert_eq!(config.startup_page.as_deref(), Some("2 open"));
+        assert_eq!(config.renderer, Backend::Online);
+        assert_eq!(config.section_order, vec!["3", "2", "1"]);
+        assert_eq!(config.roff_options.as_deref(), Some("-P -c"));
+        assert_eq!(config.memory_budget_mb, Some(64));
+        assert_eq!(config.scroll_step, 3);
+        assert_eq!(config.tick_ms, 250);
+        assert_eq!(config.width, Some(100));
+        assert!(!config.highlight_code);
+        assert_eq!(config.keymap, KeymapPreset::Less);
+        assert!(config.window_title);
+        assert_eq!(config.keys["C-n"], "tab-right");
+        assert_eq!(config.leader, "space");
+        assert_eq!(config.chords["t"], "tab-right");
+        assert_eq!(config.theme["keyword"], "#ffaa00");
+        assert_eq!(config.highlights["EINTR"], "red bold");
+        assert!(!config.search.wrap);
+        assert_eq!(
+            config.display,
+            DisplayConfig {
+                high_contrast: true,
+                reverse_video: false,
+                ascii: true,
+                background: Background::Light,
+            }
+        );
+        assert_eq!(parse("").expect("empty"), Config::default());
+        assert_eq!(
+            "online".parse::<Backend>().expect("backend"),
+            Backend::Online
+        );
+        assert!("groff".parse::<Backend>().is_err());
+        assert_eq!(
+            parse("section_order = \"2:3:1:8\"")
+                .expect("mansect")
+                .section_order,
+            vec!["2", "3", "1", "8"]
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        let err = parse("width = 90\nscroll_step = \"two\"\n").unwrap_err();
+        assert!(matches!(err, ConfigError::Parse { line: 2, .. }), "{err}");
+        let err = parse("colour = \"red\"\n").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("line 1: unknown field `colour`")
+        );
+        let err = parse("renderer = \"groff\"").unwrap_err();
+        assert!(err.to_string().contains("unknown variant `groff`"));
+        assert_eq!(
+            parse("scroll_step = 0").unwrap_err().to_string(),
+            "scroll_step must be at least 1"
+        );
+        assert_eq!(
+            parse("section_order = [\"1\", \"3:2\"]")
+                .unwrap_err()
+                .to_string(),
+            "section_order: invalid section \"3:2\""
+        );
+    }
+
+    #[test]
+    fn missing_file_loads_defaults() {
+        let dir = TempDir::new("config").expect("temp dir");
+        let path = dir.join("missing.toml");
+        assert_eq!(load(&path).expect("defaults"), Config::default());
+    }
+}
@@ -0,0 +1,295 @@
+use app::{Action, Mode};
+use platform::{Event, KeyCode};
+
+use crate::{COLUMN_STEP, map_event};
+
+const DEFAULT_LEADER: char = '\\';
+const LESS_BINDINGS: &[(&str, &str)] = &[
+    ("space", "page-down"),
+    ("b", "page-up"),
+    ("C-v", "page-down"),
+    ("C-f", "page-down"),
+    ("C-b", "page-up"),
+    ("C-d", "half-page-down"),
+    ("C-u", "half-page-up"),
+    ("C-n", "scroll-down"),
+    ("C-p", "scroll-up"),
+    ("e", "scroll-down"),
+    ("y", "scroll-up"),
+    ("enter", "scroll-down"),
+    ("C-s", "search"),
+    ("f", "page-down"),
+    ("<", "top"),
+    (">", "bottom"),
+    ("q", "quit"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    scroll_step: usize,
+    bindings: Vec<(KeyCode, Option<Action>)>,
+    leader: char,
+    chords: Vec<(KeyCode, Option<Action>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Keymap {
+    pub fn new(scroll_step: usize) -> Self {
+        Self {
+            scroll_step: scroll_step.max(1),
+            bindings: Vec::new(),
+            leader: DEFAULT_LEADER,
+            chords: Vec::new(),
+        }
+    }
+
+    pub fn with_less_bindings(mut self) -> Self {
+        for (key, action) in LESS_BINDINGS {
+            self.bind(key, action)
+                .expect("built-in bindings use known keys and actions");
+        }
+        self
+    }
+
+    pub fn bind(&mut self, key: &str, action: &str) -> Result<(), String> {
+        let (code, action) = self.parse_binding(key, action)?;
+        self.bindings.retain(|(bound, _)| *bound != code);
+        self.bindings.push((code, action));
+        Ok(())
+    }
+
+    pub fn set_leader(&mut self, key: &str) -> Result<(), String> {
+        match parse_key(key) {
+            Some(KeyCode::Char(ch)) => {
+                self.leader = ch;
+                Ok(())
+            }
+            _ => Err(format!("leader must be a character key, not {key:?}")),
+        }
+    }
+
+    pub fn bind_chord(&mut self, key: &str, action: &str) -> Result<(), String> {
+        let (code, action) = self.parse_binding(key, action)?;
+        self.chords.retain(|(bound, _)| *bound != code);
+        self.chords.push((code, action));
+        Ok(())
+    }
+
+    pub fn map_event(&self, event: Event, mode: &Mode) -> Option<Action> {
+        if let Event::Key(code) = event
+            && !self.chords.is_empty()
+        {
+            match mode {
+                Mode::Normal if code == KeyCode::Char(self.leader) => {
+                    return Some(Action::BeginPending(self.leader));
+                }
+                Mode::Pending { key } if *key == self.leader => {
+                    return self
+                        .chords
+                        .iter()
+                        .find(|(bound, _)| *bound == code)
+                        .and_then(|(_, action)| *action)
+                        .or(Some(Action::CancelPending));
+                }
+                _ => {}
+            }
+        }
+        if matches!(mode, Mode::Normal)
+            && let Event::Key(code) = event
+            && let Some((_, action)) = self.bindings.iter().find(|(bound, _)| *bound == code)
+        {
+            return *action;
+        }
+        match map_event(event, mode) {
+            Some(Action::ScrollUp(1)) => Some(Action::ScrollUp(self.scroll_step)),
+            Some(Action::ScrollDown(1)) => Some(Action::ScrollDown(self.scroll_step)),
+            action => action,
+        }
+    }
+
+    fn parse_binding(&self, key: &str, action: &str) -> Result<(KeyCode, Option<Action>), String> {
+        let code = parse_key(key).ok_or_else(|| format!("unknown key {key:?}"))?;
+        if action == "none" {
+            return Ok((code, None));
+        }
+        let action = self
+            .parse_action(action)
+            .ok_or_else(|| format!("unknown action {action:?}"))?;
+        Ok((code, Some(action)))
+    }
+
+    fn parse_action(&self, name: &str) -> Option<Action> {
+        Some(match name {
+            "scroll-up" => Action::ScrollUp(self.scroll_step),
+            "scroll-down" => Action::ScrollDown(self.scroll_step),
+            "scroll-left" => Action::ScrollLeft(COLUMN_STEP),
+            "scroll-right" => Action::ScrollRight(COLUMN_STEP),
+            "page-up" => Action::PageUp,
+            "page-down" => Action::PageDown,
+            "half-page-up" => Action::HalfPageUp,
+            "half-page-down" => Action::HalfPageDown,
+            "top" => Action::GoTop,
+            "bottom" => Action::GoBottom,
+            "narrower" => Action::DecreaseWidth,
+            "wider" => Action::IncreaseWidth,
+            "tab-left" => Action::TabLeft,
+            "tab-right" => Action::TabRight,
+            "help" => Action::EnterHelp,
+            "command" => Action::EnterCommandMode,
+            "search" => Action::EnterSearchMode,
+            "search-next" => Action::SearchNext,
+            "search-prev" => Action::SearchPrev,
+            "search-clear" => Action::SearchClear,
+            "matches" => Action::ToggleMatches,
+            "yank" => Action::Yank,
+            "visual" => Action::EnterVisual,
+            "cursor" => Action::EnterCursor,
+            "lookup" => Action::LookupSelection,
+            "hints" => Action::EnterHints,
+            "switch-pane" => Action::SwitchPane,
+            "next-section" => Action::NextSection,
+            "prev-section" => Action::PrevSection,
+            "next-option" => Action::NextOption,
+            "prev-option" => Action::PrevOption,
+            "toggle-fold" => Action::ToggleFold,
+            "fold-all" => Action::FoldAll,
+            "unfold-all" => Action::UnfoldAll,
+            "retry" => Action::RetryRender,
+            "refresh" => Action::Refresh,
+            "zen" => Action::ToggleZen,
+            "scroll-lock" => Action::ToggleScrollLock,
+            "repeat-command" => Action::RepeatCommand,
+            "repeat-search" => Action::RepeatSearch,
+            "source" => Action::ToggleSource,
+            "next-group" => Action::NextGroup,
+            "prev-group" => Action::PrevGroup,
+            "quit" => Action::Quit,
+            _ => match name.strip_prefix("tab-")?.parse::<usize>() {
+                Ok(number) if number > 0 => Action::FocusTab(number - 1),
+                _ => return None,
+            },
+        })
+    }
+}
+
+fn parse_key(spec: &str) -> Option<KeyCode> {
+    let mut chars = spec.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(ch));
+    }
+    if let Some(rest) = spec.strip_prefix("C-") {
+        let mut chars = rest.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(ch), None) if ch.is_ascii_alphabetic() => {
+                Some(KeyCode::Ctrl(ch.to_ascii_lowercase()))
+            }
+            _ => None,
+        };
+    }
+    match spec.to_ascii_lowercase().as_str() {
+        "up" => Some(KeyCode::Up),
+        "down" => Some(KeyCode::Down),
+        "left" => Some(KeyCode::Left),
+        "right" => Some(KeyCode::Right),
+        "home" => Some(KeyCode::Home),
+        "end" => Some(KeyCode::End),
+        "delete" => Some(KeyCode::Delete),
+        "pageup" => Some(KeyCode::PageUp),
+        "pagedown" => Some(KeyCode::PageDown),
+        "enter" => Some(KeyCode::Enter),
+        "tab" => Some(KeyCode::Tab),
+        "backspace" => Some(KeyCode::Backspace),
+        "esc" => Some(KeyCode::Esc),
+        "space" => Some(KeyCode::Char(' ')),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_normal_mode_keys() {
+        let mut keymap = Keymap::new(3);
+        keymap.bind("C-n", "tab-right").expect("bind");
+        keymap.bind("J", "scroll-down").expect("bind");
+        keymap.bind("y", "none").expect("bind");
+        keymap.bind("C-b", "tab-2").expect("bind");
+        let key = |code| keymap.map_event(Event::Key(code), &Mode::Normal);
+        assert_eq!(key(KeyCode::Ctrl('n')), Some(Action::TabRight));
+        assert_eq!(key(KeyCode::Ctrl('b')), Some(Action::FocusTab(1)));
+        assert_eq!(key(KeyCode::Char('J')), Some(Action::ScrollDown(3)));
+        assert_eq!(key(KeyCode::Char('j')), Some(Action::ScrollDown(3)));
+        assert_eq!(key(KeyCode::Char('y')), None);
+        assert_eq!(key(KeyCode::Char('g')), Some(Action::GoTop));
+        assert_eq!(
+            keymap.map_event(
+                Event::Key(KeyCode::Char('y')),
+                &Mode::Visual {
+                    anchor: 0,
+                    cursor: 0
+                }
+            ),
+            Some(Action::VisualYank)
+        );
+    }
+
+    #[test]
+    fn less_bindings_page_and_search() {
+        let keymap = Keymap::new(2).with_less_bindings();
+        let key = |code| keymap.map_event(Event::Key(code), &Mode::Normal);
+        assert_eq!(key(KeyCode::Char(' ')), Some(Action::PageDown));
+        assert_eq!(key(KeyCode::Char('b')), Some(Action::PageUp));
+        assert_eq!(key(KeyCode::Ctrl('n')), Some(Action::ScrollDown(2)));
+        assert_eq!(key(KeyCode::Ctrl('p')), Some(Action::ScrollUp(2)));
+        assert_eq!(key(KeyCode::Ctrl('s')), Some(Action::EnterSearchMode));
+        assert_eq!(key(KeyCode::Char('q')), Some(Action::Quit));
+        assert_eq!(key(KeyCode::Char('j')), Some(Action::ScrollDown(2)));
+    }
+
+    #[test]
+    fn resolves_leader_chords() {
+        let mut keymap = Keymap::default();
+        keymap.set_leader("space").expect("leader");
+        keymap.bind_chord("t", "tab-right").expect("chord");
+        keymap.bind_chord("f", "fold-all").expect("chord");
+        let key = |code, mode: &Mode| keymap.map_event(Event::Key(code), mode);
+        let pending = Mode::Pending { key: ' ' };
+        assert_eq!(
+            key(KeyCode::Char(' '), &Mode::Normal),
+            Some(Action::BeginPending(' '))
+        );
+        assert_eq!(key(KeyCode::Char('t'), &pending), Some(Action::TabRight));
+        assert_eq!(
+            key(KeyCode::Char('x'), &pending),
+            Some(Action::CancelPending)
+        );
+        assert_eq!(
+            key(KeyCode::Char('a'), &Mode::Pending { key: 'z' }),
+            Some(Action::ToggleFold)
+        );
+        assert_eq!(
+            keymap.set_leader("C-x"),
+            Err("leader must be a character key, not \"C-x\"".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_actions() {
+        let mut keymap = Keymap::default();
+        assert_eq!(
+            keymap.bind("hyper-x", "top"),
+            Err("unknown key \"hyper-x\"".to_string())
+        );
+        assert_eq!(
+            keymap.bind("x", "explode"),
+            Err("unknown action \"explode\"".to_string())
+        );
+    }
+}
@@ -1,5 +1,13 @@
-use app::{Action, Mode};
-use platform::{Event, KeyCode};
+use app::{Action, CursorMotion, LineEdit, Mode, SplitDirection};
+use platform::{Event, KeyCode, MouseKind};
+
+mod keymap;
+
+pub use keymap::Keymap;
+
+const PANE_STEP: i16 = 5;
+const WHEEL_STEP: usize = 3;
+const COLUMN_STEP: usize = 8;
 
 pub fn map_event(event: Event, mode: &Mode) -> Option<Action> {
     match event {
@@ -15,16 +23,48 @@ pub fn map_event(event: Event, mode: &Mode) -> Option<Action> {
                 KeyCode::Char('-') => Some(Action::DecreaseWidth),
                 KeyCode::Char('+') => Some(Action::IncreaseWidth),
                 KeyCode::Char('b') => Some(Action::PageUp),
-                KeyCode::Char('f') => Some(Action::PageDown),
+                KeyCode::Char('f') => Some(Action::EnterHints),
+                KeyCode::Ctrl('f') => Some(Action::PageDown),
+                KeyCode::Ctrl('b') => Some(Action::PageUp),
                 KeyCode::Char('H') => Some(Action::TabLeft),
                 KeyCode::Char('L') => Some(Action::TabRight),
                 KeyCode::Char('?') => Some(Action::EnterHelp),
                 KeyCode::Char(':') => Some(Action::EnterCommandMode),
                 KeyCode::Char('/') => Some(Action::EnterSearchMode),
                 KeyCode::Char('n') => Some(Action::SearchNext),
-                KeyCode::Char('p') => Some(Action::SearchPrev),
+                KeyCode::Char('p') | KeyCode::Char('N') => Some(Action::SearchPrev),
+                KeyCode::Char('y') => Some(Action::Yank),
+                KeyCode::Char('V') => Some(Action::EnterVisual),
+                KeyCode::Char('c') => Some(Action::EnterCursor),
+                KeyCode::Char('M') => Some(Action::ToggleMatches),
+                KeyCode::Char('r') => Some(Action::RetryRender),
+                KeyCode::Char('R') => Some(Action::Refresh),
+                KeyCode::Char('Z') => Some(Action::ToggleZen),
+                KeyCode::Char(digit @ '1'..='9') => digit
+                    .to_digit(10)
+                    .map(|digit| Action::CountDigit(digit as usize)),
+                KeyCode::Char('.') => Some(Action::RepeatCommand),
+                KeyCode::Char('&') => Some(Action::RepeatSearch),
+                KeyCode::Char('S') => Some(Action::ToggleSource),
+                KeyCode::Char('K') => Some(Action::LookupSelection),
+                KeyCode::Ctrl('w') => Some(Action::SwitchPane),
+                KeyCode::Ctrl('n') => Some(Action::NextGroup),
+                KeyCode::Ctrl('p') => Some(Action::PrevGroup),
+                KeyCode::Char(key @ (']' | '[' | 'z')) => Some(Action::BeginPending(key)),
+                KeyCode::Char('<') => {
+                    Some(Action::ResizePane(SplitDirection::Horizontal, -PANE_STEP))
+                }
+                KeyCode::Char('>') => {
+                    Some(Action::ResizePane(SplitDirection::Horizontal, PANE_STEP))
+                }
+                KeyCode::Char('{') => {
+                    Some(Action::ResizePane(SplitDirection::Vertical, -PANE_STEP))
+                }
+                KeyCode::Char('}') => Some(Action::ResizePane(SplitDirection::Vertical, PANE_STEP)),
                 KeyCode::Up => Some(Action::ScrollUp(1)),
                 KeyCode::Down => Some(Action::ScrollDown(1)),
+                KeyCode::Left => Some(Action::ScrollLeft(COLUMN_STEP)),
+                KeyCode::Right => Some(Action::ScrollRight(COLUMN_STEP)),
                 KeyCode::PageUp => Some(Action::PageUp),
                 KeyCode::PageDown => Some(Action::PageDown),
                 KeyCode::Esc => Some(Action::SearchClear),
@@ -33,30 +73,134 @@ pub fn map_event(event: Event, mode: &Mode) -> Option<Action> {
             Mode::Command { .. } => match code {
                 KeyCode::Esc | KeyCode::Ctrl('c') => Some(Action::CommandCancel),
                 KeyCode::Enter => Some(Action::CommandSubmit),
+                KeyCode::Tab => Some(Action::CommandComplete),
                 KeyCode::Backspace => Some(Action::CommandBackspace),
-                KeyCode::Char(value) if value == ' ' || value.is_ascii_graphic() => {
-                    Some(Action::CommandChar(value))
-                }
-                _ => None,
+                KeyCode::Char(value) if !value.is_control() => Some(Action::CommandChar(value)),
+                code => line_edit(code).map(Action::EditLine),
             },
             Mode::Search { .. } => match code {
                 KeyCode::Esc | KeyCode::Ctrl('c') => Some(Action::SearchCancel),
+                KeyCode::Ctrl('s') => Some(Action::SearchToggleScope),
+                KeyCode::Up => Some(Action::SearchHistoryPrev),
+                KeyCode::Down => Some(Action::SearchHistoryNext),
                 KeyCode::Enter => Some(Action::SearchSubmit),
                 KeyCode::Backspace => Some(Action::SearchBackspace),
-                KeyCode::Char(value) if value == ' ' || value.is_ascii_graphic() => {
-                    Some(Action::SearchChar(value))
-                }
-                _ => None,
+                KeyCode::Char(value) if !value.is_control() => Some(Action::SearchChar(value)),
+                code => line_edit(code).map(Action::EditLine),
             },
             Mode::Help => match code {
                 KeyCode::Char('q') => Some(Action::ExitHelp),
                 _ => None,
             },
+            Mode::Visual { .. } => match code {
+                KeyCode::Char('k') | KeyCode::Up => Some(Action::VisualUp),
+                KeyCode::Char('j') | KeyCode::Down => Some(Action::VisualDown),
+                KeyCode::Char('y') => Some(Action::VisualYank),
+                KeyCode::Char('o') | KeyCode::Enter => Some(Action::VisualOpen),
+                KeyCode::Char('K') => Some(Action::LookupSelection),
+                KeyCode::Esc | KeyCode::Char('V') | KeyCode::Ctrl('c') => Some(Action::ExitVisual),
+                _ => None,
+            },
+            Mode::Cursor { .. } => match code {
+                KeyCode::Char('h') | KeyCode::Left => Some(Action::MoveCursor(CursorMotion::Left)),
+                KeyCode::Char('l') | KeyCode::Right => {
+                    Some(Action::MoveCursor(CursorMotion::Right))
+                }
+                KeyCode::Char('k') | KeyCode::Up => Some(Action::MoveCursor(CursorMotion::Up)),
+                KeyCode::Char('j') | KeyCode::Down => Some(Action::MoveCursor(CursorMotion::Down)),
+                KeyCode::Char('w') => Some(Action::MoveCursor(CursorMotion::WordForward)),
+                KeyCode::Char('b') => Some(Action::MoveCursor(CursorMotion::WordBack)),
+                KeyCode::Char('0') | KeyCode::Home => {
+                    Some(Action::MoveCursor(CursorMotion::LineStart))
+                }
+                KeyCode::Char('$') | KeyCode::End => {
+                    Some(Action::MoveCursor(CursorMotion::LineEnd))
+                }
+                KeyCode::Enter => Some(Action::CursorOpen),
+                KeyCode::Char('y') => Some(Action::CursorYank),
+                KeyCode::Char('K') => Some(Action::CursorLookup),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('c') | KeyCode::Ctrl('c') => {
+                    Some(Action::ExitCursor)
+                }
+                _ => None,
+            },
+            Mode::Hints { .. } => match code {
+                KeyCode::Char(ch) if ch.is_ascii_lowercase() => Some(Action::HintChar(ch)),
+                _ => Some(Action::ExitHints),
+            },
+            Mode::Picker { .. } => match code {
+                KeyCode::Char('k') | KeyCode::Up => Some(Action::PickerUp),
+                KeyCode::Char('j') | KeyCode::Down => Some(Action::PickerDown),
+                KeyCode::Enter => Some(Action::PickerSubmit),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Ctrl('c') => {
+                    Some(Action::PickerCancel)
+                }
+                _ => None,
+            },
+            Mode::Matches { .. } => match code {
+                KeyCode::Char('k') | KeyCode::Up => Some(Action::MatchesUp),
+                KeyCode::Char('j') | KeyCode::Down => Some(Action::MatchesDown),
+                KeyCode::Enter
+                | KeyCode::Esc
+                | KeyCode::Char('q')
+                | KeyCode::Char('M')
+                | KeyCode::Ctrl('c') => Some(Action::ToggleMatches),
+                _ => None,
+            },
+            Mode::Confirm { .. } => match code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => Some(Action::ConfirmAccept),
+                KeyCode::Char('n')
+                | KeyCode::Char('N')
+                | KeyCode::Esc
+                | KeyCode::Char('q')
+                | KeyCode::Ctrl('c') => Some(Action::ConfirmReject),
+                _ => None,
+            },
+            Mode::Count { count } => match code {
+                KeyCode::Char('G') => Some(Action::GoToLine(*count)),
+                KeyCode::Char(digit @ '0'..='9') => digit
+                    .to_digit(10)
+                    .map(|digit| Action::CountDigit(digit as usize)),
+                KeyCode::Esc => Some(Action::CancelPending),
+                code => map_event(Event::Key(code), &Mode::Normal),
+            },
+            Mode::Pending { key } => match (key, code) {
+                (']', KeyCode::Char('o')) => Some(Action::NextOption),
+                ('[', KeyCode::Char('o')) => Some(Action::PrevOption),
+                (']', KeyCode::Char(']')) => Some(Action::NextSection),
+                ('[', KeyCode::Char('[')) => Some(Action::PrevSection),
+                ('z', KeyCode::Char('a')) => Some(Action::ToggleFold),
+                ('z', KeyCode::Char('M')) => Some(Action::FoldAll),
+                ('z', KeyCode::Char('R')) => Some(Action::UnfoldAll),
+                _ => Some(Action::CancelPending),
+            },
+        },
+        Event::Mouse(kind, column, row) => match (mode, kind) {
+            (Mode::Normal, MouseKind::Press) => Some(Action::MousePress(column, row)),
+            (Mode::Normal, MouseKind::Drag) => Some(Action::MouseDrag(column, row)),
+            (Mode::Normal, MouseKind::Release) => Some(Action::MouseRelease(column, row)),
+            (Mode::Normal, MouseKind::WheelUp) => Some(Action::ScrollUp(WHEEL_STEP)),
+            (Mode::Normal, MouseKind::WheelDown) => Some(Action::ScrollDown(WHEEL_STEP)),
+            _ => None,
         },
         Event::Unsupported => None,
     }
 }
 
+fn line_edit(code: KeyCode) -> Option<LineEdit> {
+    Some(match code {
+        KeyCode::Left | KeyCode::Ctrl('b') => LineEdit::Left,
+        KeyCode::Right | KeyCode::Ctrl('f') => LineEdit::Right,
+        KeyCode::Home | KeyCode::Ctrl('a') => LineEdit::Home,
+        KeyCode::End | KeyCode::Ctrl('e') => LineEdit::End,
+        KeyCode::Delete | KeyCode::Ctrl('d') => LineEdit::Delete,
+        KeyCode::Ctrl('w') => LineEdit::DeleteWord,
+        KeyCode::Ctrl('u') => LineEdit::KillToStart,
+        KeyCode::Ctrl('k') => LineEdit::KillToEnd,
+        _ => return None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +223,10 @@ mod tests {
             map_event(Event::Key(KeyCode::Char('j')), &Mode::Normal),
             Some(Action::ScrollDown(1))
         );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Right), &Mode::Normal),
+            Some(Action::ScrollRight(COLUMN_STEP))
+        );
     }
 
     #[test]
@@ -96,9 +244,25 @@ mod tests {
             Some(Action::PageUp)
         );
         assert_eq!(
-            map_event(Event::Key(KeyCode::Char('f')), &Mode::Normal),
+            map_event(Event::Key(KeyCode::Ctrl('f')), &Mode::Normal),
             Some(Action::PageDown)
         );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('f')), &Mode::Normal),
+            Some(Action::EnterHints)
+        );
+        let hints = Mode::Hints {
+            typed: String::new(),
+            hints: Vec::new(),
+        };
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('a')), &hints),
+            Some(Action::HintChar('a'))
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Esc), &hints),
+            Some(Action::ExitHints)
+        );
     }
 
     #[test]
@@ -137,6 +301,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn maps_mouse_events() {
+        assert_eq!(
+            map_event(Event::Mouse(MouseKind::Press, 4, 2), &Mode::Normal),
+            Some(Action::MousePress(4, 2))
+        );
+        assert_eq!(
+            map_event(Event::Mouse(MouseKind::WheelDown, 0, 0), &Mode::Normal),
+            Some(Action::ScrollDown(3))
+        );
+        assert_eq!(
+            map_event(Event::Mouse(MouseKind::Drag, 4, 2), &Mode::Help),
+            None
+        );
+    }
+
     #[test]
     fn maps_resize() {
         assert_eq!(
@@ -168,6 +348,10 @@ mod tests {
             map_event(Event::Key(KeyCode::Char('?')), &Mode::Normal),
             Some(Action::EnterHelp)
         );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('y')), &Mode::Normal),
+            Some(Action::Yank)
+        );
     }
 
     #[test]
@@ -211,10 +395,26 @@ mod tests {
             map_event(Event::Key(KeyCode::Enter), &mode),
             Some(Action::CommandSubmit)
         );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Tab), &mode),
+            Some(Action::CommandComplete)
+        );
         assert_eq!(
             map_event(Event::Key(KeyCode::Ctrl('c')), &mode),
             Some(Action::CommandCancel)
         );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Ctrl('a')), &mode),
+            Some(Action::EditLine(LineEdit::Home))
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Left), &mode),
+            Some(Action::EditLine(LineEdit::Left))
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Ctrl('w')), &mode),
+            Some(Action::EditLine(LineEdit::DeleteWord))
+        );
     }
 
     #[test]
@@ -227,6 +427,11 @@ mod tests {
             map_event(Event::Key(KeyCode::Char('a')), &mode),
             Some(Action::SearchChar('a'))
         );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('é')), &mode),
+            Some(Action::SearchChar('é'))
+        );
+        assert_eq!(map_event(Event::Key(KeyCode::Char('\u{7f}')), &mode), None);
         assert_eq!(
             map_event(Event::Key(KeyCode::Backspace), &mode),
             Some(Action::SearchBackspace)
@@ -249,4 +454,141 @@ mod tests {
         );
         assert_eq!(map_event(Event::Key(KeyCode::Char('j')), &Mode::Help), None);
     }
+
+    #[test]
+    fn maps_picker_mode_keys() {
+        let mode = Mode::Picker {
+            title: "Select a section for printf".to_string(),
+            entries: Vec::new(),
+            selected: 0,
+            offset: 0,
+        };
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('j')), &mode),
+            Some(Action::PickerDown)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Up), &mode),
+            Some(Action::PickerUp)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Enter), &mode),
+            Some(Action::PickerSubmit)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Esc), &mode),
+            Some(Action::PickerCancel)
+        );
+    }
+
+    #[test]
+    fn maps_pane_switch() {
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Ctrl('w')), &Mode::Normal),
+            Some(Action::SwitchPane)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('>')), &Mode::Normal),
+            Some(Action::ResizePane(SplitDirection::Horizontal, 5))
+        );
+    }
+
+    #[test]
+    fn maps_visual_mode_keys() {
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('V')), &Mode::Normal),
+            Some(Action::EnterVisual)
+        );
+        let mode = Mode::Visual {
+            anchor: 0,
+            cursor: 0,
+        };
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('j')), &mode),
+            Some(Action::VisualDown)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('y')), &mode),
+            Some(Action::VisualYank)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Enter), &mode),
+            Some(Action::VisualOpen)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('K')), &mode),
+            Some(Action::LookupSelection)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Esc), &mode),
+            Some(Action::ExitVisual)
+        );
+    }
+
+    #[test]
+    fn maps_confirm_mode_keys() {
+        let mode = Mode::Confirm {
+            topic: "tool".to_string(),
+            program: "/usr/bin/tool".into(),
+        };
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('y')), &mode),
+            Some(Action::ConfirmAccept)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Esc), &mode),
+            Some(Action::ConfirmReject)
+        );
+        assert_eq!(map_event(Event::Key(KeyCode::Char('j')), &mode), None);
+    }
+
+    #[test]
+    fn maps_pending_motions() {
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char(']')), &Mode::Normal),
+            Some(Action::BeginPending(']'))
+        );
+        let mode = Mode::Pending { key: '[' };
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('o')), &mode),
+            Some(Action::PrevOption)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('[')), &mode),
+            Some(Action::PrevSection)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('a')), &Mode::Pending { key: 'z' }),
+            Some(Action::ToggleFold)
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('x')), &mode),
+            Some(Action::CancelPending)
+        );
+    }
+
+    #[test]
+    fn counts_prefix_g_with_a_line_number() {
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('4')), &Mode::Normal),
+            Some(Action::CountDigit(4))
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('0')), &Mode::Normal),
+            None
+        );
+        let mode = Mode::Count { count: 4 };
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('0')), &mode),
+            Some(Action::CountDigit(0))
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('G')), &Mode::Count { count: 42 }),
+            Some(Action::GoToLine(42))
+        );
+        assert_eq!(
+            map_event(Event::Key(KeyCode::Char('j')), &mode),
+            Some(Action::ScrollDown(1))
+        );
+    }
 }
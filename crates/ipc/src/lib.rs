@@ -0,0 +1,178 @@
+use std::env;
+use std::path::PathBuf;
+
+#[cfg(unix)]
+mod unix;
+#[cfg(not(unix))]
+mod unsupported;
+
+#[cfg(unix)]
+pub use unix::{IpcServer, send};
+#[cfg(not(unix))]
+pub use unsupported::{IpcServer, send};
+
+const SOCKET_NAME: &str = "manifold.sock";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcCommand {
+    Open {
+        topic: String,
+        section: Option<String>,
+    },
+    FocusTab(usize),
+    Quit,
+}
+
+pub fn parse_command(line: &str) -> Result<IpcCommand, String> {
+    let args = split_args(line)?;
+    let parts: Vec<&str> = args.iter().map(String::as_str).collect();
+    match parts.as_slice() {
+        ["open", topic] => Ok(IpcCommand::Open {
+            topic: topic.to_string(),
+            section: None,
+        }),
+        ["open", topic, section] => Ok(IpcCommand::Open {
+            topic: topic.to_string(),
+            section: Some(section.to_string()),
+        }),
+        ["focus-tab", index] => match index.parse::<usize>() {
+            Ok(index) if index > 0 => Ok(IpcCommand::FocusTab(index)),
+            _ => Err(format!("invalid tab number '{index}'")),
+        },
+        ["quit"] => Ok(IpcCommand::Quit),
+        [] => Err("empty command".to_string()),
+        [command, ..] => Err(format!("unknown command '{command}'")),
+    }
+}
+
+pub fn format_command(command: &IpcCommand) -> String {
+    match command {
+        IpcCommand::Open { topic, section } => match section {
+            Some(section) => format!("open {} {}", quote(topic), quote(section)),
+            None => format!("open {}", quote(topic)),
+        },
+        IpcCommand::FocusTab(index) => format!("focus-tab {index}"),
+        IpcCommand::Quit => "quit".to_string(),
+    }
+}
+
+fn split_args(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(args);
+        }
+        let mut arg = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => arg.push('\n'),
+                        Some(ch @ ('"' | '\\')) => arg.push(ch),
+                        _ => return Err("invalid escape in quoted argument".to_string()),
+                    },
+                    Some(ch) => arg.push(ch),
+                    None => return Err("unterminated quote".to_string()),
+                }
+            }
+            if chars.peek().is_some_and(|ch| !ch.is_whitespace()) {
+                return Err("missing space after closing quote".to_string());
+            }
+        } else {
+            while let Some(ch) = chars.next_if(|ch| !ch.is_whitespace()) {
+                arg.push(ch);
+            }
+        }
+        args.push(arg);
+    }
+}
+
+fn quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.starts_with('"') && !arg.contains(char::is_whitespace) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from('"');
+    for ch in arg.chars() {
+        match ch {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(ch);
+            }
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub fn socket_path() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .map(|dir| dir.join(SOCKET_NAME))
+        .or_else(|| store::state_dir().map(|dir| dir.join(SOCKET_NAME)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            parse_command("open printf 3"),
+            Ok(IpcCommand::Open {
+                topic: "printf".to_string(),
+                section: Some("3".to_string()),
+            })
+        );
+        assert_eq!(parse_command(" focus-tab 2 "), Ok(IpcCommand::FocusTab(2)));
+        assert_eq!(parse_command("quit"), Ok(IpcCommand::Quit));
+        assert_eq!(
+            parse_command("focus-tab 0"),
+            Err("invalid tab number '0'".to_string())
+        );
+        assert_eq!(
+            parse_command("close 1"),
+            Err("unknown command 'close'".to_string())
+        );
+    }
+
+    #[test]
+    fn quotes_arguments_with_spaces() {
+        let open = IpcCommand::Open {
+            topic: "/tmp/my \"pages\"\\tool.1".to_string(),
+            section: None,
+        };
+        let line = format_command(&open);
+        assert_eq!(line, r#"open "/tmp/my \"pages\"\\tool.1""#);
+        assert_eq!(parse_command(&line), Ok(open));
+        assert_eq!(
+            parse_command(r#"open "my page" 3"#),
+            Ok(IpcCommand::Open {
+                topic: "my page".to_string(),
+                section: Some("3".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_command(r#"open a\b"#),
+            Ok(IpcCommand::Open {
+                topic: r#"a\b"#.to_string(),
+                section: None,
+            })
+        );
+        assert_eq!(
+            parse_command(r#"open "ls"#),
+            Err("unterminated quote".to_string())
+        );
+        assert_eq!(
+            parse_command(r#"open "ls"1"#),
+            Err("missing space after closing quote".to_string())
+        );
+        assert_eq!(format_command(&IpcCommand::FocusTab(2)), "focus-tab 2");
+    }
+}
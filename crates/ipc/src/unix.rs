@@ -0,0 +1,127 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use crate::{IpcCommand, parse_command};
+
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct IpcServer {
+    path: PathBuf,
+    commands: Receiver<IpcCommand>,
+}
+
+impl IpcServer {
+    pub fn bind(path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by another instance", path.display()),
+                ));
+            }
+            fs::remove_file(path)?;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        let (sender, commands) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = serve(stream, &sender);
+            }
+        });
+        Ok(Self {
+            path: path.to_path_buf(),
+            commands,
+        })
+    }
+
+    pub fn try_recv(&self) -> Option<IpcCommand> {
+        self.commands.try_recv().ok()
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub fn send(path: &Path, commands: &[String]) -> io::Result<Vec<String>> {
+    let stream = UnixStream::connect(path)?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut replies = Vec::with_capacity(commands.len());
+    for command in commands {
+        writeln!(writer, "{command}")?;
+        let mut reply = String::new();
+        reader.read_line(&mut reply)?;
+        replies.push(reply.trim_end().to_string());
+    }
+    Ok(replies)
+}
+
+fn serve(stream: UnixStream, sender: &Sender<IpcCommand>) -> io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_command(&line) {
+            Ok(command) => {
+                sender
+                    .send(command)
+                    .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+                writeln!(writer, "ok")?;
+            }
+            Err(err) => writeln!(writer, "error: {err}")?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::TempDir;
+
+    #[test]
+    fn forwards_commands_from_clients() {
+        let dir = TempDir::new("ipc").expect("temp dir");
+        let path = dir.join("ipc.sock");
+        let server = IpcServer::bind(&path).expect("bind");
+        assert_eq!(
+            IpcServer::bind(&path).err().map(|err| err.kind()),
+            Some(io::ErrorKind::AddrInUse)
+        );
+        let replies = send(
+            &path,
+            &[
+                "open ls".to_string(),
+                "jump".to_string(),
+                "quit".to_string(),
+            ],
+        )
+        .expect("send");
+        assert_eq!(replies, vec!["ok", "error: unknown command 'jump'", "ok"]);
+        assert_eq!(
+            server.try_recv(),
+            Some(IpcCommand::Open {
+                topic: "ls".to_string(),
+                section: None,
+            })
+        );
+        assert_eq!(server.try_recv(), Some(IpcCommand::Quit));
+        drop(server);
+        assert!(!path.exists());
+    }
+}
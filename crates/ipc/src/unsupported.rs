@@ -0,0 +1,27 @@
+use std::io;
+use std::path::Path;
+
+use crate::IpcCommand;
+
+pub struct IpcServer;
+
+impl IpcServer {
+    pub fn bind(_path: &Path) -> io::Result<Self> {
+        Err(unsupported())
+    }
+
+    pub fn try_recv(&self) -> Option<IpcCommand> {
+        None
+    }
+}
+
+pub fn send(_path: &Path, _commands: &[String]) -> io::Result<Vec<String>> {
+    Err(unsupported())
+}
+
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "control sockets need Unix domain sockets",
+    )
+}
@@ -0,0 +1,58 @@
+use std::collections::HashSet;
+
+pub fn paragraph_set(lines: &[String]) -> HashSet<String> {
+    paragraphs(lines).into_iter().map(|(_, key)| key).collect()
+}
+
+pub fn changed_lines(baseline: &HashSet<String>, lines: &[String]) -> Vec<usize> {
+    paragraphs(lines)
+        .into_iter()
+        .filter(|(_, key)| !baseline.contains(key))
+        .flat_map(|(range, _)| range)
+        .collect()
+}
+
+fn paragraphs(lines: &[String]) -> Vec<(std::ops::Range<usize>, String)> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (index, line) in lines.iter().enumerate() {
+        match (line.trim().is_empty(), start) {
+            (false, None) => start = Some(index),
+            (true, Some(begin)) => {
+                out.push((begin..index, paragraph_key(&lines[begin..index])));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        out.push((begin..lines.len(), paragraph_key(&lines[begin..])));
+    }
+    out
+}
+
+fn paragraph_key(lines: &[String]) -> String {
+    lines
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn marks_new_paragraphs_ignoring_reflow() {
+        let old = lines("NAME\n  tool - does things\n\n  first paragraph\n  wraps here\n");
+        let new = lines(
+            "NAME\n  tool - does things\n\n  first paragraph wraps here\n\n  a new flag\n  --fast\n",
+        );
+        assert_eq!(changed_lines(&paragraph_set(&old), &new), vec![5, 6]);
+    }
+}
@@ -0,0 +1,139 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Same,
+    Added,
+    Removed,
+}
+
+pub fn diff_lines(old: &[String], new: &[String]) -> Vec<(DiffKind, String)> {
+    let old: Vec<&str> = old.iter().map(|line| line.trim_end()).collect();
+    let new: Vec<&str> = new.iter().map(|line| line.trim_end()).collect();
+    let prefix = old
+        .iter()
+        .zip(&new)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let mut out: Vec<(DiffKind, String)> = old[..prefix]
+        .iter()
+        .map(|line| (DiffKind::Same, line.to_string()))
+        .collect();
+    align(
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+        &mut out,
+    );
+    out.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| (DiffKind::Same, line.to_string())),
+    );
+    out
+}
+
+pub fn diff_counts(diff: &[(DiffKind, String)]) -> (usize, usize) {
+    let count = |kind| diff.iter().filter(|(entry, _)| *entry == kind).count();
+    (count(DiffKind::Added), count(DiffKind::Removed))
+}
+
+fn align(old: &[&str], new: &[&str], out: &mut Vec<(DiffKind, String)>) {
+    let removed = |lines: &[&str], out: &mut Vec<(DiffKind, String)>| {
+        out.extend(
+            lines
+                .iter()
+                .map(|line| (DiffKind::Removed, line.to_string())),
+        );
+    };
+    let added = |lines: &[&str], out: &mut Vec<(DiffKind, String)>| {
+        out.extend(lines.iter().map(|line| (DiffKind::Added, line.to_string())));
+    };
+    match old {
+        [] => added(new, out),
+        _ if new.is_empty() => removed(old, out),
+        [line] => match new.iter().position(|candidate| candidate == line) {
+            Some(at) => {
+                added(&new[..at], out);
+                out.push((DiffKind::Same, line.to_string()));
+                added(&new[at + 1..], out);
+            }
+            None => {
+                removed(old, out);
+                added(new, out);
+            }
+        },
+        _ => {
+            let middle = old.len() / 2;
+            let forward = lcs_lengths(&old[..middle], new, false);
+            let backward = lcs_lengths(&old[middle..], new, true);
+            let split = (0..=new.len())
+                .max_by_key(|&at| (forward[at] + backward[new.len() - at], usize::MAX - at))
+                .unwrap_or(0);
+            align(&old[..middle], &new[..split], out);
+            align(&old[middle..], &new[split..], out);
+        }
+    }
+}
+
+fn lcs_lengths(old: &[&str], new: &[&str], reverse: bool) -> Vec<usize> {
+    let at = |len: usize, index: usize| if reverse { len - 1 - index } else { index };
+    let mut row = vec![0; new.len() + 1];
+    for left in 0..old.len() {
+        let mut diagonal = 0;
+        for right in 0..new.len() {
+            let above = row[right + 1];
+            row[right + 1] = if old[at(old.len(), left)] == new[at(new.len(), right)] {
+                diagonal + 1
+            } else {
+                above.max(row[right])
+            };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn aligns_added_and_removed_lines() {
+        let old = lines("NAME\n  open\nERRORS\n  EACCES\n  EINTR\nSEE ALSO");
+        let new = lines("NAME\n  open\nERRORS\n  EACCES\n  EFBIG\nSEE ALSO\n  close(2)");
+        let diff = diff_lines(&old, &new);
+        let rendered: Vec<String> = diff
+            .iter()
+            .map(|(kind, line)| {
+                let marker = match kind {
+                    DiffKind::Same => ' ',
+                    DiffKind::Added => '+',
+                    DiffKind::Removed => '-',
+                };
+                format!("{marker}{line}")
+            })
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                " NAME",
+                "   open",
+                " ERRORS",
+                "   EACCES",
+                "-  EINTR",
+                "+  EFBIG",
+                " SEE ALSO",
+                "+  close(2)",
+            ]
+        );
+        assert_eq!(diff_counts(&diff), (2, 1));
+    }
+}
@@ -0,0 +1,179 @@
+use std::path::Path;
+
+use crate::outline::is_heading;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Text,
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|value| value.to_str())
+            .map(|value| value.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("md" | "markdown") => ExportFormat::Markdown,
+            Some("html" | "htm") => ExportFormat::Html,
+            _ => ExportFormat::Text,
+        }
+    }
+}
+
+pub fn export_lines(title: &str, lines: &[String], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Text => {
+            let mut out = lines.join("\n");
+            out.push('\n');
+            out
+        }
+        ExportFormat::Markdown => export_markdown(title, lines),
+        ExportFormat::Html => export_html(title, lines),
+    }
+}
+
+enum Block<'a> {
+    Heading(&'a str),
+    Body(&'a [String]),
+}
+
+fn blocks(lines: &[String]) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    for (index, line) in lines.iter().enumerate() {
+        if is_heading(line) {
+            push_body(&mut blocks, &lines[start..index]);
+            blocks.push(Block::Heading(line.trim_end()));
+            start = index + 1;
+        }
+    }
+    push_body(&mut blocks, &lines[start..]);
+    blocks
+}
+
+fn push_body<'a>(blocks: &mut Vec<Block<'a>>, body: &'a [String]) {
+    let first = body.iter().position(|line| !line.trim().is_empty());
+    let last = body.iter().rposition(|line| !line.trim().is_empty());
+    if let (Some(first), Some(last)) = (first, last) {
+        blocks.push(Block::Body(&body[first..=last]));
+    }
+}
+
+fn export_markdown(title: &str, lines: &[String]) -> String {
+    let mut out = format!("# {title}\n");
+    for block in blocks(lines) {
+        out.push('\n');
+        match block {
+            Block::Heading(text) => {
+                out.push_str("## ");
+                out.push_str(text);
+                out.push('\n');
+            }
+            Block::Body(body) => {
+                out.push_str("```text\n");
+                for line in body {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                out.push_str("```\n");
+            }
+        }
+    }
+    out
+}
+
+fn export_html(title: &str, lines: &[String]) -> String {
+    let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>{}</title>\n", escape_html(title)));
+    out.push_str("</head>\n<body>\n");
+    out.push_str(&format!("<h1>{}</h1>\n", escape_html(title)));
+    for block in blocks(lines) {
+        match block {
+            Block::Heading(text) => {
+                let id = text.to_ascii_lowercase().replace(' ', "-");
+                out.push_str(&format!(
+                    "<h2 id=\"{}\">{}</h2>\n",
+                    escape_html(&id),
+                    escape_html(text)
+                ));
+            }
+            Block::Body(body) => {
+                out.push_str("<pre>");
+                out.push_str(&escape_html(&body.join("\n")));
+                out.push_str("</pre>\n");
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page() -> Vec<String> {
+        [
+            "NAME",
+            "       ls - list <dir> contents",
+            "",
+            "SYNOPSIS",
+            "       ls [OPTION]...",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn selects_format_by_extension() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("ls.md")),
+            ExportFormat::Markdown
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("ls.HTML")),
+            ExportFormat::Html
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("ls.txt")),
+            ExportFormat::Text
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("ls")), ExportFormat::Text);
+    }
+
+    #[test]
+    fn exports_markdown_with_section_headings() {
+        let out = export_lines("ls(1)", &page(), ExportFormat::Markdown);
+        assert_eq!(
+            out,
+            "# ls(1)\n\n## NAME\n\n```text\n       ls - list <dir> contents\n```\n\n\
+             ## SYNOPSIS\n\n```text\n       ls [OPTION]...\n```\n"
+        );
+    }
+
+    #[test]
+    fn exports_escaped_html() {
+        let out = export_lines("ls(1)", &page(), ExportFormat::Html);
+        assert!(out.contains("<h2 id=\"name\">NAME</h2>"));
+        assert!(out.contains("<pre>       ls - list &lt;dir&gt; contents</pre>"));
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::BTreeSet;
+
+use crate::outline::{is_heading, section_range};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FoldView {
+    pub lines: Vec<String>,
+    pub rows: Vec<usize>,
+    pub markers: Vec<(usize, usize)>,
+}
+
+impl FoldView {
+    pub fn build(lines: &[String], folded: &BTreeSet<String>) -> Self {
+        let mut view = FoldView::default();
+        let mut index = 0;
+        while index < lines.len() {
+            view.rows.push(index);
+            view.lines.push(lines[index].clone());
+            if is_heading(&lines[index]) && folded.contains(lines[index].trim_end()) {
+                let (_, end) = section_range(lines, index);
+                if end > index + 1 {
+                    view.markers.push((view.rows.len() - 1, end - index - 1));
+                }
+                index = end.max(index + 1);
+            } else {
+                index += 1;
+            }
+        }
+        view
+    }
+
+    pub fn row_for(&self, line: usize) -> usize {
+        self.rows
+            .partition_point(|source| *source <= line)
+            .saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hides_folded_section_bodies() {
+        let lines: Vec<String> = [
+            "NAME",
+            "   ls",
+            "ENVIRONMENT",
+            "   LANG",
+            "   TZ",
+            "SEE ALSO",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        let folded = BTreeSet::from(["ENVIRONMENT".to_string()]);
+        let view = FoldView::build(&lines, &folded);
+        assert_eq!(view.lines, vec!["NAME", "   ls", "ENVIRONMENT", "SEE ALSO"]);
+        assert_eq!(view.rows, vec![0, 1, 2, 5]);
+        assert_eq!(view.markers, vec![(2, 2)]);
+        assert_eq!(view.row_for(4), 2);
+        assert_eq!(view.row_for(5), 3);
+    }
+}
@@ -0,0 +1,173 @@
+use std::ops::Range;
+
+use crate::outline::{heading_lines, section_range};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeToken {
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+const KEYWORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case", "esac",
+    "in", "function", "return", "break", "continue", "export", "local", "switch", "default",
+    "goto", "sizeof", "typedef", "struct", "union", "enum", "static", "const", "extern", "void",
+    "int", "char", "long", "short", "unsigned", "signed", "float", "double", "bool", "size_t",
+    "ssize_t", "NULL",
+];
+
+const DIRECTIVES: &[&str] = &[
+    "include", "define", "undef", "if", "ifdef", "ifndef", "elif", "else", "endif", "pragma",
+];
+
+pub fn code_lines(lines: &[String]) -> Vec<usize> {
+    let mut code = Vec::new();
+    for heading in heading_lines(lines) {
+        if !lines[heading].contains("EXAMPLE") {
+            continue;
+        }
+        let (_, end) = section_range(lines, heading);
+        let body = &lines[heading + 1..end];
+        let Some(base) = body
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| indent(line))
+            .min()
+        else {
+            continue;
+        };
+        code.extend(
+            body.iter()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty() && indent(line) > base)
+                .map(|(offset, _)| heading + 1 + offset),
+        );
+    }
+    code
+}
+
+pub fn highlight_code(line: &str) -> Vec<(CodeToken, Range<usize>)> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let at_word_start = index == 0 || !is_word_byte(bytes[index - 1]);
+        let rest = &line[index..];
+        let token = match bytes[index] {
+            b'#' if at_word_start => {
+                let word = word_at(line, index + 1);
+                if DIRECTIVES.contains(&word) {
+                    Some((CodeToken::Keyword, index + 1 + word.len()))
+                } else if index == 0 || bytes[index - 1].is_ascii_whitespace() {
+                    Some((CodeToken::Comment, line.len()))
+                } else {
+                    None
+                }
+            }
+            b'/' if rest.starts_with("//") => Some((CodeToken::Comment, line.len())),
+            b'/' if rest.starts_with("/*") => Some((
+                CodeToken::Comment,
+                rest[2..]
+                    .find("*/")
+                    .map(|end| index + 2 + end + 2)
+                    .unwrap_or(line.len()),
+            )),
+            quote @ (b'"' | b'\'') => Some((CodeToken::String, string_end(bytes, index, quote))),
+            byte if byte.is_ascii_digit() && at_word_start => {
+                Some((CodeToken::Number, index + word_at(line, index).len()))
+            }
+            byte if is_word_byte(byte) && at_word_start => {
+                let word = word_at(line, index);
+                KEYWORDS
+                    .contains(&word)
+                    .then_some((CodeToken::Keyword, index + word.len()))
+            }
+            _ => None,
+        };
+        match token {
+            Some((kind, end)) => {
+                tokens.push((kind, index..end));
+                index = end;
+            }
+            None => index += line[index..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    tokens
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+fn word_at(line: &str, start: usize) -> &str {
+    let end = line[start..]
+        .bytes()
+        .position(|byte| !is_word_byte(byte))
+        .map_or(line.len(), |offset| start + offset);
+    &line[start..end]
+}
+
+fn string_end(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            byte if byte == quote => return index + 1,
+            _ => index += 1,
+        }
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn finds_indented_blocks_in_examples() {
+        let page = lines(
+            "DESCRIPTION\n           not code\nEXAMPLES\n       List files:\n\n           $ ls -l\n\n       Done.\nSEE ALSO\n           ls(1)",
+        );
+        assert_eq!(code_lines(&page), vec![5]);
+    }
+
+    #[test]
+    fn tokenizes_shell_and_c() {
+        let line = "#include <stdio.h>";
+        assert_eq!(highlight_code(line), vec![(CodeToken::Keyword, 0..8)]);
+
+        let line = r#"if [ "$x" = 'a\'b' ]; then echo 42 # note"#;
+        let spans: Vec<(CodeToken, &str)> = highlight_code(line)
+            .into_iter()
+            .map(|(kind, range)| (kind, &line[range]))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (CodeToken::Keyword, "if"),
+                (CodeToken::String, "\"$x\""),
+                (CodeToken::String, "'a\\'b'"),
+                (CodeToken::Keyword, "then"),
+                (CodeToken::Number, "42"),
+                (CodeToken::Comment, "# note"),
+            ]
+        );
+
+        let line = "int fd = open(path, O_RDONLY); /* fd */ x";
+        let spans: Vec<&str> = highlight_code(line)
+            .into_iter()
+            .map(|(_, range)| &line[range])
+            .collect();
+        assert_eq!(spans, vec!["int", "/* fd */"]);
+    }
+}
@@ -1,29 +1,119 @@
-use render::{ManRenderer, RenderError};
+use render::{ManRenderer, RenderChunk, RenderError, display_column, display_width, is_local_page};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashSet};
+use std::io;
+use std::ops::Range;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime};
 
-#[derive(Debug, Clone)]
+mod changes;
+mod diff;
+mod export;
+mod folding;
+mod highlight;
+mod outline;
+mod references;
+mod search;
+mod spool;
+
+pub use changes::{changed_lines, paragraph_set};
+pub use diff::{DiffKind, diff_counts, diff_lines};
+pub use export::{ExportFormat, export_lines};
+use folding::FoldView;
+pub use highlight::{CodeToken, code_lines, highlight_code};
+use outline::alias_mention;
+pub use outline::{
+    alias_anchor, find_option, heading_lines, is_heading, option_definition, option_lines,
+    parse_name_aliases, resolve_anchor, section_lines, section_range,
+};
+pub use references::{Reference, find_references};
+use search::collect_matches;
+pub use search::{KeywordMatch, SearchMatch, keyword_matches};
+use spool::SpooledLines;
+
+const STREAM_WAIT: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    pub renders: usize,
+    pub cache_hits: usize,
+    pub last_render: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchStep {
+    Moved(usize),
+    Wrapped(usize),
+    Stopped,
+}
+
+#[derive(Debug)]
 pub struct RenderCache {
     pub width: u16,
     pub lines: Vec<String>,
+    spool: Option<SpooledLines>,
 }
 
 impl RenderCache {
-    pub fn empty() -> Self {
+    pub fn new(width: u16, lines: Vec<String>) -> Self {
         Self {
-            width: 0,
+            width,
+            lines,
+            spool: None,
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self::new(0, Vec::new())
+    }
+
+    fn spooled(width: u16) -> io::Result<Self> {
+        Ok(Self {
+            width,
             lines: Vec::new(),
+            spool: Some(SpooledLines::create()?),
+        })
+    }
+
+    fn extend(&mut self, lines: Vec<String>) -> io::Result<()> {
+        match &mut self.spool {
+            Some(spool) => spool.append(&lines),
+            None => {
+                self.lines.extend(lines);
+                Ok(())
+            }
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct ManPage {
     name: String,
     section: Option<String>,
     pub scroll: usize,
+    pub column: usize,
+    pub last_active: u64,
+    pub pinned: bool,
+    pub label: Option<String>,
+    width: Option<u16>,
     cache: RenderCache,
     search_query: Option<String>,
     search_matches: Vec<SearchMatch>,
     search_index: Option<usize>,
+    search_scope: Option<String>,
+    fixed: bool,
+    baseline: Option<HashSet<String>>,
+    changed: Vec<usize>,
+    diff: Vec<DiffKind>,
+    folded: BTreeSet<String>,
+    view: Option<FoldView>,
+    stats: RenderStats,
+    error: Option<String>,
+    formatted: Option<(RenderCache, usize)>,
+    warning: Option<String>,
+    stream: Option<(Receiver<RenderChunk>, Instant)>,
+    modified: Option<SystemTime>,
+    low_memory: bool,
 }
 
 impl ManPage {
@@ -32,10 +122,143 @@ impl ManPage {
             name: name.into(),
             section,
             scroll: 0,
+            column: 0,
+            last_active: 0,
+            pinned: false,
+            label: None,
+            width: None,
             cache: RenderCache::empty(),
             search_query: None,
             search_matches: Vec::new(),
             search_index: None,
+            search_scope: None,
+            fixed: false,
+            baseline: None,
+            changed: Vec::new(),
+            diff: Vec::new(),
+            folded: BTreeSet::new(),
+            view: None,
+            stats: RenderStats::default(),
+            error: None,
+            formatted: None,
+            warning: None,
+            stream: None,
+            modified: None,
+            low_memory: false,
+        }
+    }
+
+    pub fn with_lines(
+        name: impl Into<String>,
+        section: Option<String>,
+        lines: Vec<String>,
+    ) -> Self {
+        let mut page = Self::new(name, section);
+        page.cache = RenderCache::new(0, lines);
+        page.fixed = true;
+        page
+    }
+
+    pub fn with_diff(name: impl Into<String>, diff: Vec<(DiffKind, String)>) -> Self {
+        let (kinds, lines) = diff
+            .into_iter()
+            .map(|(kind, line)| {
+                let marker = match kind {
+                    DiffKind::Same => ' ',
+                    DiffKind::Added => '+',
+                    DiffKind::Removed => '-',
+                };
+                (kind, format!("{marker} {line}"))
+            })
+            .unzip();
+        let mut page = Self::with_lines(name, None, lines);
+        page.diff = kinds;
+        page
+    }
+
+    pub fn diff_kinds(&self) -> &[DiffKind] {
+        &self.diff
+    }
+
+    pub fn is_fixed(&self) -> bool {
+        self.fixed
+    }
+
+    pub fn width(&self) -> Option<u16> {
+        self.width
+    }
+
+    pub fn set_width(&mut self, width: Option<u16>) {
+        self.width = width;
+    }
+
+    pub fn set_low_memory(&mut self, enabled: bool) {
+        self.low_memory = enabled;
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn show_error(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        let title = match self.section() {
+            Some(section) => format!("{}({section})", self.name),
+            None => self.name.clone(),
+        };
+        let mut lines = vec![format!("Could not render {title}"), String::new()];
+        lines.extend(message.lines().map(|line| format!("    {line}")));
+        lines.push(String::new());
+        lines.push("Press r to try again, for example after installing the page.".to_string());
+        self.cache = RenderCache::new(0, lines);
+        self.stream = None;
+        self.view = None;
+        self.search_matches = Vec::new();
+        self.search_index = None;
+        self.changed = Vec::new();
+        self.scroll = 0;
+        self.column = 0;
+        self.error = Some(message);
+    }
+
+    pub fn show_source(&mut self, lines: Vec<String>) {
+        if self.stream.take().is_some() {
+            self.cache = RenderCache::empty();
+        }
+        let formatted = std::mem::replace(&mut self.cache, RenderCache::new(0, lines));
+        if self.formatted.is_none() {
+            self.formatted = Some((formatted, self.scroll));
+        }
+        self.view = None;
+        self.changed = Vec::new();
+        self.scroll = 0;
+        self.column = 0;
+        self.refresh_search(0);
+    }
+
+    pub fn show_formatted(&mut self) -> bool {
+        let Some((cache, scroll)) = self.formatted.take() else {
+            return false;
+        };
+        self.cache = cache;
+        self.scroll = scroll;
+        self.column = 0;
+        self.refresh_view();
+        self.clamp_scroll();
+        true
+    }
+
+    pub fn take_warning(&mut self) -> Option<String> {
+        self.warning.take()
+    }
+
+    pub fn is_source(&self) -> bool {
+        self.formatted.is_some()
+    }
+
+    pub fn clear_error(&mut self) {
+        if self.error.take().is_some() {
+            self.cache = RenderCache::empty();
         }
     }
 
@@ -48,11 +271,264 @@ impl ManPage {
     }
 
     pub fn lines(&self) -> &[String] {
-        &self.cache.lines
+        match &self.view {
+            Some(view) => &view.lines,
+            None => &self.cache.lines,
+        }
+    }
+
+    pub fn window(&self) -> (usize, &[String]) {
+        let first = self.cache.spool.as_ref().map_or(0, |spool| spool.first);
+        (first, self.lines())
+    }
+
+    pub fn line_range(&self, rows: Range<usize>) -> Cow<'_, [String]> {
+        let (first, lines) = self.window();
+        let end = rows.end.min(self.line_count());
+        let start = rows.start.min(end);
+        match &self.cache.spool {
+            Some(spool) if start < first || end > first + lines.len() => spool
+                .read(start..end)
+                .map_or(Cow::Borrowed(&[]), Cow::Owned),
+            _ => Cow::Borrowed(&lines[start - first..end - first]),
+        }
+    }
+
+    pub fn source_lines(&self) -> Cow<'_, [String]> {
+        match &self.cache.spool {
+            Some(spool) => spool
+                .read(0..spool.line_count())
+                .map_or(Cow::Borrowed(&[]), Cow::Owned),
+            None => Cow::Borrowed(&self.cache.lines),
+        }
+    }
+
+    pub fn full_lines(&self) -> Cow<'_, [String]> {
+        match &self.view {
+            Some(view) => Cow::Borrowed(&view.lines),
+            None => self.source_lines(),
+        }
+    }
+
+    pub fn is_spooled(&self) -> bool {
+        self.cache.spool.is_some()
+    }
+
+    pub fn load_window(&mut self, rows: Range<usize>) -> io::Result<()> {
+        let Some(spool) = &mut self.cache.spool else {
+            return Ok(());
+        };
+        let end = rows.end.min(spool.line_count());
+        let start = rows.start.min(end);
+        if spool.first == start && self.cache.lines.len() == end - start {
+            return Ok(());
+        }
+        self.cache.lines = spool.read(start..end)?;
+        spool.first = start;
+        Ok(())
     }
 
     pub fn line_count(&self) -> usize {
-        self.cache.lines.len()
+        match &self.cache.spool {
+            Some(spool) => spool.line_count(),
+            None => self.lines().len(),
+        }
+    }
+
+    pub fn headings(&self) -> Vec<usize> {
+        self.heading_entries()
+            .into_iter()
+            .map(|(row, _)| row)
+            .collect()
+    }
+
+    pub fn options(&self) -> Vec<usize> {
+        match &self.cache.spool {
+            Some(spool) => {
+                let (rows, lines) = spool.outline();
+                rows.iter()
+                    .zip(lines)
+                    .filter(|(_, line)| option_definition(line).is_some())
+                    .map(|(row, _)| *row)
+                    .collect()
+            }
+            None => option_lines(self.lines()),
+        }
+    }
+
+    pub fn widest_line(&self) -> usize {
+        match &self.cache.spool {
+            Some(spool) => spool.widest(),
+            None => self
+                .lines()
+                .iter()
+                .map(|line| display_width(line))
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    fn heading_entries(&self) -> Vec<(usize, &str)> {
+        match &self.cache.spool {
+            Some(spool) => {
+                let (rows, lines) = spool.outline();
+                rows.iter()
+                    .zip(lines)
+                    .filter(|(_, line)| is_heading(line))
+                    .map(|(row, line)| (*row, line.as_str()))
+                    .collect()
+            }
+            None => {
+                let lines = self.lines();
+                heading_lines(lines)
+                    .into_iter()
+                    .map(|row| (row, lines[row].as_str()))
+                    .collect()
+            }
+        }
+    }
+
+    fn scan_lines(&self, mut visit: impl FnMut(usize, &[String])) {
+        match &self.cache.spool {
+            Some(spool) => {
+                let _ = spool.scan(visit);
+            }
+            None => visit(0, self.lines()),
+        }
+    }
+
+    pub fn source_line(&self, row: usize) -> usize {
+        match &self.view {
+            Some(view) => view.rows.get(row).copied().unwrap_or(row),
+            None => row,
+        }
+    }
+
+    pub fn row_for(&self, line: usize) -> usize {
+        match &self.view {
+            Some(view) => view.row_for(line),
+            None => line,
+        }
+    }
+
+    pub fn fold_markers(&self) -> &[(usize, usize)] {
+        match &self.view {
+            Some(view) => &view.markers,
+            None => &[],
+        }
+    }
+
+    pub fn toggle_fold(&mut self) -> Option<bool> {
+        if self.is_spooled() {
+            return None;
+        }
+        let top = self.source_line(self.scroll);
+        let lines = &self.cache.lines;
+        let heading = heading_lines(lines)
+            .into_iter()
+            .rev()
+            .find(|heading| *heading <= top)?;
+        let name = lines[heading].trim_end().to_string();
+        let folded = !self.folded.remove(&name);
+        if folded {
+            self.folded.insert(name);
+        }
+        self.refresh_view();
+        self.scroll = self.row_for(if folded { heading } else { top });
+        Some(folded)
+    }
+
+    pub fn fold_all(&mut self) {
+        if self.is_spooled() {
+            return;
+        }
+        let top = self.source_line(self.scroll);
+        let lines = &self.cache.lines;
+        self.folded = heading_lines(lines)
+            .into_iter()
+            .map(|heading| lines[heading].trim_end().to_string())
+            .collect();
+        self.refresh_view();
+        self.scroll = self.row_for(top);
+    }
+
+    pub fn unfold_all(&mut self) {
+        let top = self.source_line(self.scroll);
+        self.folded.clear();
+        self.refresh_view();
+        self.scroll = self.row_for(top);
+    }
+
+    pub fn aliases(&self) -> Vec<String> {
+        match &self.cache.spool {
+            Some(spool) => parse_name_aliases(spool.name_section()),
+            None => parse_name_aliases(&self.cache.lines),
+        }
+    }
+
+    pub fn alias_line(&self, alias: &str) -> Option<usize> {
+        let Some(spool) = &self.cache.spool else {
+            return alias_anchor(&self.cache.lines, alias).map(|line| self.row_for(line));
+        };
+        let name_end = spool.name_end();
+        let (mut strict, mut loose) = (None, None);
+        let _ = spool.scan(|start, lines| {
+            for (row, line) in (start..).zip(lines).skip(name_end.saturating_sub(start)) {
+                if strict.is_some() {
+                    return;
+                }
+                match alias_mention(line, alias) {
+                    Some(true) => strict = Some(row),
+                    Some(false) => {
+                        loose.get_or_insert(row);
+                    }
+                    None => {}
+                }
+            }
+        });
+        strict.or(loose)
+    }
+
+    pub fn anchor_line(&self, anchor: &str) -> Option<usize> {
+        match &self.cache.spool {
+            Some(spool) => {
+                let (rows, lines) = spool.outline();
+                resolve_anchor(lines, anchor).map(|index| rows[index])
+            }
+            None => resolve_anchor(&self.cache.lines, anchor).map(|line| self.row_for(line)),
+        }
+    }
+
+    pub fn option_line(&self, flag: &str) -> Option<usize> {
+        match &self.cache.spool {
+            Some(spool) => {
+                let (rows, lines) = spool.outline();
+                find_option(lines, flag).map(|index| rows[index])
+            }
+            None => find_option(&self.cache.lines, flag).map(|line| self.row_for(line)),
+        }
+    }
+
+    pub fn set_baseline(&mut self, previous: &[String]) {
+        let baseline = paragraph_set(previous);
+        self.changed = self.changed_against(&baseline);
+        self.baseline = Some(baseline);
+    }
+
+    fn changed_against(&self, baseline: &HashSet<String>) -> Vec<usize> {
+        let mut changed = Vec::new();
+        self.scan_lines(|start, lines| {
+            changed.extend(
+                changed_lines(baseline, lines)
+                    .into_iter()
+                    .map(|line| start + line),
+            );
+        });
+        changed
+    }
+
+    pub fn changed_lines(&self) -> &[usize] {
+        &self.changed
     }
 
     pub fn search_query(&self) -> Option<&str> {
@@ -67,32 +543,182 @@ impl ManPage {
         self.search_index
     }
 
+    pub fn search_scope(&self) -> Option<&str> {
+        self.search_scope.as_deref()
+    }
+
+    pub fn set_search_scope(&mut self, scope: Option<String>) {
+        self.search_scope = scope;
+        self.refresh_search(self.scroll);
+    }
+
+    pub fn section_at(&self, row: usize) -> Option<String> {
+        self.heading_entries()
+            .into_iter()
+            .rev()
+            .find(|(heading, _)| *heading <= row)
+            .map(|(_, line)| line.trim_end().to_string())
+    }
+
     pub fn ensure_render(
         &mut self,
         renderer: &dyn ManRenderer,
         width: u16,
     ) -> Result<(), RenderError> {
-        let safe_width = width.max(1);
-        if self.cache.width != safe_width || self.cache.lines.is_empty() {
+        let safe_width = self.width.unwrap_or(width).max(1);
+        if self.needs_render(safe_width) {
+            let started = Instant::now();
+            self.modified = source_modified(&self.name);
+            if let Some(stream) = renderer.render_stream(&self.name, self.section(), safe_width)? {
+                self.begin_stream(stream, safe_width, started);
+                self.receive_stream(STREAM_WAIT)?;
+                self.clamp_scroll();
+                return Ok(());
+            }
             let lines = renderer.render(&self.name, self.section(), safe_width)?;
-            self.cache = RenderCache {
-                width: safe_width,
-                lines,
-            };
-        }
-        if self.search_query.is_some() {
-            self.refresh_search(self.scroll);
+            self.warning = renderer.take_warning();
+            self.stats.renders += 1;
+            self.stats.last_render = Some(started.elapsed());
+            let mut cache = self.new_cache(safe_width);
+            cache.extend(lines)?;
+            self.cache = cache;
+            self.refresh_view();
+        } else {
+            self.stats.cache_hits += 1;
+            if self.search_query.is_some() && !self.is_spooled() {
+                self.refresh_search(self.scroll);
+            }
         }
         self.clamp_scroll();
         Ok(())
     }
 
+    pub fn start_render(
+        &mut self,
+        renderer: &dyn ManRenderer,
+        width: u16,
+    ) -> Result<bool, RenderError> {
+        let safe_width = self.width.unwrap_or(width).max(1);
+        if !self.needs_render(safe_width) {
+            return Ok(false);
+        }
+        let started = Instant::now();
+        let modified = source_modified(&self.name);
+        let Some(stream) = renderer.render_stream(&self.name, self.section(), safe_width)? else {
+            return Ok(false);
+        };
+        self.modified = modified;
+        self.begin_stream(stream, safe_width, started);
+        Ok(true)
+    }
+
+    fn begin_stream(&mut self, stream: Receiver<RenderChunk>, width: u16, started: Instant) {
+        self.stats.renders += 1;
+        self.cache = self.new_cache(width);
+        self.view = None;
+        self.stream = Some((stream, started));
+    }
+
+    fn new_cache(&mut self, width: u16) -> RenderCache {
+        if self.low_memory && !self.fixed {
+            match RenderCache::spooled(width) {
+                Ok(cache) => return cache,
+                Err(err) => self.warning = Some(format!("Could not keep the page on disk: {err}")),
+            }
+        }
+        RenderCache::new(width, Vec::new())
+    }
+
+    pub fn needs_render(&self, width: u16) -> bool {
+        let width = self.width.unwrap_or(width);
+        !self.fixed
+            && self.error.is_none()
+            && self.formatted.is_none()
+            && (self.cache.width != width.max(1)
+                || (self.line_count() == 0 && self.stream.is_none()))
+    }
+
+    pub fn reload_source(&mut self) {
+        self.release_render();
+        self.modified = source_modified(&self.name);
+    }
+
+    pub fn source_changed(&self) -> bool {
+        self.modified
+            .is_some_and(|modified| source_modified(&self.name).is_some_and(|now| now != modified))
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    pub fn receive_stream(&mut self, wait: Duration) -> Result<bool, RenderError> {
+        let Some((stream, started)) = self.stream.take() else {
+            return Ok(false);
+        };
+        let deadline = Instant::now() + wait;
+        let mut received = false;
+        let mut finished = None;
+        while finished.is_none() {
+            match stream.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(RenderChunk::Lines(lines)) => {
+                    self.cache.extend(lines)?;
+                    received = true;
+                }
+                Ok(RenderChunk::Warning(warning)) => self.warning = Some(warning),
+                Ok(RenderChunk::Done(result)) => finished = Some(result),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => finished = Some(Ok(())),
+            }
+        }
+        let done = finished.is_some();
+        match finished {
+            Some(result) => {
+                self.stats.last_render = Some(started.elapsed());
+                result?;
+            }
+            None => self.stream = Some((stream, started)),
+        }
+        if done || (received && !self.is_spooled()) {
+            self.refresh_view();
+        }
+        Ok(received || done)
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.cache
+            .lines
+            .iter()
+            .map(|line| line.capacity() + std::mem::size_of::<String>())
+            .sum::<usize>()
+            + self
+                .cache
+                .spool
+                .as_ref()
+                .map_or(0, SpooledLines::memory_usage)
+    }
+
+    pub fn render_stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    pub fn release_render(&mut self) {
+        if self.fixed || self.error.is_some() || self.formatted.is_some() {
+            return;
+        }
+        self.cache = RenderCache::empty();
+        self.stream = None;
+        self.view = None;
+        self.search_matches = Vec::new();
+        self.changed = Vec::new();
+    }
+
     pub fn clamp_scroll(&mut self) {
-        if self.cache.lines.is_empty() {
+        if self.line_count() == 0 {
             self.scroll = 0;
             return;
         }
-        let max_scroll = self.cache.lines.len().saturating_sub(1);
+        let max_scroll = self.line_count() - 1;
         if self.scroll > max_scroll {
             self.scroll = max_scroll;
         }
@@ -115,39 +741,85 @@ impl ManPage {
         self.search_query = None;
         self.search_matches.clear();
         self.search_index = None;
+        self.column = 0;
     }
 
-    pub fn next_match_line(&mut self) -> Option<usize> {
-        let count = self.search_matches.len();
-        if count == 0 {
-            self.search_index = None;
-            return None;
-        }
-        let next = match self.search_index {
-            Some(index) => (index + 1) % count,
-            None => 0,
-        };
-        self.search_index = Some(next);
-        Some(self.search_matches[next].line)
+    pub fn next_match_line(&mut self, wrap: bool) -> Option<MatchStep> {
+        self.step_match(true, wrap)
     }
 
-    pub fn previous_match_line(&mut self) -> Option<usize> {
+    pub fn previous_match_line(&mut self, wrap: bool) -> Option<MatchStep> {
+        self.step_match(false, wrap)
+    }
+
+    fn step_match(&mut self, forward: bool, wrap: bool) -> Option<MatchStep> {
         let count = self.search_matches.len();
         if count == 0 {
             self.search_index = None;
             return None;
         }
-        let next = match self.search_index {
-            Some(index) => (index + count - 1) % count,
-            None => 0,
+        let (next, wrapped) = match self.search_index {
+            None => (0, false),
+            Some(index) if forward && index + 1 < count => (index + 1, false),
+            Some(index) if !forward && index > 0 => (index - 1, false),
+            Some(_) if !wrap => return Some(MatchStep::Stopped),
+            Some(_) => (if forward { 0 } else { count - 1 }, true),
         };
         self.search_index = Some(next);
-        Some(self.search_matches[next].line)
+        let line = self.search_matches[next].line;
+        Some(if wrapped {
+            MatchStep::Wrapped(line)
+        } else {
+            MatchStep::Moved(line)
+        })
     }
 
     pub fn current_match_line(&self) -> Option<usize> {
+        self.current_match().map(|entry| entry.line)
+    }
+
+    pub fn select_match(&mut self, index: usize) -> Option<usize> {
+        let line = self.search_matches.get(index)?.line;
+        self.search_index = Some(index);
+        Some(line)
+    }
+
+    pub fn current_match(&self) -> Option<&SearchMatch> {
         self.search_index
-            .and_then(|index| self.search_matches.get(index).map(|m| m.line))
+            .and_then(|index| self.search_matches.get(index))
+    }
+
+    pub fn reveal_match(&mut self, line: usize, width: usize) {
+        if width == 0 {
+            return;
+        }
+        let Some(entry) = self.current_match().filter(|entry| entry.line == line) else {
+            self.column = 0;
+            return;
+        };
+        let lines = self.line_range(line..line + 1);
+        let Some(text) = lines.first() else {
+            return;
+        };
+        let start = display_column(text, entry.start);
+        let end = display_column(text, entry.end);
+        drop(lines);
+        if end <= width {
+            self.column = 0;
+        } else if start < self.column || end > self.column + width {
+            self.column = start.saturating_sub(width / 2);
+        }
+    }
+
+    fn refresh_view(&mut self) {
+        self.view = (!self.folded.is_empty() && !self.is_spooled())
+            .then(|| FoldView::build(&self.cache.lines, &self.folded));
+        if let Some(baseline) = &self.baseline {
+            self.changed = self.changed_against(baseline);
+        }
+        if self.search_query.is_some() {
+            self.refresh_search(self.scroll);
+        }
     }
 
     fn refresh_search(&mut self, start_line: usize) {
@@ -156,7 +828,35 @@ impl ManPage {
             self.search_index = None;
             return;
         };
-        self.search_matches = collect_matches(&self.cache.lines, query);
+        let mut matches = Vec::new();
+        self.scan_lines(|start, lines| {
+            matches.extend(
+                collect_matches(lines, query)
+                    .into_iter()
+                    .map(|entry| SearchMatch {
+                        line: start + entry.line,
+                        ..entry
+                    }),
+            );
+        });
+        if let Some(scope) = self.search_scope.as_deref() {
+            let headings = self.heading_entries();
+            let range = headings
+                .iter()
+                .position(|(_, line)| line.trim_end() == scope)
+                .map(|index| {
+                    let end = headings
+                        .get(index + 1)
+                        .map_or(self.line_count(), |(row, _)| *row);
+                    headings[index].0..end
+                });
+            matches.retain(|entry| {
+                range
+                    .as_ref()
+                    .is_some_and(|range| range.contains(&entry.line))
+            });
+        }
+        self.search_matches = matches;
         if self.search_matches.is_empty() {
             self.search_index = None;
             return;
@@ -172,30 +872,9 @@ impl ManPage {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SearchMatch {
-    pub line: usize,
-    pub start: usize,
-    pub end: usize,
-}
-
-fn collect_matches(lines: &[String], query: &str) -> Vec<SearchMatch> {
-    if query.is_empty() {
-        return Vec::new();
-    }
-    let mut matches = Vec::new();
-    for (line_index, line) in lines.iter().enumerate() {
-        let mut offset = 0;
-        while let Some(pos) = line[offset..].find(query) {
-            let start = offset + pos;
-            let end = start + query.len();
-            matches.push(SearchMatch {
-                line: line_index,
-                start,
-                end,
-            });
-            offset = end;
-        }
+fn source_modified(name: &str) -> Option<SystemTime> {
+    if !is_local_page(name) {
+        return None;
     }
-    matches
+    std::fs::metadata(name).ok()?.modified().ok()
 }
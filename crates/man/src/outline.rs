@@ -0,0 +1,303 @@
+use std::ops::Range;
+
+pub fn is_heading(line: &str) -> bool {
+    let Some(first) = line.chars().next() else {
+        return false;
+    };
+    if !first.is_ascii_uppercase() {
+        return false;
+    }
+    line.trim_end().chars().all(|ch| {
+        ch.is_ascii_uppercase() || ch.is_ascii_digit() || matches!(ch, ' ' | '-' | '_' | '/' | '&')
+    })
+}
+
+pub fn heading_lines(lines: &[String]) -> Vec<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| is_heading(line))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+pub fn section_range(lines: &[String], heading: usize) -> (usize, usize) {
+    let end = lines
+        .iter()
+        .enumerate()
+        .skip(heading + 1)
+        .find(|(_, line)| is_heading(line))
+        .map(|(index, _)| index)
+        .unwrap_or(lines.len());
+    (heading, end)
+}
+
+pub fn section_lines<'a>(lines: &'a [String], name: &str) -> Option<&'a [String]> {
+    let name = name.trim();
+    let heading = heading_lines(lines)
+        .into_iter()
+        .find(|heading| lines[*heading].trim_end().eq_ignore_ascii_case(name))?;
+    let (start, end) = section_range(lines, heading);
+    let end = lines[..end]
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(end, |last| last + 1);
+    Some(&lines[start..end])
+}
+
+pub fn parse_name_aliases(lines: &[String]) -> Vec<String> {
+    let Some(heading) = lines.iter().position(|line| line.trim_end() == "NAME") else {
+        return Vec::new();
+    };
+    let (_, end) = section_range(lines, heading);
+    let text = lines[heading + 1..end]
+        .iter()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let names = [" - ", " \u{2010} ", " \u{2013} ", " \u{2014} "]
+        .iter()
+        .filter_map(|separator| text.find(separator))
+        .min()
+        .map(|index| &text[..index])
+        .unwrap_or(text.as_str());
+    names
+        .split(',')
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty() && !name.contains(char::is_whitespace))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+pub fn alias_anchor(lines: &[String], alias: &str) -> Option<usize> {
+    let name_end = lines
+        .iter()
+        .position(|line| line.trim_end() == "NAME")
+        .map(|heading| section_range(lines, heading).1)
+        .unwrap_or(0);
+    let mut loose = None;
+    for (index, line) in lines.iter().enumerate().skip(name_end) {
+        match alias_mention(line, alias) {
+            Some(true) => return Some(index),
+            Some(false) => {
+                loose.get_or_insert(index);
+            }
+            None => {}
+        }
+    }
+    loose
+}
+
+pub(crate) fn alias_mention(line: &str, alias: &str) -> Option<bool> {
+    if is_heading(line) {
+        return None;
+    }
+    let end = find_word(line, alias)?;
+    Some(line[end..].starts_with('('))
+}
+
+pub fn resolve_anchor(lines: &[String], anchor: &str) -> Option<usize> {
+    let wanted = anchor.trim();
+    if wanted.is_empty() {
+        return None;
+    }
+    if !wanted.starts_with('-') {
+        return find_heading(lines, wanted);
+    }
+    lines.iter().position(|line| defines_flag(line, wanted))
+}
+
+fn find_heading(lines: &[String], name: &str) -> Option<usize> {
+    let wanted = name.trim().to_ascii_lowercase();
+    if wanted.is_empty() {
+        return None;
+    }
+    let headings = heading_lines(lines);
+    let heading = |index: &usize| lines[*index].trim_end().to_ascii_lowercase();
+    headings
+        .iter()
+        .find(|index| heading(index) == wanted)
+        .or_else(|| {
+            headings
+                .iter()
+                .find(|index| heading(index).starts_with(&wanted))
+        })
+        .copied()
+}
+
+pub fn find_option(lines: &[String], flag: &str) -> Option<usize> {
+    let flag = flag.trim();
+    if flag.is_empty() {
+        return None;
+    }
+    if flag.starts_with('-') {
+        return lines.iter().position(|line| defines_flag(line, flag));
+    }
+    [format!("--{flag}"), format!("-{flag}")]
+        .iter()
+        .find_map(|flag| lines.iter().position(|line| defines_flag(line, flag)))
+}
+
+pub fn option_definition(line: &str) -> Option<Range<usize>> {
+    let trimmed = line.trim_start();
+    let start = line.len() - trimmed.len();
+    let name = trimmed
+        .strip_prefix("--")
+        .or_else(|| trimmed.strip_prefix('-'))?;
+    if start == 0
+        || !name
+            .chars()
+            .next()
+            .is_some_and(|ch| !ch.is_whitespace() && ch != '-')
+    {
+        return None;
+    }
+    let end = trimmed
+        .find("  ")
+        .map_or(line.len(), |offset| start + offset);
+    Some(start..line[..end].trim_end().len())
+}
+
+pub fn option_lines(lines: &[String]) -> Vec<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| option_definition(line).is_some())
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn defines_flag(line: &str, flag: &str) -> bool {
+    let Some(range) = option_definition(line) else {
+        return false;
+    };
+    line[range].split([',', ' ']).any(|token| {
+        token
+            .strip_prefix(flag)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['=', '[', '<']))
+    })
+}
+
+fn find_word(line: &str, word: &str) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(pos) = line[offset..].find(word) {
+        let start = offset + pos;
+        let end = start + word.len();
+        let before = line[..start].chars().next_back();
+        let after = line[end..].chars().next();
+        if !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char) {
+            return Some(end);
+        }
+        offset = end;
+    }
+    None
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(text: &str) -> Vec<String> {
+        text.lines().map(|line| line.to_string()).collect()
+    }
+
+    fn printf_page() -> Vec<String> {
+        page(
+            "printf(3)            Library Functions Manual            printf(3)\n\
+             \n\
+             NAME\n\
+             \x20      printf, fprintf, dprintf, sprintf, snprintf - formatted output\n\
+             \x20      conversion\n\
+             \n\
+             SYNOPSIS\n\
+             \x20      int printf(const char *restrict format, ...);\n\
+             \x20      int fprintf(FILE *restrict stream,\n\
+             \x20      int sprintf(char *restrict str,\n\
+             \n\
+             DESCRIPTION\n\
+             \x20      The functions in the printf() family produce output.\n\
+             \x20      sprintf writes to the character string str.\n",
+        )
+    }
+
+    #[test]
+    fn detects_headings() {
+        assert!(is_heading("NAME"));
+        assert!(is_heading("RETURN VALUE"));
+        assert!(!is_heading("       printf, fprintf"));
+        assert!(!is_heading("printf(3)     Library Functions Manual"));
+        assert!(!is_heading(""));
+        assert_eq!(heading_lines(&printf_page()), vec![2, 6, 11]);
+    }
+
+    #[test]
+    fn slices_a_named_section() {
+        let page = printf_page();
+        let synopsis = section_lines(&page, "synopsis").expect("section");
+        assert_eq!(synopsis.len(), 4);
+        assert_eq!(synopsis[0], "SYNOPSIS");
+        assert_eq!(section_lines(&page, "DESCRIPTION").expect("last").len(), 3);
+        assert!(section_lines(&page, "RETURN VALUE").is_none());
+    }
+
+    #[test]
+    fn parses_name_aliases() {
+        assert_eq!(
+            parse_name_aliases(&printf_page()),
+            vec!["printf", "fprintf", "dprintf", "sprintf", "snprintf"]
+        );
+        assert!(parse_name_aliases(&page("no name section")).is_empty());
+    }
+
+    #[test]
+    fn resolves_section_and_flag_anchors() {
+        let lines = page(
+            "NAME\n\
+             \x20      ls - list\n\
+             OPTIONS\n\
+             \x20      The -a flag is described below.\n\
+             \x20      -a, --all\n\
+             \x20             do not ignore entries starting with .\n\
+             \x20      --color[=WHEN]\n\
+             FILES\n",
+        );
+        assert_eq!(resolve_anchor(&lines, "files"), Some(7));
+        assert_eq!(resolve_anchor(&lines, "opt"), Some(2));
+        assert_eq!(resolve_anchor(&lines, "N"), Some(0));
+        assert_eq!(resolve_anchor(&lines, "-a"), Some(4));
+        assert_eq!(resolve_anchor(&lines, "--all"), Some(4));
+        assert_eq!(resolve_anchor(&lines, "--color"), Some(6));
+        assert_eq!(resolve_anchor(&lines, "--col"), None);
+        assert_eq!(resolve_anchor(&lines, "EXAMPLES"), None);
+        assert_eq!(option_lines(&lines), vec![4, 6]);
+        assert_eq!(find_option(&lines, "all"), Some(4));
+        assert_eq!(find_option(&lines, "a"), Some(4));
+        assert_eq!(find_option(&lines, "--color"), Some(6));
+        assert_eq!(find_option(&lines, "-z"), None);
+    }
+
+    #[test]
+    fn finds_option_definitions() {
+        assert_eq!(
+            option_definition("       -a, --all   show all"),
+            Some(7..16)
+        );
+        assert_eq!(option_definition("       --color[=WHEN]"), Some(7..21));
+        assert_eq!(option_definition("       -?"), Some(7..9));
+        assert_eq!(option_definition("       -- ends options"), None);
+        assert_eq!(option_definition("-a at column zero"), None);
+        assert_eq!(option_definition("       plain text"), None);
+    }
+
+    #[test]
+    fn anchors_alias_to_definition() {
+        let lines = printf_page();
+        assert_eq!(alias_anchor(&lines, "sprintf"), Some(9));
+        assert_eq!(alias_anchor(&lines, "printf"), Some(7));
+        assert_eq!(alias_anchor(&lines, "snprintf"), None);
+    }
+}
@@ -0,0 +1,82 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub start: usize,
+    pub end: usize,
+    pub name: String,
+    pub section: String,
+}
+
+impl Reference {
+    pub fn target(&self) -> String {
+        format!("{}({})", self.name, self.section)
+    }
+}
+
+pub fn find_references(line: &str) -> Vec<Reference> {
+    let mut references = Vec::new();
+    let bytes = line.as_bytes();
+    let mut search_from = 0;
+    while let Some(pos) = line[search_from..].find('(') {
+        let open = search_from + pos;
+        search_from = open + 1;
+        let Some(close) = line[open..].find(')').map(|offset| open + offset) else {
+            break;
+        };
+        let section = &line[open + 1..close];
+        if !is_section(section) {
+            continue;
+        }
+        let mut start = open;
+        while start > 0 && is_name_byte(bytes[start - 1]) {
+            start -= 1;
+        }
+        while start < open && !bytes[start].is_ascii_alphanumeric() && bytes[start] != b'_' {
+            start += 1;
+        }
+        if start == open {
+            continue;
+        }
+        references.push(Reference {
+            start,
+            end: close + 1,
+            name: line[start..open].to_string(),
+            section: section.to_string(),
+        });
+        search_from = close + 1;
+    }
+    references
+}
+
+fn is_section(value: &str) -> bool {
+    let mut chars = value.chars();
+    matches!(chars.next(), Some(first) if first.is_ascii_digit())
+        && value.len() <= 6
+        && chars.all(|ch| ch.is_ascii_alphanumeric())
+}
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-' | b'.' | b':' | b'+')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_references_in_see_also_lines() {
+        let refs = find_references("       open(2), systemd.exec(5), printf(3p), git-rebase(1).");
+        let targets: Vec<String> = refs.iter().map(Reference::target).collect();
+        assert_eq!(
+            targets,
+            vec!["open(2)", "systemd.exec(5)", "printf(3p)", "git-rebase(1)"]
+        );
+        assert_eq!((refs[0].start, refs[0].end), (7, 14));
+    }
+
+    #[test]
+    fn ignores_calls_and_plain_parentheses() {
+        assert!(find_references("int printf(const char *format);").is_empty());
+        assert!(find_references("see the manual (1)").is_empty());
+        assert!(find_references("(1) and ()").is_empty());
+    }
+}
@@ -0,0 +1,256 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordMatch {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+    pub keyword: usize,
+}
+
+pub fn keyword_matches(lines: &[String], keywords: &[String]) -> Vec<KeywordMatch> {
+    let keywords: Vec<(usize, bool, Vec<char>)> = keywords
+        .iter()
+        .enumerate()
+        .filter(|(_, keyword)| !keyword.is_empty())
+        .map(|(index, keyword)| {
+            let fold = !keyword.chars().any(char::is_uppercase);
+            (index, fold, normalize(keyword, fold))
+        })
+        .collect();
+    let mut matches = Vec::new();
+    if keywords.is_empty() {
+        return matches;
+    }
+    for (line_index, line) in lines.iter().enumerate() {
+        let boundaries = grapheme_boundaries(line);
+        let exact = normalize(line, false);
+        let folded = normalize(line, true);
+        let mut ranges = Vec::new();
+        for (index, fold, keyword) in &keywords {
+            let chars = if *fold { &folded } else { &exact };
+            for (start, end) in find_all(chars, keyword, boundaries.as_deref()) {
+                ranges.push((start, end, *index));
+            }
+        }
+        ranges.sort_unstable();
+        let mut end_of_last = 0;
+        for (start, end, keyword) in ranges {
+            if start < end_of_last {
+                continue;
+            }
+            matches.push(KeywordMatch {
+                line: line_index,
+                start,
+                end,
+                keyword,
+            });
+            end_of_last = end;
+        }
+    }
+    matches
+}
+
+pub fn search_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut rest = query;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let term = if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            rest = quoted.get(end + 1..).unwrap_or("");
+            &quoted[..end]
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let term = &rest[..end];
+            rest = &rest[end..];
+            term
+        };
+        if !term.is_empty() {
+            terms.push(term.to_string());
+        }
+    }
+    terms
+}
+
+pub(crate) fn collect_matches(lines: &[String], query: &str) -> Vec<SearchMatch> {
+    let terms = search_terms(query);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let fold = !query.chars().any(char::is_uppercase);
+    let terms: Vec<Vec<char>> = terms.iter().map(|term| normalize(term, fold)).collect();
+    let mut matches = Vec::new();
+    'lines: for (line_index, line) in lines.iter().enumerate() {
+        let chars = normalize(line, fold);
+        let boundaries = grapheme_boundaries(line);
+        let mut ranges = Vec::new();
+        for term in &terms {
+            let found = find_all(&chars, term, boundaries.as_deref());
+            if found.is_empty() {
+                continue 'lines;
+            }
+            ranges.extend(found);
+        }
+        ranges.sort_unstable();
+        let mut end_of_last = 0;
+        for (start, end) in ranges {
+            if start < end_of_last {
+                continue;
+            }
+            matches.push(SearchMatch {
+                line: line_index,
+                start,
+                end,
+            });
+            end_of_last = end;
+        }
+    }
+    matches
+}
+
+fn normalize(text: &str, fold: bool) -> Vec<char> {
+    text.chars()
+        .map(|ch| if fold { fold_char(ch) } else { ch })
+        .collect()
+}
+
+fn fold_char(ch: char) -> char {
+    if ch.is_ascii() {
+        return ch.to_ascii_lowercase();
+    }
+    let mut lower = ch.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(single), None) => single,
+        _ => ch,
+    }
+}
+
+fn grapheme_boundaries(line: &str) -> Option<Vec<bool>> {
+    if line.is_ascii() {
+        return None;
+    }
+    let mut boundaries = vec![false; line.chars().count() + 1];
+    let mut column = 0;
+    for grapheme in line.graphemes(true) {
+        boundaries[column] = true;
+        column += grapheme.chars().count();
+    }
+    boundaries[column] = true;
+    Some(boundaries)
+}
+
+fn find_all(chars: &[char], term: &[char], boundaries: Option<&[bool]>) -> Vec<(usize, usize)> {
+    let mut found = Vec::new();
+    let mut start = 0;
+    while start + term.len() <= chars.len() {
+        let end = start + term.len();
+        let aligned = boundaries.is_none_or(|boundaries| boundaries[start] && boundaries[end]);
+        if aligned && chars[start..end] == *term {
+            found.push((start, end));
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_terms_and_quoted_phrases() {
+        assert_eq!(search_terms("read  timeout "), vec!["read", "timeout"]);
+        assert_eq!(
+            search_terms("\"read timeout\" -t"),
+            vec!["read timeout", "-t"]
+        );
+        assert_eq!(
+            search_terms("\"unterminated phrase"),
+            vec!["unterminated phrase"]
+        );
+        assert!(search_terms(" \"\" ").is_empty());
+    }
+
+    #[test]
+    fn matches_lines_containing_every_term() {
+        let lines: Vec<String> = [
+            "read with a timeout",
+            "read only",
+            "timeout then read timeout",
+            "read timeout",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        let spans: Vec<(usize, usize, usize)> = collect_matches(&lines, "read timeout")
+            .into_iter()
+            .map(|entry| (entry.line, entry.start, entry.end))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (0, 0, 4),
+                (0, 12, 19),
+                (2, 0, 7),
+                (2, 13, 17),
+                (2, 18, 25),
+                (3, 0, 4),
+                (3, 5, 12),
+            ]
+        );
+        let phrase = collect_matches(&lines, "\"read timeout\"");
+        assert_eq!(phrase.len(), 2);
+        assert_eq!((phrase[0].line, phrase[0].start), (2, 13));
+    }
+
+    #[test]
+    fn finds_keywords_with_smart_case_without_overlaps() {
+        let lines: Vec<String> = ["EINTR is deprecated", "eintr", "Deprecated: EINTRx"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        let keywords = vec!["EINTR".to_string(), "deprecated".to_string(), String::new()];
+        let spans: Vec<(usize, usize, usize, usize)> = keyword_matches(&lines, &keywords)
+            .into_iter()
+            .map(|entry| (entry.line, entry.start, entry.end, entry.keyword))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![(0, 0, 5, 0), (0, 9, 19, 1), (2, 0, 10, 1), (2, 12, 17, 0)]
+        );
+        assert!(keyword_matches(&lines, &[]).is_empty());
+    }
+
+    #[test]
+    fn matches_unicode_by_character_with_smart_case() {
+        let lines: Vec<String> = ["\u{2014} Größe — ÜBER", "cafe\u{301} café"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        let spans = |query| -> Vec<(usize, usize, usize)> {
+            collect_matches(&lines, query)
+                .into_iter()
+                .map(|entry| (entry.line, entry.start, entry.end))
+                .collect()
+        };
+        assert_eq!(spans("über"), vec![(0, 10, 14)]);
+        assert_eq!(spans("größe"), vec![(0, 2, 7)]);
+        assert!(spans("Über").is_empty());
+        assert_eq!(spans("ÜBER"), vec![(0, 10, 14)]);
+        assert_eq!(spans("cafe"), vec![]);
+        assert_eq!(spans("café"), vec![(1, 6, 10)]);
+    }
+}
@@ -0,0 +1,258 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use render::display_width;
+use store::create_temp_file;
+
+use crate::outline::{is_heading, option_definition};
+
+const OFFSET_SIZE: usize = std::mem::size_of::<u64>();
+const SCAN_CHUNK: usize = 512;
+
+#[derive(Debug)]
+pub struct LineSpool {
+    text: File,
+    index: File,
+    paths: [PathBuf; 2],
+    count: usize,
+    written: u64,
+}
+
+impl LineSpool {
+    pub fn create() -> io::Result<Self> {
+        let (paths, text, index) = create_files()?;
+        let spool = Self {
+            text,
+            index,
+            paths,
+            count: 0,
+            written: 0,
+        };
+        (&spool.index).write_all(&0u64.to_le_bytes())?;
+        Ok(spool)
+    }
+
+    pub fn append(&mut self, lines: &[String]) -> io::Result<()> {
+        (&self.text).seek(SeekFrom::End(0))?;
+        (&self.index).seek(SeekFrom::End(0))?;
+        let mut text = BufWriter::new(&self.text);
+        let mut index = BufWriter::new(&self.index);
+        let mut written = self.written;
+        for line in lines {
+            text.write_all(line.as_bytes())?;
+            written += line.len() as u64;
+            index.write_all(&written.to_le_bytes())?;
+        }
+        text.flush()?;
+        index.flush()?;
+        self.written = written;
+        self.count += lines.len();
+        Ok(())
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.count
+    }
+
+    pub fn read(&self, rows: Range<usize>) -> io::Result<Vec<String>> {
+        let end = rows.end.min(self.count);
+        let start = rows.start.min(end);
+        let mut bytes = vec![0; (end - start + 1) * OFFSET_SIZE];
+        (&self.index).seek(SeekFrom::Start((start * OFFSET_SIZE) as u64))?;
+        (&self.index).read_exact(&mut bytes)?;
+        let offsets: Vec<u64> = bytes
+            .chunks_exact(OFFSET_SIZE)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().expect("offset size")))
+            .collect();
+        let first = offsets[0];
+        let mut text = vec![0; (offsets[offsets.len() - 1] - first) as usize];
+        (&self.text).seek(SeekFrom::Start(first))?;
+        (&self.text).read_exact(&mut text)?;
+        Ok(offsets
+            .windows(2)
+            .map(|pair| {
+                let line = &text[(pair[0] - first) as usize..(pair[1] - first) as usize];
+                String::from_utf8_lossy(line).into_owned()
+            })
+            .collect())
+    }
+}
+
+impl Drop for LineSpool {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SpooledLines {
+    spool: LineSpool,
+    pub first: usize,
+    outline_rows: Vec<usize>,
+    outline: Vec<String>,
+    name: Vec<String>,
+    name_row: usize,
+    in_name: bool,
+    widest: usize,
+}
+
+impl SpooledLines {
+    pub fn create() -> io::Result<Self> {
+        Ok(Self {
+            spool: LineSpool::create()?,
+            first: 0,
+            outline_rows: Vec::new(),
+            outline: Vec::new(),
+            name: Vec::new(),
+            name_row: 0,
+            in_name: false,
+            widest: 0,
+        })
+    }
+
+    pub fn append(&mut self, lines: &[String]) -> io::Result<()> {
+        let start = self.spool.line_count();
+        self.spool.append(lines)?;
+        for (index, line) in lines.iter().enumerate() {
+            let heading = is_heading(line);
+            if heading || option_definition(line).is_some() {
+                self.outline_rows.push(start + index);
+                self.outline.push(line.clone());
+            }
+            if heading {
+                self.in_name = self.name.is_empty() && line.trim_end() == "NAME";
+                if self.in_name {
+                    self.name_row = start + index;
+                }
+            }
+            if self.in_name {
+                self.name.push(line.clone());
+            }
+            self.widest = self.widest.max(display_width(line));
+        }
+        Ok(())
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.spool.line_count()
+    }
+
+    pub fn read(&self, rows: Range<usize>) -> io::Result<Vec<String>> {
+        self.spool.read(rows)
+    }
+
+    pub fn scan(&self, mut visit: impl FnMut(usize, &[String])) -> io::Result<()> {
+        let mut start = 0;
+        while start < self.line_count() {
+            let mut lines = self.read(start..start + SCAN_CHUNK)?;
+            if start + lines.len() < self.line_count()
+                && let Some(blank) = lines.iter().rposition(|line| line.trim().is_empty())
+            {
+                lines.truncate(blank + 1);
+            }
+            visit(start, &lines);
+            start += lines.len();
+        }
+        Ok(())
+    }
+
+    pub fn outline(&self) -> (&[usize], &[String]) {
+        (&self.outline_rows, &self.outline)
+    }
+
+    pub fn name_section(&self) -> &[String] {
+        &self.name
+    }
+
+    pub fn name_end(&self) -> usize {
+        match self.name.len() {
+            0 => 0,
+            len => self.name_row + len,
+        }
+    }
+
+    pub fn widest(&self) -> usize {
+        self.widest
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.outline_rows.capacity() * std::mem::size_of::<usize>()
+            + self
+                .outline
+                .iter()
+                .chain(&self.name)
+                .map(|line| line.capacity() + std::mem::size_of::<String>())
+                .sum::<usize>()
+    }
+}
+
+fn create_files() -> io::Result<([PathBuf; 2], File, File)> {
+    let (text_path, text) = create_temp_file("page.lines")?;
+    match create_temp_file("page.index") {
+        Ok((index_path, index)) => Ok(([text_path, index_path], text, index)),
+        Err(err) => {
+            let _ = fs::remove_file(&text_path);
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn reads_line_ranges_back_from_disk() {
+        let lines = strings(&["NAME", "", "   ls - list directory contents", "終端"]);
+        let mut spool = LineSpool::create().unwrap();
+        spool.append(&lines[..1]).unwrap();
+        assert_eq!(spool.read(0..4).unwrap(), &lines[..1]);
+        spool.append(&lines[1..]).unwrap();
+        let paths = spool.paths.clone();
+        assert!(paths.iter().all(|path| path.exists()));
+        assert_eq!(spool.line_count(), 4);
+        assert_eq!(spool.read(0..4).unwrap(), lines);
+        assert_eq!(spool.read(1..3).unwrap(), &lines[1..3]);
+        assert_eq!(spool.read(3..10).unwrap(), &lines[3..]);
+        assert!(spool.read(7..9).unwrap().is_empty());
+        drop(spool);
+        assert!(paths.iter().all(|path| !path.exists()));
+    }
+
+    #[test]
+    fn outlines_pages_while_they_are_spooled() {
+        let lines = strings(&[
+            "LS(1)",
+            "NAME",
+            "       ls, dir - list directory contents",
+            "OPTIONS",
+            "       -a, --all",
+            "              do not ignore entries starting with .",
+            "SEE ALSO",
+        ]);
+        let mut spooled = SpooledLines::create().unwrap();
+        for chunk in lines.chunks(2) {
+            spooled.append(chunk).unwrap();
+        }
+        let (rows, outline) = spooled.outline();
+        assert_eq!(rows, [1, 3, 4, 6]);
+        assert_eq!(outline[2], lines[4]);
+        assert_eq!(spooled.name_section(), &lines[1..3]);
+        assert_eq!(spooled.name_end(), 3);
+        assert_eq!(spooled.widest(), lines[5].len());
+        let mut seen = Vec::new();
+        spooled
+            .scan(|start, chunk| seen.extend((start..).zip(chunk.iter().cloned())))
+            .unwrap();
+        assert_eq!(seen.len(), 7);
+        assert_eq!(seen[4], (4, lines[4].clone()));
+    }
+}
@@ -0,0 +1,101 @@
+use clap::{Command, ValueEnum};
+use render::PageEntry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+pub fn script(shell: Shell, command: &Command) -> String {
+    let name = command.get_name();
+    let flags: Vec<(String, String)> = command
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set())
+        .filter_map(|arg| {
+            let help = arg.get_help().map(ToString::to_string).unwrap_or_default();
+            arg.get_long().map(|long| (long.to_string(), help))
+        })
+        .collect();
+    match shell {
+        Shell::Bash => {
+            let words: Vec<String> = flags.iter().map(|(long, _)| format!("--{long}")).collect();
+            format!(
+                "_{name}() {{\n    local cur=${{COMP_WORDS[COMP_CWORD]}}\n    if [[ $cur == -* ]]; then\n        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n    else\n        COMPREPLY=($(compgen -W \"$({name} --complete-topics 2>/dev/null)\" -- \"$cur\"))\n    fi\n}}\ncomplete -F _{name} {name}\n",
+                words.join(" ")
+            )
+        }
+        Shell::Zsh => {
+            let words: Vec<String> = flags.iter().map(|(long, _)| format!("--{long}")).collect();
+            format!(
+                "#compdef {name}\n_{name}() {{\n    if [[ $PREFIX == -* ]]; then\n        compadd -- {}\n    else\n        compadd -- ${{(f)\"$({name} --complete-topics 2>/dev/null)\"}}\n    fi\n}}\ncompdef _{name} {name}\n",
+                words.join(" ")
+            )
+        }
+        Shell::Fish => {
+            let mut out =
+                format!("complete -c {name} -f -a '({name} --complete-topics 2>/dev/null)'\n");
+            for (long, help) in flags {
+                out.push_str(&format!(
+                    "complete -c {name} -l {long} -d '{}'\n",
+                    help.replace('\'', "\\'")
+                ));
+            }
+            out
+        }
+    }
+}
+
+pub fn topic_words(entries: &[PageEntry]) -> Vec<String> {
+    let mut names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+    names.sort_unstable();
+    names.dedup();
+    let mut sections: Vec<&str> = entries.iter().map(|entry| entry.section.as_str()).collect();
+    sections.sort_unstable();
+    sections.dedup();
+    sections
+        .into_iter()
+        .chain(names)
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, section: &str) -> PageEntry {
+        PageEntry {
+            name: name.to_string(),
+            section: section.to_string(),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn lists_sections_then_names_once() {
+        let entries = [entry("printf", "3"), entry("printf", "1"), entry("ls", "1")];
+        assert_eq!(topic_words(&entries), vec!["1", "3", "ls", "printf"]);
+    }
+
+    #[test]
+    fn scripts_complete_flags_and_topics() {
+        let command = Command::new("manifold")
+            .arg(
+                clap::Arg::new("width")
+                    .long("width")
+                    .help("Width of the text"),
+            )
+            .arg(clap::Arg::new("secret").long("secret").hide(true));
+        let bash = script(Shell::Bash, &command);
+        assert!(bash.contains("compgen -W \"--width\""), "{bash}");
+        assert!(bash.contains("manifold --complete-topics"));
+        assert!(bash.ends_with("complete -F _manifold manifold\n"));
+        let zsh = script(Shell::Zsh, &command);
+        assert!(zsh.starts_with("#compdef manifold\n"));
+        let fish = script(Shell::Fish, &command);
+        assert!(fish.contains("complete -c manifold -l width -d 'Width of the text'\n"));
+        assert!(!fish.contains("secret"));
+    }
+}
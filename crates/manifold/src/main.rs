@@ -1,11 +1,43 @@
-use app::{Action, App, UpdateOutcome};
-use clap::Parser;
-use input::map_event;
-use platform::{Event, EventStream, TerminalContext};
+use app::{
+    Action, App, Effect, ExternalTool, MIN_CONTENT_WIDTH, PageRef, ThemeVariant, UpdateOutcome,
+    parse_page_ref, section_lines,
+};
+use clap::{CommandFactory, Parser};
+use config::{Backend, Background, Config, ConfigError, KeymapPreset};
+use input::Keymap;
+use ipc::{IpcCommand, IpcServer};
+use platform::{DefaultTerminal, Event, IdleBackoff, TerminalContext};
 use render::{
-    ArgsInterpretation, ManRenderer, RenderError, SystemManRenderer, ValidationError, classify_args,
+    ArgsInterpretation, CheatRenderer, ManRenderer, OnlineRenderer, PageEntry, RenderError,
+    SystemManRenderer, ValidationError, WELCOME_PAGE, WELCOME_SECTION, WelcomeRenderer,
+    build_page_index, classify_args, clean_formatted_text, find_executable, is_local_page,
+    load_page_index, save_page_index,
 };
+use std::env;
 use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Read, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+use store::{
+    Bookmarks, History, PinnedPages, PositionStore, SearchHistory, SessionStore, SnapshotStore,
+    format_timestamp,
+};
+use ui::Theme;
+
+mod completions;
+
+#[cfg(not(any(feature = "crossterm", feature = "termion", feature = "termwiz")))]
+compile_error!("manifold needs one of the crossterm, termion or termwiz features");
+
+type Terminal = TerminalContext<DefaultTerminal>;
+
+use completions::Shell;
 
 #[derive(Parser, Debug)]
 #[command(name = "manifold", about = "Tabbed CLI man page reader", version)]
@@ -16,6 +48,104 @@ struct Cli {
         help = "Man page to open (TOPIC or SECTION TOPIC)"
     )]
     args: Vec<String>,
+    #[arg(
+        long,
+        help = "Read a preformatted page from stdin (for use as MANPAGER)"
+    )]
+    as_pager: bool,
+    #[arg(
+        long,
+        help = "Print the shell line that registers manifold as MANPAGER"
+    )]
+    init: bool,
+    #[arg(
+        long,
+        value_name = "SHELL",
+        help = "Print a completion script for bash, zsh or fish"
+    )]
+    completions: Option<Shell>,
+    #[arg(long, hide = true)]
+    complete_topics: bool,
+    #[arg(
+        long,
+        help = "Mark paragraphs that changed since the page was last opened"
+    )]
+    show_changes: bool,
+    #[arg(
+        long,
+        help = "Keep rendered pages on disk and only the visible lines in memory"
+    )]
+    low_mem: bool,
+    #[arg(long, help = "Fetch pages from man7.org when they are not installed")]
+    online: bool,
+    #[arg(
+        long,
+        value_name = "BACKEND",
+        help = "Renderer to use (man or online), overriding the config file"
+    )]
+    backend: Option<Backend>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Read settings from PATH instead of the default config file"
+    )]
+    config: Option<PathBuf>,
+    #[arg(long, help = "Print the visited-pages history and exit")]
+    history: bool,
+    #[arg(
+        long,
+        help = "Always open pages at the top instead of the last position"
+    )]
+    no_restore_position: bool,
+    #[arg(
+        long,
+        help = "Disable syntax highlighting of code in EXAMPLES sections"
+    )]
+    no_highlight: bool,
+    #[arg(long, help = "Do not set the terminal window title")]
+    no_title: bool,
+    #[arg(
+        long,
+        help = "Print the rendered page(s) to stdout instead of opening the viewer"
+    )]
+    dump: bool,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Print only the named section of the page(s), e.g. \"RETURN VALUE\" (implies --dump)"
+    )]
+    print_section: Option<String>,
+    #[arg(
+        long,
+        value_name = "ANCHOR",
+        help = "Open the page(s) scrolled to a section or option, like :goto"
+    )]
+    jump: Option<String>,
+    #[arg(
+        short,
+        long,
+        help = "Open every section that has the topic, one tab each (like man -a)"
+    )]
+    all: bool,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Restore the tabs and layout saved with :mksession NAME"
+    )]
+    session: Option<String>,
+    #[arg(long, value_name = "COLUMNS", help = "Width of the text column")]
+    width: Option<u16>,
+    #[arg(
+        long,
+        help = "Open the pages in an already running instance if there is one"
+    )]
+    reuse: bool,
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        help = "Write a log to manifold.log in the state directory (error, warn, info, debug, trace)"
+    )]
+    log_level: Option<tracing::Level>,
 }
 
 type PageTopics = Vec<String>;
@@ -23,7 +153,19 @@ type PageSection = Option<String>;
 type PageSelection = (PageTopics, PageSection);
 const WIDTH_STEP: u16 = 5;
 const DEFAULT_CONTENT_WIDTH: u16 = 80;
-const MIN_CONTENT_WIDTH: u16 = 15;
+const PAGER_COMMAND: &str = "manifold --as-pager";
+const LOG_FILE: &str = "manifold.log";
+const PRERENDER_DELAY: Duration = Duration::from_millis(100);
+const STREAM_POLL: Duration = Duration::from_millis(30);
+const PAGE_INDEX_FILE: &str = "page-index.tsv";
+#[cfg(unix)]
+const DEFAULT_EDITOR: &str = "vi";
+#[cfg(unix)]
+const DEFAULT_PAGER: &str = "less";
+#[cfg(not(unix))]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(unix))]
+const DEFAULT_PAGER: &str = "more";
 
 fn resolve_initial_pages(args: &[String]) -> Result<Option<PageSelection>, ValidationError> {
     match args {
@@ -43,17 +185,80 @@ fn resolve_initial_pages(args: &[String]) -> Result<Option<PageSelection>, Valid
     }
 }
 
-fn min_content_width(terminal_width: u16) -> u16 {
-    terminal_width.min(MIN_CONTENT_WIDTH)
+fn apply_jump((topics, section): PageSelection, jump: Option<&str>) -> PageSelection {
+    let Some(jump) = jump else {
+        return (topics, section);
+    };
+    let topics = topics
+        .into_iter()
+        .map(|topic| match parse_page_ref(&topic).anchor {
+            Some(_) => topic,
+            None => format!("{topic}#{jump}"),
+        })
+        .collect();
+    (topics, section)
+}
+
+fn every_section(renderer: &dyn ManRenderer, (topics, section): PageSelection) -> PageSelection {
+    if section.is_some() {
+        return (topics, section);
+    }
+    let topics = topics
+        .into_iter()
+        .flat_map(|topic| {
+            let sections = renderer.sections(&topic).unwrap_or_default();
+            if sections.is_empty() {
+                vec![topic]
+            } else {
+                sections
+                    .iter()
+                    .map(|section| format!("{topic}({section})"))
+                    .collect()
+            }
+        })
+        .collect();
+    (topics, None)
+}
+
+fn pager_init_line(shell: &str) -> String {
+    if shell.rsplit('/').next() == Some("fish") {
+        format!("set -gx MANPAGER '{PAGER_COMMAND}'")
+    } else {
+        format!("export MANPAGER='{PAGER_COMMAND}'")
+    }
+}
+
+fn read_pager_input(mut reader: impl Read) -> io::Result<Vec<String>> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    Ok(clean_formatted_text(&String::from_utf8_lossy(&bytes)))
 }
 
-fn default_content_width(terminal_width: u16) -> u16 {
-    terminal_width.min(DEFAULT_CONTENT_WIDTH)
+fn reads_piped_page(cli: &Cli, stdin_is_terminal: bool) -> bool {
+    !stdin_is_terminal && cli.args.is_empty() && cli.session.is_none()
+}
+
+fn shows_welcome(cli: &Cli, has_tabs: bool) -> bool {
+    !has_tabs && cli.args.is_empty() && cli.session.is_none()
+}
+
+fn pager_page_ref(man_pn: Option<String>) -> PageRef {
+    match man_pn.filter(|value| !value.trim().is_empty()) {
+        Some(value) => parse_page_ref(value.trim()),
+        None => PageRef {
+            name: "stdin".to_string(),
+            section: None,
+            anchor: None,
+        },
+    }
+}
+
+fn default_content_width(terminal_width: u16, preferred: Option<u16>) -> u16 {
+    terminal_width.min(preferred.unwrap_or(DEFAULT_CONTENT_WIDTH))
 }
 
 fn clamp_content_width(width: u16, terminal_width: u16) -> u16 {
-    let min_width = min_content_width(terminal_width);
-    width.clamp(min_width, terminal_width)
+    width.min(terminal_width).max(MIN_CONTENT_WIDTH)
 }
 
 fn apply_width_action(width: u16, terminal_width: u16, action: &Action) -> Option<u16> {
@@ -79,26 +284,33 @@ enum LoopOutcome {
 
 fn handle_event(
     app: &mut App,
+    keymap: &Keymap,
     renderer: &dyn ManRenderer,
     content_width: &mut u16,
     terminal_width: &mut u16,
     content_height: &mut usize,
     event: Event,
 ) -> Result<LoopOutcome, RenderError> {
-    let Some(action) = map_event(event, app.mode()) else {
+    let Some(action) = keymap.map_event(event, app.mode()) else {
         return Ok(LoopOutcome::NoRedraw);
     };
 
     if let Action::Resize(width, height) = action {
         *terminal_width = width.max(1);
         *content_width = clamp_content_width(*content_width, *terminal_width);
-        *content_height = ui::content_height(height);
+        *content_height = ui::content_height(height, app.zen());
     }
     if let Some(updated_width) = apply_width_action(*content_width, *terminal_width, &action) {
         *content_width = updated_width;
     }
 
+    let zen = app.zen();
     let outcome = app.update(action, renderer, *content_width, *content_height)?;
+    if app.zen() != zen {
+        let rows = *content_height + usize::from(ui::chrome_height(zen));
+        *content_height = rows.saturating_sub(usize::from(ui::chrome_height(app.zen())));
+        app.resize_active(renderer, *content_width, *content_height)?;
+    }
     if outcome == UpdateOutcome::Quit {
         return Ok(LoopOutcome::Quit);
     }
@@ -106,53 +318,746 @@ fn handle_event(
     Ok(LoopOutcome::Redraw)
 }
 
+fn handle_ipc(
+    app: &mut App,
+    command: IpcCommand,
+    renderer: &dyn ManRenderer,
+    content_width: u16,
+    content_height: usize,
+) -> Result<LoopOutcome, RenderError> {
+    tracing::debug!(?command, "ipc command");
+    match command {
+        IpcCommand::Open { topic, section } => {
+            if let Err(err) = app.open_pages(
+                vec![topic],
+                section,
+                renderer,
+                content_width,
+                content_height,
+            ) {
+                tracing::warn!("ipc open failed: {err}");
+                app.set_error(err.to_string());
+            }
+        }
+        IpcCommand::FocusTab(number) => {
+            app.update(
+                Action::FocusTab(number.saturating_sub(1)),
+                renderer,
+                content_width,
+                content_height,
+            )?;
+        }
+        IpcCommand::Quit => return Ok(LoopOutcome::Quit),
+    }
+    Ok(LoopOutcome::Redraw)
+}
+
+fn apply_effect(terminal: &mut Terminal, effect: Effect) -> io::Result<()> {
+    match effect {
+        Effect::Copy(text) => terminal.copy_to_clipboard(&text),
+        Effect::OpenExternal {
+            tool,
+            path,
+            temporary,
+        } => {
+            let program = external_program(tool, |name| env::var(name).ok());
+            let result = terminal.run_external(&mut external_command(&program, &path));
+            if temporary {
+                let _ = std::fs::remove_file(&path);
+            }
+            result.map(|_| ())
+        }
+        Effect::ReloadConfig | Effect::SetTheme(_) => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn external_command(program: &str, path: &Path) -> Command {
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(format!("{program} \"$1\""))
+        .arg("manifold")
+        .arg(path);
+    command
+}
+
+#[cfg(windows)]
+fn external_command(program: &str, path: &Path) -> Command {
+    use std::os::windows::process::CommandExt;
+    let mut command = Command::new("cmd");
+    command
+        .arg("/C")
+        .raw_arg(format!("{program} \"{}\"", path.display()));
+    command
+}
+
+fn external_program(tool: ExternalTool, var: impl Fn(&str) -> Option<String>) -> String {
+    let (variables, fallback): (&[&str], &str) = match tool {
+        ExternalTool::Editor => (&["VISUAL", "EDITOR"], DEFAULT_EDITOR),
+        ExternalTool::Pager => (&["PAGER"], DEFAULT_PAGER),
+    };
+    variables
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+fn dump_pages(
+    renderer: &dyn ManRenderer,
+    (topics, section): PageSelection,
+    width: u16,
+    only_section: Option<&str>,
+    mut out: impl Write,
+) -> Result<(), Box<dyn Error>> {
+    for (index, topic) in topics.iter().enumerate() {
+        let page = parse_page_ref(topic);
+        let section = page.section.or_else(|| section.clone());
+        let lines = renderer.render(&page.name, section.as_deref(), width.max(1))?;
+        let lines = match only_section {
+            Some(name) => section_lines(&lines, name)
+                .ok_or_else(|| format!("{topic} has no {} section", name.trim()))?,
+            None => &lines[..],
+        };
+        if index > 0 {
+            writeln!(out)?;
+        }
+        for line in lines {
+            writeln!(out, "{line}")?;
+        }
+    }
+    Ok(())
+}
+
+fn reuse_commands((topics, section): &PageSelection) -> Result<Vec<String>, String> {
+    topics
+        .iter()
+        .map(|topic| {
+            let topic = if is_local_page(topic) {
+                let path = std::fs::canonicalize(topic).map_err(|err| format!("{topic}: {err}"))?;
+                path.to_str()
+                    .ok_or_else(|| format!("{topic}: path is not valid UTF-8"))?
+                    .to_string()
+            } else {
+                topic.clone()
+            };
+            Ok(ipc::format_command(&IpcCommand::Open {
+                topic,
+                section: section.clone(),
+            }))
+        })
+        .collect()
+}
+
+fn forward_to_running(path: &Path, pages: Option<&PageSelection>) -> Result<bool, Box<dyn Error>> {
+    let commands = match pages {
+        Some(pages) => reuse_commands(pages)?,
+        None => Vec::new(),
+    };
+    let replies = match ipc::send(path, &commands) {
+        Ok(replies) => replies,
+        Err(err)
+            if matches!(
+                err.kind(),
+                io::ErrorKind::NotFound
+                    | io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::Unsupported
+            ) =>
+        {
+            return Ok(false);
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if let Some(error) = replies.iter().find(|reply| reply.as_str() != "ok") {
+        return Err(error.clone().into());
+    }
+    Ok(true)
+}
+
+fn ignore_broken_pipe(result: Result<(), Box<dyn Error>>) -> Result<(), Box<dyn Error>> {
+    match result {
+        Err(err)
+            if err
+                .downcast_ref::<io::Error>()
+                .is_some_and(|err| err.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            Ok(())
+        }
+        other => other,
+    }
+}
+
+fn window_title(app: &App) -> String {
+    if app.has_tabs() {
+        format!("manifold: {}", app.title())
+    } else {
+        "manifold".to_string()
+    }
+}
+
+fn update_window_title(
+    terminal: &mut Terminal,
+    app: &App,
+    current: &mut Option<String>,
+) -> io::Result<()> {
+    let title = window_title(app);
+    if current.as_ref() == Some(&title) {
+        return Ok(());
+    }
+    terminal.set_title(&title)?;
+    *current = Some(title);
+    Ok(())
+}
+
+fn make_renderer(online: bool, config: &Config) -> Box<dyn ManRenderer> {
+    let section_order = &config.section_order;
+    let mut system = SystemManRenderer::with_section_order(section_order.to_vec());
+    if let Some(options) = &config.roff_options {
+        system = system.with_roff_options(options);
+    }
+    if !online && find_executable("man").is_none() {
+        tracing::warn!("no man command found and the online renderer is off");
+    }
+    match store::cache_dir().filter(|_| online) {
+        Some(dir) => Box::new(WelcomeRenderer::new(
+            OnlineRenderer::new(system, dir.join("online")).with_sections(section_order.to_vec()),
+        )),
+        None => Box::new(WelcomeRenderer::new(system)),
+    }
+}
+
+fn apply_config(config: &Config, light_terminal: bool) -> Result<(Keymap, Theme), ConfigError> {
+    let mut keymap = Keymap::new(config.scroll_step);
+    if config.keymap == KeymapPreset::Less {
+        keymap = keymap.with_less_bindings();
+    }
+    for (key, action) in &config.keys {
+        keymap
+            .bind(key, action)
+            .map_err(|err| ConfigError::Invalid(format!("keys.{key}: {err}")))?;
+    }
+    keymap
+        .set_leader(&config.leader)
+        .map_err(|err| ConfigError::Invalid(format!("leader: {err}")))?;
+    for (key, action) in &config.chords {
+        keymap
+            .bind_chord(key, action)
+            .map_err(|err| ConfigError::Invalid(format!("chords.{key}: {err}")))?;
+    }
+    Ok((keymap, build_theme(config, light_terminal)?))
+}
+
+fn build_theme(config: &Config, light_terminal: bool) -> Result<Theme, ConfigError> {
+    let light = match config.display.background {
+        Background::Auto => light_terminal,
+        Background::Dark => false,
+        Background::Light => true,
+    };
+    let mut theme = match (config.display.high_contrast, light) {
+        (false, false) => Theme::default(),
+        (true, false) => Theme::high_contrast(),
+        (false, true) => Theme::light(),
+        (true, true) => Theme::high_contrast_light(),
+    };
+    theme.reverse_video = config.display.reverse_video;
+    theme.ascii = config.display.ascii;
+    for (name, color) in &config.theme {
+        theme
+            .set(name, color)
+            .map_err(|err| ConfigError::Invalid(format!("theme.{name}: {err}")))?;
+    }
+    for (term, style) in &config.highlights {
+        theme
+            .add_highlight(style)
+            .map_err(|err| ConfigError::Invalid(format!("highlights.{term}: {err}")))?;
+    }
+    Ok(theme)
+}
+
+struct Settings {
+    config: Config,
+    keymap: Keymap,
+    theme: Theme,
+    light_terminal: bool,
+}
+
+impl Settings {
+    fn new(config: Config, light_terminal: bool) -> Result<Self, ConfigError> {
+        let (keymap, theme) = apply_config(&config, light_terminal)?;
+        Ok(Self {
+            config,
+            keymap,
+            theme,
+            light_terminal,
+        })
+    }
+
+    fn load(path: Option<&Path>, light_terminal: bool) -> Result<Self, ConfigError> {
+        match path {
+            Some(path) => Self::new(config::load(path)?, light_terminal),
+            None => Self::new(Config::default(), light_terminal),
+        }
+    }
+
+    fn set_light_terminal(&mut self, light_terminal: bool) -> Result<(), ConfigError> {
+        self.light_terminal = light_terminal;
+        self.theme = build_theme(&self.config, light_terminal)?;
+        Ok(())
+    }
+
+    fn set_theme(&mut self, variant: ThemeVariant) -> Result<(), ConfigError> {
+        self.config.display.background = match variant {
+            ThemeVariant::Auto => Background::Auto,
+            ThemeVariant::Dark => Background::Dark,
+            ThemeVariant::Light => Background::Light,
+        };
+        self.theme = build_theme(&self.config, self.light_terminal)?;
+        Ok(())
+    }
+}
+
+fn reload_config(
+    settings: &mut Settings,
+    loaded: Result<Settings, ConfigError>,
+    app: &mut App,
+    renderer: &dyn ManRenderer,
+    cli: &Cli,
+    content_width: &mut u16,
+    (terminal_width, content_height): (u16, usize),
+) -> Result<(), RenderError> {
+    let previous_width = settings.config.width;
+    match loaded {
+        Ok(loaded) => {
+            app.set_code_highlighting(loaded.config.highlight_code && !cli.no_highlight);
+            app.set_search_wrap(loaded.config.search.wrap);
+            app.set_keywords(loaded.config.highlights.keys().cloned().collect());
+            app.set_memory_budget(memory_budget(&loaded.config));
+            *settings = loaded;
+            app.set_error("Reloaded config");
+        }
+        Err(err) => {
+            tracing::error!("config not reloaded: {err}");
+            app.set_error(format!("Config not reloaded: {err}"));
+        }
+    }
+    if settings.config.width != previous_width && cli.width.is_none() {
+        *content_width = clamp_content_width(
+            default_content_width(terminal_width, settings.config.width),
+            terminal_width,
+        );
+        app.resize_active(renderer, *content_width, content_height)?;
+    }
+    Ok(())
+}
+
+fn memory_budget(config: &Config) -> Option<usize> {
+    config
+        .memory_budget_mb
+        .map(|megabytes| megabytes.saturating_mul(1024 * 1024))
+}
+
+fn refresh_page_index(path: Option<PathBuf>) -> Receiver<Vec<PageEntry>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || match build_page_index() {
+        Ok(entries) if !entries.is_empty() => {
+            if let Some(path) = &path
+                && let Err(err) = save_page_index(path, &entries)
+            {
+                tracing::warn!(path = %path.display(), "could not save page index: {err}");
+            }
+            tracing::debug!(pages = entries.len(), "page index refreshed");
+            let _ = sender.send(entries);
+        }
+        Ok(_) => tracing::debug!("man -k returned no pages"),
+        Err(err) => tracing::warn!("could not build page index: {err}"),
+    });
+    receiver
+}
+
+fn init_logging(level: tracing::Level) -> io::Result<PathBuf> {
+    let dir = store::state_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no state directory"))?;
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(LOG_FILE);
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_max_level(level)
+        .init();
+    Ok(path)
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
+    platform::install_panic_hook();
+    match panic::catch_unwind(run) {
+        Ok(result) => result,
+        Err(_) => {
+            platform::restore_terminal();
+            std::process::exit(101);
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
     let cli = Cli::parse();
-    let renderer = SystemManRenderer::new();
+    if let Some(level) = cli.log_level {
+        match init_logging(level) {
+            Ok(path) => tracing::info!(
+                version = env!("CARGO_PKG_VERSION"),
+                args = ?cli.args,
+                "logging to {}",
+                path.display()
+            ),
+            Err(err) => eprintln!("manifold: logging disabled: {err}"),
+        }
+    }
+    if cli.init {
+        println!(
+            "{}",
+            pager_init_line(&env::var("SHELL").unwrap_or_default())
+        );
+        return Ok(());
+    }
+    if let Some(shell) = cli.completions {
+        print!("{}", completions::script(shell, &Cli::command()));
+        return Ok(());
+    }
+    if cli.complete_topics {
+        let index_path = store::cache_dir().map(|dir| dir.join(PAGE_INDEX_FILE));
+        let mut entries = index_path
+            .as_deref()
+            .map(load_page_index)
+            .unwrap_or_default();
+        if entries.is_empty() {
+            entries = build_page_index().unwrap_or_default();
+            if let Some(path) = &index_path
+                && !entries.is_empty()
+            {
+                let _ = save_page_index(path, &entries);
+            }
+        }
+        let mut out = io::stdout().lock();
+        let written = completions::topic_words(&entries)
+            .iter()
+            .try_for_each(|word| writeln!(out, "{word}"));
+        return ignore_broken_pipe(written.map_err(Into::into));
+    }
+    if cli.history {
+        for entry in History::open_default()
+            .map(|history| history.entries())
+            .unwrap_or_default()
+        {
+            println!("{}  {}", format_timestamp(entry.timestamp), entry.page);
+        }
+        return Ok(());
+    }
+    if let Some(path) = cli.config.as_deref()
+        && !path.is_file()
+    {
+        eprintln!("manifold: {}: no such config file", path.display());
+        std::process::exit(2);
+    }
+    let sessions = SessionStore::open_default();
+    if let Some(name) = cli.session.as_deref()
+        && sessions
+            .as_ref()
+            .and_then(|sessions| sessions.load(name))
+            .is_none()
+    {
+        eprintln!("manifold: no session named {name}");
+        std::process::exit(2);
+    }
+    let config_file = cli.config.clone().or_else(config::config_path);
+    let mut settings = match Settings::load(config_file.as_deref(), false) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            let path = config_file.unwrap_or_else(|| "config.toml".into());
+            tracing::error!("{}: {err}", path.display());
+            eprintln!("manifold: {}: {err}", path.display());
+            std::process::exit(2);
+        }
+    };
+    if cli.reuse
+        && let Some(path) = ipc::socket_path()
+        && forward_to_running(
+            &path,
+            resolve_initial_pages(&cli.args)?
+                .map(|pages| apply_jump(pages, cli.jump.as_deref()))
+                .as_ref(),
+        )?
+    {
+        return Ok(());
+    }
+    let backend = cli.backend.unwrap_or(settings.config.renderer);
+    let renderer = make_renderer(cli.online || backend == Backend::Online, &settings.config);
+    let renderer = renderer.as_ref();
+    let pager_lines = if cli.as_pager {
+        Some(read_pager_input(io::stdin().lock())?)
+    } else if reads_piped_page(&cli, io::stdin().is_terminal()) {
+        Some(read_pager_input(io::stdin().lock())?).filter(|lines| !lines.is_empty())
+    } else {
+        None
+    };
+    if cli.dump || cli.print_section.is_some() || !io::stdout().is_terminal() {
+        let width = cli
+            .width
+            .or(settings.config.width)
+            .unwrap_or(DEFAULT_CONTENT_WIDTH);
+        let mut out = io::stdout().lock();
+        let only_section = cli.print_section.as_deref();
+        if let Some(lines) = &pager_lines {
+            let lines = match only_section {
+                Some(name) => section_lines(lines, name)
+                    .ok_or_else(|| format!("no {} section in the input", name.trim()))?,
+                None => &lines[..],
+            };
+            let written = lines.iter().try_for_each(|line| writeln!(out, "{line}"));
+            return ignore_broken_pipe(written.map_err(Into::into));
+        }
+        match resolve_initial_pages(&cli.args)? {
+            Some(pages) => {
+                let pages = if cli.all {
+                    every_section(renderer, pages)
+                } else {
+                    pages
+                };
+                return ignore_broken_pipe(dump_pages(renderer, pages, width, only_section, out));
+            }
+            None if cli.dump || only_section.is_some() => {
+                return Err("--dump needs a page to render".into());
+            }
+            None => {}
+        }
+    }
 
-    let mut terminal = TerminalContext::new()?;
-    let events = EventStream::new();
+    let mut terminal = Terminal::new()?;
+    let light_terminal = terminal.background() == Some(platform::Background::Light);
+    settings.set_light_terminal(light_terminal)?;
+    if settings.config.mouse {
+        terminal.enable_mouse()?;
+    }
 
     let size = terminal.terminal_mut().size()?;
     let mut terminal_width = size.width.max(1);
-    let mut content_width = default_content_width(terminal_width);
-    let mut content_height = ui::content_height(size.height);
-    let initial_pages = resolve_initial_pages(&cli.args)?;
+    let mut content_width = clamp_content_width(
+        default_content_width(terminal_width, cli.width.or(settings.config.width)),
+        terminal_width,
+    );
+    let mut content_height = ui::content_height(size.height, false);
+    let mut initial_pages =
+        resolve_initial_pages(&cli.args)?.map(|pages| apply_jump(pages, cli.jump.as_deref()));
+    if initial_pages.is_none()
+        && pager_lines.is_none()
+        && cli.session.is_none()
+        && let Some(page) = &settings.config.startup_page
+    {
+        let args: Vec<String> = page.split_whitespace().map(str::to_string).collect();
+        initial_pages = resolve_initial_pages(&args)?;
+    }
     let mut app = App::empty();
+    app.set_screen_width(terminal_width);
+    app.set_low_memory(cli.low_mem);
+    app.set_code_highlighting(settings.config.highlight_code && !cli.no_highlight);
+    app.set_search_wrap(settings.config.search.wrap);
+    app.set_keywords(settings.config.highlights.keys().cloned().collect());
+    app.set_memory_budget(memory_budget(&settings.config));
+    if let Some(history) = History::open_default() {
+        app.track_history(history);
+    }
+    if let Some(searches) = SearchHistory::open_default() {
+        app.track_searches(searches);
+    }
+    if let Some(bookmarks) = Bookmarks::open_default() {
+        app.track_bookmarks(bookmarks);
+    }
+    if let Some(dir) = store::cache_dir() {
+        app.track_cheats(CheatRenderer::new(dir.join("cheat")));
+    }
+    if settings.config.restore_position
+        && !cli.no_restore_position
+        && let Some(positions) = PositionStore::open_default()
+    {
+        app.track_positions(positions);
+    }
+    if cli.show_changes
+        && let Some(store) = SnapshotStore::open_default()
+    {
+        app.track_changes(store);
+    }
+    let index_path = store::cache_dir().map(|dir| dir.join(PAGE_INDEX_FILE));
+    if let Some(path) = &index_path {
+        app.set_page_index(load_page_index(path));
+    }
+    let page_index = refresh_page_index(index_path);
+    if let Some(pins) = PinnedPages::open_default() {
+        app.track_pins(pins);
+        app.open_pinned(renderer, content_width, content_height)?;
+    }
+    if let Some(sessions) = sessions {
+        app.track_sessions(sessions);
+        if let Some(name) = cli.session.as_deref() {
+            app.open_session(name, renderer, content_width, content_height)?;
+        }
+    }
+    if let Some(lines) = pager_lines {
+        let page = pager_page_ref(env::var("MAN_PN").ok());
+        app.open_text(page.name, page.section, lines, content_height);
+    }
     if let Some((topics, section)) = initial_pages {
-        app.open_pages(topics, section, &renderer, content_width, content_height)?;
+        if cli.all && section.is_none() {
+            app.open_all_sections(topics, renderer, content_width, content_height)?;
+        } else {
+            app.open_pages(topics, section, renderer, content_width, content_height)?;
+        }
     }
-    app.resize_active(&renderer, content_width, content_height)?;
+    if shows_welcome(&cli, app.has_tabs()) {
+        app.open_pages(
+            vec![WELCOME_PAGE.to_string()],
+            Some(WELCOME_SECTION.to_string()),
+            renderer,
+            content_width,
+            content_height,
+        )?;
+    }
+    app.resize_active(renderer, content_width, content_height)?;
+    let ipc_server = match ipc::socket_path().filter(|_| settings.config.ipc || cli.reuse) {
+        Some(path) => match IpcServer::bind(&path) {
+            Ok(server) => Some(server),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), "control socket disabled: {err}");
+                app.set_error(format!("Control socket disabled: {err}"));
+                None
+            }
+        },
+        None => None,
+    };
 
     terminal
         .terminal_mut()
-        .draw(|frame| ui::draw(frame, &app))?;
+        .draw(|frame| ui::draw(frame, &app, &settings.theme))?;
+    let mut config_stamp = config_file.as_deref().and_then(config::modified);
+    let mut window_title = None;
+    if settings.config.window_title && !cli.no_title {
+        update_window_title(&mut terminal, &app, &mut window_title)?;
+    }
 
+    let mut idle = IdleBackoff::default();
     loop {
-        match handle_event(
-            &mut app,
-            &renderer,
-            &mut content_width,
-            &mut terminal_width,
-            &mut content_height,
-            events.next()?,
-        )? {
+        let prerender = app.prerender_pending();
+        let watching = app.watches_files() || settings.config.watch_config;
+        let tick = Duration::from_millis(settings.config.tick_ms);
+        let timeout = if app.streaming() {
+            Some(STREAM_POLL)
+        } else if prerender {
+            Some(tick.min(PRERENDER_DELAY))
+        } else if ipc_server.is_some() {
+            Some(tick)
+        } else if watching {
+            Some(idle.timeout(tick))
+        } else {
+            None
+        };
+        let event = terminal.wait_event(timeout)?;
+        if let Ok(entries) = page_index.try_recv() {
+            app.set_page_index(entries);
+        }
+        let woken = event.is_some();
+        let started = Instant::now();
+        let mut outcome = match event {
+            Some(event) => handle_event(
+                &mut app,
+                &settings.keymap,
+                renderer,
+                &mut content_width,
+                &mut terminal_width,
+                &mut content_height,
+                event,
+            )?,
+            None => {
+                if prerender {
+                    app.prerender_next(renderer);
+                }
+                let mut outcome = LoopOutcome::NoRedraw;
+                if settings.config.watch_config
+                    && config_file.as_deref().and_then(config::modified) != config_stamp
+                {
+                    config_stamp = config_file.as_deref().and_then(config::modified);
+                    reload_config(
+                        &mut settings,
+                        Settings::load(config_file.as_deref(), light_terminal),
+                        &mut app,
+                        renderer,
+                        &cli,
+                        &mut content_width,
+                        (terminal_width, content_height),
+                    )?;
+                    outcome = LoopOutcome::Redraw;
+                }
+                while outcome != LoopOutcome::Quit
+                    && let Some(command) = ipc_server.as_ref().and_then(IpcServer::try_recv)
+                {
+                    outcome =
+                        handle_ipc(&mut app, command, renderer, content_width, content_height)?;
+                }
+                outcome
+            }
+        };
+        if app.reload_changed(renderer, content_width, content_height)
+            && outcome == LoopOutcome::NoRedraw
+        {
+            outcome = LoopOutcome::Redraw;
+        }
+        if app.receive_streams(content_height) && outcome == LoopOutcome::NoRedraw {
+            outcome = LoopOutcome::Redraw;
+        }
+        if woken || outcome != LoopOutcome::NoRedraw {
+            idle.reset();
+        } else {
+            idle.idle();
+        }
+        match outcome {
             LoopOutcome::NoRedraw => {}
             LoopOutcome::Redraw => {
+                for effect in app.drain_effects() {
+                    if let Effect::SetTheme(variant) = effect {
+                        settings.set_theme(variant)?;
+                    } else if effect == Effect::ReloadConfig {
+                        config_stamp = config_file.as_deref().and_then(config::modified);
+                        reload_config(
+                            &mut settings,
+                            Settings::load(config_file.as_deref(), light_terminal),
+                            &mut app,
+                            renderer,
+                            &cli,
+                            &mut content_width,
+                            (terminal_width, content_height),
+                        )?;
+                    } else {
+                        apply_effect(&mut terminal, effect)?;
+                    }
+                }
                 terminal
                     .terminal_mut()
-                    .draw(|frame| ui::draw(frame, &app))?;
+                    .draw(|frame| ui::draw(frame, &app, &settings.theme))?;
+                app.record_frame(started.elapsed());
+                if settings.config.window_title && !cli.no_title {
+                    update_window_title(&mut terminal, &app, &mut window_title)?;
+                }
             }
             LoopOutcome::Quit => break,
         }
     }
 
+    app.save_positions()?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use store::TempDir;
 
     struct TestRenderer {
         lines: Vec<String>,
@@ -182,13 +1087,14 @@ mod tests {
     fn clamps_content_width_to_terminal_range() {
         assert_eq!(clamp_content_width(10, 50), 15);
         assert_eq!(clamp_content_width(80, 50), 50);
-        assert_eq!(clamp_content_width(5, 5), 5);
+        assert_eq!(clamp_content_width(80, 5), 15);
     }
 
     #[test]
     fn uses_default_content_width_with_terminal_cap() {
-        assert_eq!(default_content_width(300), 80);
-        assert_eq!(default_content_width(50), 50);
+        assert_eq!(default_content_width(300, None), 80);
+        assert_eq!(default_content_width(50, None), 50);
+        assert_eq!(default_content_width(300, Some(120)), 120);
     }
 
     #[test]
@@ -198,6 +1104,388 @@ mod tests {
         assert_eq!(apply_width_action(20, 50, &Action::ScrollUp(1)), None);
     }
 
+    #[test]
+    fn parses_topics_and_flags() {
+        let cli = Cli::parse_from([
+            "manifold",
+            "--backend",
+            "online",
+            "--config",
+            "alt.toml",
+            "--width",
+            "90",
+            "git-rebase",
+        ]);
+        assert_eq!(cli.backend, Some(Backend::Online));
+        assert_eq!(cli.config, Some(PathBuf::from("alt.toml")));
+        assert_eq!(cli.width, Some(90));
+        assert_eq!(
+            resolve_initial_pages(&cli.args).expect("pages"),
+            Some((vec!["git-rebase".to_string()], None))
+        );
+        let pages = (vec!["open".to_string(), "ls#-a".to_string()], None);
+        assert_eq!(
+            apply_jump(pages, Some("ERRORS")).0,
+            vec!["open#ERRORS", "ls#-a"]
+        );
+        let cli = Cli::parse_from(["manifold", "--dump", "2", "open"]);
+        assert!(cli.dump);
+        assert_eq!(cli.args, vec!["2", "open"]);
+        assert!(Cli::try_parse_from(["manifold", "--backend", "groff"]).is_err());
+    }
+
+    #[test]
+    fn prints_pager_init_line_for_shell() {
+        assert_eq!(
+            pager_init_line("/bin/bash"),
+            "export MANPAGER='manifold --as-pager'"
+        );
+        assert_eq!(
+            pager_init_line("/usr/bin/fish"),
+            "set -gx MANPAGER 'manifold --as-pager'"
+        );
+    }
+
+    #[test]
+    fn reads_preformatted_pager_input() {
+        let input = "L\u{8}LS\u{8}S(1)\n\tlist\n".as_bytes();
+        assert_eq!(
+            read_pager_input(input).expect("read"),
+            vec!["LS(1)".to_string(), "        list".to_string()]
+        );
+        let page = pager_page_ref(Some("ls(1)".to_string()));
+        assert_eq!(page.name, "ls");
+        assert_eq!(page.section.as_deref(), Some("1"));
+        assert_eq!(pager_page_ref(None).name, "stdin");
+        assert_eq!(
+            read_pager_input("\x1b[1mNAME\x1b[0m\n".as_bytes()).expect("read"),
+            vec!["NAME".to_string()]
+        );
+    }
+
+    #[test]
+    fn reads_a_piped_page_only_without_topics() {
+        let cli = Cli::try_parse_from(["manifold"]).expect("cli");
+        assert!(reads_piped_page(&cli, false));
+        assert!(!reads_piped_page(&cli, true));
+        let cli = Cli::try_parse_from(["manifold", "ls"]).expect("cli");
+        assert!(!reads_piped_page(&cli, false));
+        let cli = Cli::try_parse_from(["manifold", "--session", "work"]).expect("cli");
+        assert!(!reads_piped_page(&cli, false));
+    }
+
+    #[test]
+    fn welcomes_only_when_nothing_else_opens() {
+        let cli = Cli::try_parse_from(["manifold"]).expect("cli");
+        assert!(shows_welcome(&cli, false));
+        assert!(!shows_welcome(&cli, true));
+        let cli = Cli::try_parse_from(["manifold", "--session", "work"]).expect("cli");
+        assert!(!shows_welcome(&cli, false));
+        let renderer = WelcomeRenderer::new(TestRenderer {
+            lines: vec!["ls".to_string()],
+        });
+        let mut app = App::empty();
+        app.open_pages(
+            vec![WELCOME_PAGE.to_string()],
+            Some(WELCOME_SECTION.to_string()),
+            &renderer,
+            80,
+            10,
+        )
+        .expect("open");
+        assert_eq!(app.title(), "welcome(manifold)");
+        assert_eq!(app.lines()[0], "NAME");
+    }
+
+    #[test]
+    fn dumps_pages_separated_by_blank_lines() {
+        let renderer = TestRenderer {
+            lines: vec!["NAME".to_string(), "   tool".to_string()],
+        };
+        let mut out = Vec::new();
+        dump_pages(
+            &renderer,
+            (vec!["a".to_string(), "b(1)".to_string()], None),
+            80,
+            None,
+            &mut out,
+        )
+        .expect("dump");
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "NAME\n   tool\n\nNAME\n   tool\n"
+        );
+    }
+
+    #[test]
+    fn dumps_only_the_requested_section() {
+        let renderer = TestRenderer {
+            lines: [
+                "NAME",
+                "   tool",
+                "",
+                "RETURN VALUE",
+                "   zero",
+                "",
+                "ERRORS",
+                "   none",
+            ]
+            .iter()
+            .map(|line| line.to_string())
+            .collect(),
+        };
+        let mut out = Vec::new();
+        dump_pages(
+            &renderer,
+            (vec!["open(2)".to_string()], None),
+            80,
+            Some("return value"),
+            &mut out,
+        )
+        .expect("dump");
+        assert_eq!(String::from_utf8(out).unwrap(), "RETURN VALUE\n   zero\n");
+        let err = dump_pages(
+            &renderer,
+            (vec!["open(2)".to_string()], None),
+            80,
+            Some("BUGS"),
+            Vec::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "open(2) has no BUGS section");
+    }
+
+    #[test]
+    fn applies_config_keys_and_theme() {
+        let config =
+            config::parse("scroll_step = 2\n[keys]\nx = \"top\"\n[theme]\nadded = \"blue\"\n")
+                .expect("config");
+        let (keymap, theme) = apply_config(&config, false).expect("apply");
+        assert_eq!(
+            keymap.map_event(Event::Key(platform::KeyCode::Char('x')), &app::Mode::Normal),
+            Some(Action::GoTop)
+        );
+        let mut expected = Theme::default();
+        expected.set("added", "blue").expect("color");
+        assert_eq!(theme, expected);
+        let config = config::parse(
+            "[display]\nhigh_contrast = true\nascii = true\n[theme]\nhint = \"red\"\n",
+        )
+        .expect("config");
+        let (_, theme) = apply_config(&config, false).expect("apply");
+        let mut expected = Theme::high_contrast();
+        expected.ascii = true;
+        expected.set("hint", "red").expect("color");
+        assert_eq!(theme, expected);
+        let config = config::parse("[display]\nbackground = \"auto\"\n").expect("config");
+        let (_, theme) = apply_config(&config, true).expect("apply");
+        assert_eq!(theme, Theme::light());
+        let mut settings = Settings::new(config, true).expect("settings");
+        settings.set_theme(ThemeVariant::Dark).expect("dark");
+        assert_eq!(settings.theme, Theme::default());
+        settings.set_theme(ThemeVariant::Auto).expect("auto");
+        settings.set_light_terminal(false).expect("dark terminal");
+        assert_eq!(settings.theme, Theme::default());
+        let config = config::parse("[highlights]\nEINTR = \"loud\"").expect("config");
+        assert_eq!(
+            apply_config(&config, false).unwrap_err().to_string(),
+            "highlights.EINTR: unknown color \"loud\""
+        );
+        let config = config::parse("[keys]\nx = \"jump\"").expect("config");
+        assert_eq!(
+            apply_config(&config, false).unwrap_err().to_string(),
+            "keys.x: unknown action \"jump\""
+        );
+    }
+
+    #[test]
+    fn reloads_config_and_rerenders_on_width_change() {
+        let (mut app, renderer) = make_app();
+        let cli = Cli::parse_from(["manifold"]);
+        let mut settings = Settings::new(Config::default(), false).expect("defaults");
+        let mut content_width = 80;
+        let loaded = config::parse("width = 60\n[keys]\nx = \"top\"")
+            .and_then(|config| Settings::new(config, false));
+        reload_config(
+            &mut settings,
+            loaded,
+            &mut app,
+            &renderer,
+            &cli,
+            &mut content_width,
+            (100, 20),
+        )
+        .expect("reload");
+        assert_eq!(content_width, 60);
+        assert_eq!(app.status_message(), Some("Reloaded config"));
+        assert_eq!(
+            settings
+                .keymap
+                .map_event(Event::Key(platform::KeyCode::Char('x')), &app::Mode::Normal),
+            Some(Action::GoTop)
+        );
+
+        reload_config(
+            &mut settings,
+            config::parse("width = 5").and_then(|config| Settings::new(config, false)),
+            &mut app,
+            &renderer,
+            &cli,
+            &mut content_width,
+            (100, 20),
+        )
+        .expect("reload");
+        assert_eq!(content_width, 60);
+        assert_eq!(
+            app.status_message(),
+            Some("Config not reloaded: width must be at least 15")
+        );
+    }
+
+    #[test]
+    fn searches_through_the_keymap() {
+        let (mut app, renderer) = make_app();
+        let keymap = Keymap::default();
+        let (mut content_width, mut terminal_width, mut content_height) = (80, 100, 20);
+        let mut press = |app: &mut App, code: platform::KeyCode| {
+            handle_event(
+                app,
+                &keymap,
+                &renderer,
+                &mut content_width,
+                &mut terminal_width,
+                &mut content_height,
+                Event::Key(code),
+            )
+            .expect("event");
+        };
+        press(&mut app, platform::KeyCode::Char('/'));
+        assert!(matches!(app.mode(), app::Mode::Search { .. }));
+        for ch in "line 3".chars() {
+            press(&mut app, platform::KeyCode::Char(ch));
+        }
+        press(&mut app, platform::KeyCode::Enter);
+        assert_eq!(app.mode(), &app::Mode::Normal);
+        assert_eq!(app.search_query(), Some("line 3"));
+        assert_eq!(app.tabs()[0].search_index(), Some(0));
+        press(&mut app, platform::KeyCode::Char('n'));
+        press(&mut app, platform::KeyCode::Char('n'));
+        assert_eq!(app.tabs()[0].search_index(), Some(2));
+        press(&mut app, platform::KeyCode::Char('N'));
+        assert_eq!(app.tabs()[0].search_index(), Some(1));
+        press(&mut app, platform::KeyCode::Esc);
+        assert_eq!(app.search_query(), None);
+    }
+
+    #[test]
+    fn applies_control_socket_commands() {
+        let (mut app, renderer) = make_app();
+        let open = IpcCommand::Open {
+            topic: "printf".to_string(),
+            section: Some("3".to_string()),
+        };
+        assert_eq!(
+            handle_ipc(&mut app, open, &renderer, 80, 20).expect("open"),
+            LoopOutcome::Redraw
+        );
+        assert_eq!(app.tabs().len(), 2);
+        assert_eq!(app.title(), "printf(3)");
+        handle_ipc(&mut app, IpcCommand::FocusTab(1), &renderer, 80, 20).expect("focus");
+        assert_eq!(app.active_index(), 0);
+        assert_eq!(
+            handle_ipc(&mut app, IpcCommand::Quit, &renderer, 80, 20).expect("quit"),
+            LoopOutcome::Quit
+        );
+    }
+
+    #[test]
+    fn forwards_local_pages_as_absolute_paths() {
+        let manifest = std::fs::canonicalize("Cargo.toml").expect("manifest");
+        assert_eq!(
+            reuse_commands(&(vec!["./Cargo.toml".to_string()], None)),
+            Ok(vec![format!("open {}", manifest.display())])
+        );
+        assert!(reuse_commands(&(vec!["./missing.1".to_string()], None)).is_err());
+        let dir = TempDir::new("reuse").expect("temp dir");
+        let spaced = dir.join("spaced page.1");
+        std::fs::write(&spaced, ".TH T 1\n").expect("write");
+        let topic = spaced.to_string_lossy().to_string();
+        let commands = reuse_commands(&(vec![topic.clone()], None)).expect("spaced path");
+        assert_eq!(
+            ipc::parse_command(&commands[0]),
+            Ok(IpcCommand::Open {
+                topic,
+                section: None,
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn forwards_pages_to_a_running_instance() {
+        let pages = (
+            vec!["ls".to_string(), "printf".to_string()],
+            Some("1".to_string()),
+        );
+        assert_eq!(
+            reuse_commands(&pages),
+            Ok(vec!["open ls 1".to_string(), "open printf 1".to_string()])
+        );
+
+        let dir = TempDir::new("reuse").expect("temp dir");
+        let path = dir.join("reuse.sock");
+        assert!(!forward_to_running(&path, Some(&pages)).expect("no instance"));
+        let server = IpcServer::bind(&path).expect("bind");
+        assert!(forward_to_running(&path, Some(&pages)).expect("forward"));
+        assert_eq!(
+            server.try_recv(),
+            Some(IpcCommand::Open {
+                topic: "ls".to_string(),
+                section: Some("1".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn titles_window_after_active_page() {
+        let (app, _) = make_app();
+        assert_eq!(window_title(&app), "manifold: example");
+        assert_eq!(window_title(&App::empty()), "manifold");
+    }
+
+    #[test]
+    fn resolves_external_programs_from_environment() {
+        let env = |name: &str| (name == "EDITOR").then(|| "nano -w".to_string());
+        assert_eq!(external_program(ExternalTool::Editor, env), "nano -w");
+        assert_eq!(external_program(ExternalTool::Pager, env), DEFAULT_PAGER);
+        assert_eq!(
+            external_program(ExternalTool::Editor, |_| None),
+            DEFAULT_EDITOR
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_external_programs_through_the_shell() {
+        let path = Path::new("/tmp/my page.txt");
+        let output = external_command("printf '%s|' -w", path)
+            .output()
+            .expect("run");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "-w|/tmp/my page.txt|"
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn runs_external_programs_through_cmd() {
+        let command = external_command("code --wait", Path::new(r"C:\Temp\my page.txt"));
+        assert_eq!(command.get_program(), "cmd");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["/C", r#"code --wait "C:\Temp\my page.txt""#]);
+    }
+
     #[test]
     fn handled_input_requests_redraw() {
         let (mut app, renderer) = make_app();
@@ -207,6 +1495,7 @@ mod tests {
 
         let outcome = handle_event(
             &mut app,
+            &Keymap::default(),
             &renderer,
             &mut content_width,
             &mut terminal_width,
@@ -228,6 +1517,7 @@ mod tests {
 
         let outcome = handle_event(
             &mut app,
+            &Keymap::default(),
             &renderer,
             &mut content_width,
             &mut terminal_width,
@@ -249,6 +1539,7 @@ mod tests {
 
         let outcome = handle_event(
             &mut app,
+            &Keymap::default(),
             &renderer,
             &mut content_width,
             &mut terminal_width,
@@ -263,6 +1554,27 @@ mod tests {
         assert_eq!(content_height, 28);
     }
 
+    #[test]
+    fn zen_mode_reclaims_the_chrome_rows() {
+        let (mut app, renderer) = make_app();
+        let keymap = Keymap::default();
+        let (mut content_width, mut terminal_width, mut content_height) = (80, 100, 20);
+        for expected in [22, 20] {
+            handle_event(
+                &mut app,
+                &keymap,
+                &renderer,
+                &mut content_width,
+                &mut terminal_width,
+                &mut content_height,
+                Event::Key(platform::KeyCode::Char('Z')),
+            )
+            .expect("toggle zen");
+            assert_eq!(content_height, expected);
+        }
+        assert!(!app.zen());
+    }
+
     #[test]
     fn quit_requests_exit_without_redraw() {
         let mut app = App::empty();
@@ -273,6 +1585,7 @@ mod tests {
 
         let outcome = handle_event(
             &mut app,
+            &Keymap::default(),
             &renderer,
             &mut content_width,
             &mut terminal_width,
@@ -284,6 +1597,7 @@ mod tests {
 
         let outcome = handle_event(
             &mut app,
+            &Keymap::default(),
             &renderer,
             &mut content_width,
             &mut terminal_width,
@@ -295,6 +1609,7 @@ mod tests {
 
         let outcome = handle_event(
             &mut app,
+            &Keymap::default(),
             &renderer,
             &mut content_width,
             &mut terminal_width,
@@ -0,0 +1,148 @@
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent,
+    KeyCode as CrosstermKeyCode, KeyEventKind, MouseButton, MouseEventKind,
+};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use std::io::{self, Stdout, Write};
+use std::time::Duration;
+
+use crate::{Event, KeyCode, MouseKind, TerminalBackend};
+
+pub struct CrosstermTerminal {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl TerminalBackend for CrosstermTerminal {
+    type Backend = CrosstermBackend<Stdout>;
+
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        Ok(Self { terminal })
+    }
+
+    fn terminal_mut(&mut self) -> &mut Terminal<Self::Backend> {
+        &mut self.terminal
+    }
+
+    fn set_mouse(&mut self, enabled: bool) -> io::Result<()> {
+        if enabled {
+            execute!(self.terminal.backend_mut(), EnableMouseCapture)
+        } else {
+            execute!(self.terminal.backend_mut(), DisableMouseCapture)
+        }
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        disable_raw_mode()?;
+        execute!(self.terminal.backend_mut(), LeaveAlternateScreen)?;
+        self.terminal.show_cursor()
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        enable_raw_mode()?;
+        execute!(self.terminal.backend_mut(), EnterAlternateScreen)
+    }
+
+    fn write_raw(&mut self, sequence: &str) -> io::Result<()> {
+        let backend = self.terminal.backend_mut();
+        backend.write_all(sequence.as_bytes())?;
+        backend.flush()
+    }
+
+    fn read_event(&mut self, timeout: Option<Duration>) -> io::Result<Option<Event>> {
+        if let Some(timeout) = timeout
+            && !event::poll(timeout)?
+        {
+            return Ok(None);
+        }
+        Ok(Some(map_crossterm_event(event::read()?)))
+    }
+
+    fn restore() {
+        let mut stdout = io::stdout();
+        let _ = execute!(
+            stdout,
+            DisableMouseCapture,
+            LeaveAlternateScreen,
+            crossterm::cursor::Show
+        );
+        let _ = disable_raw_mode();
+    }
+}
+
+fn map_crossterm_event(event: CrosstermEvent) -> Event {
+    match event {
+        CrosstermEvent::Resize(width, height) => Event::Resize(width, height),
+        CrosstermEvent::Key(key) if key.kind == KeyEventKind::Release => Event::Unsupported,
+        CrosstermEvent::Key(key) => match key.code {
+            CrosstermKeyCode::Char(value) => {
+                if key.modifiers.contains(event::KeyModifiers::CONTROL) {
+                    Event::Key(KeyCode::Ctrl(value))
+                } else {
+                    Event::Key(KeyCode::Char(value))
+                }
+            }
+            CrosstermKeyCode::Up => Event::Key(KeyCode::Up),
+            CrosstermKeyCode::Down => Event::Key(KeyCode::Down),
+            CrosstermKeyCode::Left => Event::Key(KeyCode::Left),
+            CrosstermKeyCode::Right => Event::Key(KeyCode::Right),
+            CrosstermKeyCode::Home => Event::Key(KeyCode::Home),
+            CrosstermKeyCode::End => Event::Key(KeyCode::End),
+            CrosstermKeyCode::Delete => Event::Key(KeyCode::Delete),
+            CrosstermKeyCode::PageUp => Event::Key(KeyCode::PageUp),
+            CrosstermKeyCode::PageDown => Event::Key(KeyCode::PageDown),
+            CrosstermKeyCode::Enter => Event::Key(KeyCode::Enter),
+            CrosstermKeyCode::Tab => Event::Key(KeyCode::Tab),
+            CrosstermKeyCode::Backspace => Event::Key(KeyCode::Backspace),
+            CrosstermKeyCode::Esc => Event::Key(KeyCode::Esc),
+            _ => Event::Unsupported,
+        },
+        CrosstermEvent::Mouse(mouse) => {
+            let kind = match mouse.kind {
+                MouseEventKind::Down(MouseButton::Left) => MouseKind::Press,
+                MouseEventKind::Drag(MouseButton::Left) => MouseKind::Drag,
+                MouseEventKind::Up(MouseButton::Left) => MouseKind::Release,
+                MouseEventKind::ScrollUp => MouseKind::WheelUp,
+                MouseEventKind::ScrollDown => MouseKind::WheelDown,
+                _ => return Event::Unsupported,
+            };
+            Event::Mouse(kind, mouse.column, mouse.row)
+        }
+        _ => Event::Unsupported,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_key_releases() {
+        use crossterm::event::{KeyEvent, KeyEventState, KeyModifiers};
+
+        let key = |kind| {
+            CrosstermEvent::Key(KeyEvent {
+                code: CrosstermKeyCode::Char('j'),
+                modifiers: KeyModifiers::NONE,
+                kind,
+                state: KeyEventState::NONE,
+            })
+        };
+        assert_eq!(
+            map_crossterm_event(key(KeyEventKind::Press)),
+            Event::Key(KeyCode::Char('j'))
+        );
+        assert_eq!(
+            map_crossterm_event(key(KeyEventKind::Release)),
+            Event::Unsupported
+        );
+    }
+}
@@ -1,11 +1,49 @@
-use crossterm::event::{self, Event as CrosstermEvent, KeyCode as CrosstermKeyCode};
-use crossterm::execute;
-use crossterm::terminal::{
-    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
-};
+#[cfg(feature = "crossterm")]
+mod crossterm_terminal;
+#[cfg(feature = "termion")]
+mod termion_terminal;
+#[cfg(feature = "termwiz")]
+mod termwiz_terminal;
+
 use ratatui::Terminal;
-use ratatui::backend::CrosstermBackend;
-use std::io::{self, Stdout};
+use ratatui::backend::Backend;
+use std::io::{self, Write};
+use std::panic;
+use std::process::{Command, ExitStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Once, OnceLock};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "crossterm")]
+pub use crossterm_terminal::CrosstermTerminal;
+#[cfg(feature = "termion")]
+pub use termion_terminal::TermionTerminal;
+#[cfg(feature = "termwiz")]
+pub use termwiz_terminal::TermwizTerminal;
+
+#[cfg(feature = "crossterm")]
+pub type DefaultTerminal = CrosstermTerminal;
+#[cfg(all(feature = "termwiz", not(feature = "crossterm")))]
+pub type DefaultTerminal = TermwizTerminal;
+#[cfg(all(
+    feature = "termion",
+    not(any(feature = "crossterm", feature = "termwiz"))
+))]
+pub type DefaultTerminal = TermionTerminal;
+
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+static PANIC_HOOK: Once = Once::new();
+static RESTORE: OnceLock<fn()> = OnceLock::new();
+const RESTORE_SEQUENCE: &str = "\x1b[?1000l\x1b[?1002l\x1b[?1003l\x1b[?1006l\x1b[?1049l\x1b[?25h";
+const MAX_IDLE_DOUBLINGS: u32 = 3;
+const BACKGROUND_QUERY: &str = "\x1b]11;?\x1b\\\x1b[c";
+const BACKGROUND_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Background {
+    Dark,
+    Light,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KeyCode {
@@ -13,80 +51,332 @@ pub enum KeyCode {
     Ctrl(char),
     Up,
     Down,
+    Left,
+    Right,
+    Home,
+    End,
+    Delete,
     PageUp,
     PageDown,
     Enter,
+    Tab,
     Backspace,
     Esc,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseKind {
+    Press,
+    Drag,
+    Release,
+    WheelUp,
+    WheelDown,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Event {
     Key(KeyCode),
+    Mouse(MouseKind, u16, u16),
     Resize(u16, u16),
     Unsupported,
 }
 
-pub struct TerminalContext {
-    terminal: Terminal<CrosstermBackend<Stdout>>,
+pub trait TerminalBackend: Sized {
+    type Backend: Backend<Error = io::Error>;
+
+    fn enter() -> io::Result<Self>;
+    fn terminal_mut(&mut self) -> &mut Terminal<Self::Backend>;
+    fn set_mouse(&mut self, enabled: bool) -> io::Result<()>;
+    fn suspend(&mut self) -> io::Result<()>;
+    fn resume(&mut self) -> io::Result<()>;
+    fn write_raw(&mut self, sequence: &str) -> io::Result<()>;
+    fn read_event(&mut self, timeout: Option<Duration>) -> io::Result<Option<Event>>;
+
+    fn restore() {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(RESTORE_SEQUENCE.as_bytes());
+        let _ = stdout.flush();
+    }
 }
 
-impl TerminalContext {
+pub struct TerminalContext<B: TerminalBackend> {
+    backend: B,
+    mouse: bool,
+}
+
+impl<B: TerminalBackend> TerminalContext<B> {
     pub fn new() -> io::Result<Self> {
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
-        let backend = CrosstermBackend::new(stdout);
-        let terminal = Terminal::new(backend)?;
-        Ok(Self { terminal })
+        install_panic_hook();
+        let _ = RESTORE.set(B::restore);
+        let backend = B::enter()?;
+        TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+        Ok(Self {
+            backend,
+            mouse: false,
+        })
+    }
+
+    pub fn enable_mouse(&mut self) -> io::Result<()> {
+        self.backend.set_mouse(true)?;
+        self.mouse = true;
+        Ok(())
+    }
+
+    pub fn terminal_mut(&mut self) -> &mut Terminal<B::Backend> {
+        self.backend.terminal_mut()
+    }
+
+    pub fn wait_event(&mut self, timeout: Option<Duration>) -> io::Result<Option<Event>> {
+        self.backend.read_event(timeout)
+    }
+
+    pub fn run_external(&mut self, command: &mut Command) -> io::Result<ExitStatus> {
+        self.suspend()?;
+        let status = command.status();
+        self.resume()?;
+        status
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        if self.mouse {
+            self.backend.set_mouse(false)?;
+        }
+        TERMINAL_ACTIVE.store(false, Ordering::SeqCst);
+        self.backend.suspend()
     }
 
-    pub fn terminal_mut(&mut self) -> &mut Terminal<CrosstermBackend<Stdout>> {
-        &mut self.terminal
+    fn resume(&mut self) -> io::Result<()> {
+        self.backend.resume()?;
+        TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+        if self.mouse {
+            self.backend.set_mouse(true)?;
+        }
+        self.backend.terminal_mut().clear()
+    }
+
+    pub fn copy_to_clipboard(&mut self, text: &str) -> io::Result<()> {
+        self.backend.write_raw(&osc52_sequence(text))
+    }
+
+    pub fn set_title(&mut self, title: &str) -> io::Result<()> {
+        self.backend.write_raw(&title_sequence(title))
+    }
+
+    pub fn background(&mut self) -> Option<Background> {
+        self.backend.write_raw(BACKGROUND_QUERY).ok()?;
+        let reply = read_reply(Instant::now() + BACKGROUND_TIMEOUT).ok()?;
+        parse_background(&reply)
     }
 }
 
-impl Drop for TerminalContext {
+impl<B: TerminalBackend> Drop for TerminalContext<B> {
     fn drop(&mut self) {
-        let _ = disable_raw_mode();
-        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
-        let _ = self.terminal.show_cursor();
+        if !TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        if self.mouse {
+            let _ = self.backend.set_mouse(false);
+        }
+        let _ = self.backend.suspend();
+    }
+}
+
+pub fn restore_terminal() {
+    if !TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(restore) = RESTORE.get() {
+        restore();
+    }
+}
+
+pub fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous(info);
+        }));
+    });
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IdleBackoff {
+    doublings: u32,
+}
+
+impl IdleBackoff {
+    pub fn timeout(&self, tick: Duration) -> Duration {
+        tick.saturating_mul(1 << self.doublings)
+    }
+
+    pub fn idle(&mut self) {
+        self.doublings = (self.doublings + 1).min(MAX_IDLE_DOUBLINGS);
+    }
+
+    pub fn reset(&mut self) {
+        self.doublings = 0;
     }
 }
 
-pub struct EventStream;
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
 
-impl EventStream {
-    pub fn new() -> Self {
-        Self
+pub fn title_sequence(title: &str) -> String {
+    let title: String = title.chars().filter(|ch| !ch.is_control()).collect();
+    format!("\x1b]2;{title}\x07")
+}
+
+pub fn parse_background(reply: &[u8]) -> Option<Background> {
+    let reply = String::from_utf8_lossy(reply);
+    let start = reply.find("\x1b]11;rgb:")? + "\x1b]11;rgb:".len();
+    let body = &reply[start..];
+    let end = body.find(['\x07', '\x1b'])?;
+    let channels = body[..end]
+        .split('/')
+        .map(color_channel)
+        .collect::<Option<Vec<_>>>()?;
+    let [red, green, blue] = channels[..] else {
+        return None;
+    };
+    let luminance = 0.299 * red + 0.587 * green + 0.114 * blue;
+    Some(if luminance > 0.5 {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+fn color_channel(hex: &str) -> Option<f64> {
+    if hex.is_empty() || hex.len() > 4 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
     }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let max = (1u32 << (4 * hex.len())) - 1;
+    Some(f64::from(value) / f64::from(max))
+}
 
-    pub fn next(&self) -> io::Result<Event> {
-        let event = event::read()?;
-        Ok(map_crossterm_event(event))
+fn has_device_attributes(reply: &[u8]) -> bool {
+    reply
+        .windows(3)
+        .position(|window| window == b"\x1b[?")
+        .is_some_and(|start| reply[start..].contains(&b'c'))
+}
+
+#[cfg(unix)]
+fn read_reply(deadline: Instant) -> io::Result<Vec<u8>> {
+    use std::io::Read;
+    use std::os::fd::AsRawFd;
+
+    let mut tty = std::fs::File::open("/dev/tty")?;
+    let mut reply = Vec::new();
+    while !has_device_attributes(&reply) {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        let mut poll = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut poll, 1, left.as_millis() as libc::c_int) };
+        if ready < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err);
+        }
+        let mut buf = [0u8; 256];
+        let read = if ready == 0 { 0 } else { tty.read(&mut buf)? };
+        if read == 0 {
+            break;
+        }
+        reply.extend_from_slice(&buf[..read]);
     }
+    Ok(reply)
 }
 
-fn map_crossterm_event(event: CrosstermEvent) -> Event {
-    match event {
-        CrosstermEvent::Resize(width, height) => Event::Resize(width, height),
-        CrosstermEvent::Key(key) => match key.code {
-            CrosstermKeyCode::Char(value) => {
-                if key.modifiers.contains(event::KeyModifiers::CONTROL) {
-                    Event::Key(KeyCode::Ctrl(value))
-                } else {
-                    Event::Key(KeyCode::Char(value))
-                }
+#[cfg(not(unix))]
+fn read_reply(_deadline: Instant) -> io::Result<Vec<u8>> {
+    Ok(Vec::new())
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for (index, shift) in [18, 12, 6, 0].into_iter().enumerate() {
+            if index <= chunk.len() {
+                out.push(ALPHABET[(n >> shift) as usize & 0x3f] as char);
+            } else {
+                out.push('=');
             }
-            CrosstermKeyCode::Up => Event::Key(KeyCode::Up),
-            CrosstermKeyCode::Down => Event::Key(KeyCode::Down),
-            CrosstermKeyCode::PageUp => Event::Key(KeyCode::PageUp),
-            CrosstermKeyCode::PageDown => Event::Key(KeyCode::PageDown),
-            CrosstermKeyCode::Enter => Event::Key(KeyCode::Enter),
-            CrosstermKeyCode::Backspace => Event::Key(KeyCode::Backspace),
-            CrosstermKeyCode::Esc => Event::Key(KeyCode::Esc),
-            _ => Event::Unsupported,
-        },
-        _ => Event::Unsupported,
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_while_idle() {
+        let tick = Duration::from_millis(500);
+        let mut idle = IdleBackoff::default();
+        assert_eq!(idle.timeout(tick), tick);
+        for _ in 0..10 {
+            idle.idle();
+        }
+        assert_eq!(idle.timeout(tick), Duration::from_secs(4));
+        idle.reset();
+        assert_eq!(idle.timeout(tick), tick);
+    }
+
+    #[test]
+    fn encodes_base64_with_padding() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn wraps_text_in_osc52() {
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn reads_the_background_from_osc11_replies() {
+        assert_eq!(
+            parse_background(b"\x1b]11;rgb:ffff/ffff/dddd\x1b\\\x1b[?62;22c"),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            parse_background(b"\x1b]11;rgb:1e/1e/2e\x07"),
+            Some(Background::Dark)
+        );
+        assert_eq!(parse_background(b"\x1b]11;rgb:fff/fff\x07"), None);
+        assert_eq!(parse_background(b"\x1b[?1;2c"), None);
+        assert!(has_device_attributes(b"\x1b]11;rgb:0/0/0\x07\x1b[?1;2c"));
+        assert!(!has_device_attributes(b"\x1b]11;rgb:0/0/0\x07\x1b[?1;2"));
+    }
+
+    #[test]
+    fn strips_control_characters_from_titles() {
+        assert_eq!(
+            title_sequence("manifold: open(2)\x07\x1b"),
+            "\x1b]2;manifold: open(2)\x07"
+        );
     }
 }
@@ -0,0 +1,349 @@
+use ratatui::Terminal;
+use ratatui::backend::{Backend, ClearType, WindowSize};
+use ratatui::buffer::Cell;
+use ratatui::layout::{Position, Size};
+use ratatui::style::{Color, Modifier};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read, Stdout, Write};
+use std::os::fd::AsRawFd;
+use std::time::{Duration, Instant};
+use termion::event::{Event as TermionEvent, Key, MouseButton, MouseEvent};
+use termion::raw::{IntoRawMode, RawTerminal};
+
+use crate::{Event, KeyCode, MouseKind, TerminalBackend};
+
+const RESIZE_CHECK: Duration = Duration::from_millis(250);
+const MOUSE_ON: &str = "\x1b[?1002h\x1b[?1006h";
+const MOUSE_OFF: &str = "\x1b[?1002l\x1b[?1006l";
+
+pub struct TermionBackend {
+    out: RawTerminal<Stdout>,
+    cursor: Position,
+}
+
+impl Write for TermionBackend {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.out.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+impl Backend for TermionBackend {
+    type Error = io::Error;
+
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let mut out = String::new();
+        let mut last: Option<Position> = None;
+        let mut style = None;
+        for (x, y, cell) in content {
+            if last != Some(Position::new(x.wrapping_sub(1), y)) {
+                out.push_str(&format!("\x1b[{};{}H", y + 1, x + 1));
+            }
+            last = Some(Position::new(x, y));
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                out.push_str(&sgr_sequence(cell.fg, cell.bg, cell.modifier));
+                style = Some(cell_style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m");
+        self.out.write_all(out.as_bytes())
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.out.write_all(b"\x1b[?25l")
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.out.write_all(b"\x1b[?25h")
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        Ok(self.cursor)
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+        self.cursor = position.into();
+        write!(
+            self.out,
+            "\x1b[{};{}H",
+            self.cursor.y + 1,
+            self.cursor.x + 1
+        )
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.out.write_all(b"\x1b[2J")
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+        let sequence = match clear_type {
+            ClearType::All => "\x1b[2J",
+            ClearType::AfterCursor => "\x1b[J",
+            ClearType::BeforeCursor => "\x1b[1J",
+            ClearType::CurrentLine => "\x1b[2K",
+            ClearType::UntilNewLine => "\x1b[K",
+        };
+        self.out.write_all(sequence.as_bytes())
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        let (width, height) = termion::terminal_size()?;
+        Ok(Size::new(width, height))
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        let (width, height) = termion::terminal_size_pixels().unwrap_or((0, 0));
+        Ok(WindowSize {
+            columns_rows: self.size()?,
+            pixels: Size::new(width, height),
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+pub struct TermionTerminal {
+    terminal: Terminal<TermionBackend>,
+    tty: File,
+    pending: VecDeque<Event>,
+    size: (u16, u16),
+}
+
+impl TermionTerminal {
+    fn fill(&mut self, wait: Duration) -> io::Result<()> {
+        let mut poll = libc::pollfd {
+            fd: self.tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut poll, 1, wait.as_millis() as libc::c_int) };
+        if ready < 0 {
+            let err = io::Error::last_os_error();
+            return if err.kind() == io::ErrorKind::Interrupted {
+                Ok(())
+            } else {
+                Err(err)
+            };
+        }
+        if ready == 0 {
+            return Ok(());
+        }
+        let mut buf = [0u8; 1024];
+        let read = self.tty.read(&mut buf)?;
+        self.pending.extend(parse_input(&buf[..read]));
+        Ok(())
+    }
+}
+
+impl TerminalBackend for TermionTerminal {
+    type Backend = TermionBackend;
+
+    fn enter() -> io::Result<Self> {
+        let mut out = io::stdout().into_raw_mode()?;
+        out.write_all(b"\x1b[?1049h")?;
+        out.flush()?;
+        let backend = TermionBackend {
+            out,
+            cursor: Position::ORIGIN,
+        };
+        Ok(Self {
+            terminal: Terminal::new(backend)?,
+            tty: termion::get_tty()?,
+            pending: VecDeque::new(),
+            size: termion::terminal_size()?,
+        })
+    }
+
+    fn terminal_mut(&mut self) -> &mut Terminal<Self::Backend> {
+        &mut self.terminal
+    }
+
+    fn set_mouse(&mut self, enabled: bool) -> io::Result<()> {
+        self.write_raw(if enabled { MOUSE_ON } else { MOUSE_OFF })
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        self.write_raw("\x1b[?1049l\x1b[?25h")?;
+        self.terminal.backend().out.suspend_raw_mode()
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        self.terminal.backend().out.activate_raw_mode()?;
+        self.write_raw("\x1b[?1049h")
+    }
+
+    fn write_raw(&mut self, sequence: &str) -> io::Result<()> {
+        let backend = self.terminal.backend_mut();
+        backend.write_all(sequence.as_bytes())?;
+        Write::flush(backend)
+    }
+
+    fn read_event(&mut self, timeout: Option<Duration>) -> io::Result<Option<Event>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            let size = termion::terminal_size()?;
+            if size != self.size {
+                self.size = size;
+                return Ok(Some(Event::Resize(size.0, size.1)));
+            }
+            let wait = match deadline {
+                Some(deadline) => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    if left.is_zero() {
+                        return Ok(None);
+                    }
+                    left.min(RESIZE_CHECK)
+                }
+                None => RESIZE_CHECK,
+            };
+            self.fill(wait)?;
+        }
+    }
+}
+
+fn parse_input(bytes: &[u8]) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut iter = bytes.iter().map(|byte| Ok(*byte));
+    while let Some(Ok(byte)) = iter.next() {
+        events.push(match termion::event::parse_event(byte, &mut iter) {
+            Ok(event) => map_termion_event(event),
+            Err(_) if byte == b'\x1b' => Event::Key(KeyCode::Esc),
+            Err(_) => Event::Unsupported,
+        });
+    }
+    events
+}
+
+fn map_termion_event(event: TermionEvent) -> Event {
+    match event {
+        TermionEvent::Key(key) => {
+            let code = match key {
+                Key::Char('\n') => KeyCode::Enter,
+                Key::Char('\t') => KeyCode::Tab,
+                Key::Char(value) => KeyCode::Char(value),
+                Key::Ctrl(value) => KeyCode::Ctrl(value),
+                Key::Up => KeyCode::Up,
+                Key::Down => KeyCode::Down,
+                Key::Left => KeyCode::Left,
+                Key::Right => KeyCode::Right,
+                Key::Home => KeyCode::Home,
+                Key::End => KeyCode::End,
+                Key::Delete => KeyCode::Delete,
+                Key::PageUp => KeyCode::PageUp,
+                Key::PageDown => KeyCode::PageDown,
+                Key::Backspace => KeyCode::Backspace,
+                Key::Esc => KeyCode::Esc,
+                _ => return Event::Unsupported,
+            };
+            Event::Key(code)
+        }
+        TermionEvent::Mouse(mouse) => {
+            let (kind, x, y) = match mouse {
+                MouseEvent::Press(MouseButton::Left, x, y) => (MouseKind::Press, x, y),
+                MouseEvent::Press(MouseButton::WheelUp, x, y) => (MouseKind::WheelUp, x, y),
+                MouseEvent::Press(MouseButton::WheelDown, x, y) => (MouseKind::WheelDown, x, y),
+                MouseEvent::Hold(x, y) => (MouseKind::Drag, x, y),
+                MouseEvent::Release(x, y) => (MouseKind::Release, x, y),
+                _ => return Event::Unsupported,
+            };
+            Event::Mouse(kind, x.saturating_sub(1), y.saturating_sub(1))
+        }
+        TermionEvent::Unsupported(_) => Event::Unsupported,
+    }
+}
+
+fn sgr_sequence(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.push(color_code(fg, 30));
+    codes.push(color_code(bg, 40));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color_code(color: Color, base: u8) -> String {
+    let ansi = |offset: u8| (base + offset).to_string();
+    let bright = |offset: u8| (base + 60 + offset).to_string();
+    match color {
+        Color::Reset => ansi(9),
+        Color::Black => ansi(0),
+        Color::Red => ansi(1),
+        Color::Green => ansi(2),
+        Color::Yellow => ansi(3),
+        Color::Blue => ansi(4),
+        Color::Magenta => ansi(5),
+        Color::Cyan => ansi(6),
+        Color::Gray => ansi(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(index) => format!("{};5;{index}", base + 8),
+        Color::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keys_and_sgr_mouse_reports() {
+        assert_eq!(
+            parse_input(b"j\r\x04\x1b[A\x1b[<0;5;3M\x1b[<32;6;3M\x1b[<0;6;3m\x1b"),
+            vec![
+                Event::Key(KeyCode::Char('j')),
+                Event::Key(KeyCode::Enter),
+                Event::Key(KeyCode::Ctrl('d')),
+                Event::Key(KeyCode::Up),
+                Event::Mouse(MouseKind::Press, 4, 2),
+                Event::Mouse(MouseKind::Drag, 5, 2),
+                Event::Mouse(MouseKind::Release, 5, 2),
+                Event::Key(KeyCode::Esc),
+            ]
+        );
+    }
+
+    #[test]
+    fn styles_cells_with_sgr_codes() {
+        assert_eq!(
+            sgr_sequence(Color::Indexed(208), Color::Reset, Modifier::BOLD),
+            "\x1b[0;1;38;5;208;49m"
+        );
+        assert_eq!(
+            sgr_sequence(Color::LightCyan, Color::Rgb(1, 2, 3), Modifier::empty()),
+            "\x1b[0;96;48;2;1;2;3m"
+        );
+    }
+}
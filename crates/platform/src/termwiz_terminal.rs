@@ -0,0 +1,192 @@
+use ratatui::Terminal;
+use ratatui::backend::TermwizBackend;
+use ratatui::termwiz::input::{
+    InputEvent, KeyCode as TermwizKeyCode, KeyEvent, Modifiers, MouseButtons, MouseEvent,
+};
+use ratatui::termwiz::terminal::{SystemTerminal, Terminal as _};
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::{Event, KeyCode, MouseKind, TerminalBackend};
+
+const MOUSE_ON: &str = "\x1b[?1002h\x1b[?1006h";
+const MOUSE_OFF: &str = "\x1b[?1002l\x1b[?1003l\x1b[?1006l";
+
+pub struct TermwizTerminal {
+    terminal: Terminal<TermwizBackend>,
+    buttons: MouseButtons,
+}
+
+impl TermwizTerminal {
+    fn write_now(&mut self, sequence: &str) -> io::Result<()> {
+        self.terminal
+            .backend_mut()
+            .buffered_terminal_mut()
+            .flush()
+            .map_err(io::Error::other)?;
+        let mut stdout = io::stdout();
+        stdout.write_all(sequence.as_bytes())?;
+        stdout.flush()
+    }
+
+    fn system(&mut self) -> &mut SystemTerminal {
+        self.terminal
+            .backend_mut()
+            .buffered_terminal_mut()
+            .terminal()
+    }
+}
+
+impl TerminalBackend for TermwizTerminal {
+    type Backend = TermwizBackend;
+
+    fn enter() -> io::Result<Self> {
+        let backend = TermwizBackend::new().map_err(|err| io::Error::other(err.to_string()))?;
+        let mut terminal = Self {
+            terminal: Terminal::new(backend)?,
+            buttons: MouseButtons::NONE,
+        };
+        terminal.write_now(MOUSE_OFF)?;
+        Ok(terminal)
+    }
+
+    fn terminal_mut(&mut self) -> &mut Terminal<Self::Backend> {
+        &mut self.terminal
+    }
+
+    fn set_mouse(&mut self, enabled: bool) -> io::Result<()> {
+        self.write_now(if enabled { MOUSE_ON } else { MOUSE_OFF })
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        self.terminal.show_cursor()?;
+        self.terminal
+            .backend_mut()
+            .buffered_terminal_mut()
+            .flush()
+            .map_err(io::Error::other)?;
+        let system = self.system();
+        system.exit_alternate_screen().map_err(io::Error::other)?;
+        system.set_cooked_mode().map_err(io::Error::other)
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        let system = self.system();
+        system.set_raw_mode().map_err(io::Error::other)?;
+        system.enter_alternate_screen().map_err(io::Error::other)?;
+        self.write_now(MOUSE_OFF)
+    }
+
+    fn write_raw(&mut self, sequence: &str) -> io::Result<()> {
+        self.write_now(sequence)
+    }
+
+    fn read_event(&mut self, timeout: Option<Duration>) -> io::Result<Option<Event>> {
+        let Some(input) = self
+            .system()
+            .poll_input(timeout)
+            .map_err(io::Error::other)?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(match input {
+            InputEvent::Key(key) => map_key(key),
+            InputEvent::Mouse(mouse) => {
+                let previous = std::mem::replace(&mut self.buttons, mouse.mouse_buttons.clone());
+                map_mouse(&previous, &mouse)
+            }
+            InputEvent::Resized { cols, rows } => {
+                self.terminal
+                    .backend_mut()
+                    .buffered_terminal_mut()
+                    .check_for_resize()
+                    .map_err(io::Error::other)?;
+                Event::Resize(cols as u16, rows as u16)
+            }
+            _ => Event::Unsupported,
+        }))
+    }
+}
+
+fn map_key(key: KeyEvent) -> Event {
+    let code = match key.key {
+        TermwizKeyCode::Char(value) if key.modifiers.contains(Modifiers::CTRL) => {
+            KeyCode::Ctrl(value)
+        }
+        TermwizKeyCode::Char(value) => KeyCode::Char(value),
+        TermwizKeyCode::UpArrow => KeyCode::Up,
+        TermwizKeyCode::DownArrow => KeyCode::Down,
+        TermwizKeyCode::LeftArrow => KeyCode::Left,
+        TermwizKeyCode::RightArrow => KeyCode::Right,
+        TermwizKeyCode::Home => KeyCode::Home,
+        TermwizKeyCode::End => KeyCode::End,
+        TermwizKeyCode::Delete => KeyCode::Delete,
+        TermwizKeyCode::PageUp => KeyCode::PageUp,
+        TermwizKeyCode::PageDown => KeyCode::PageDown,
+        TermwizKeyCode::Enter => KeyCode::Enter,
+        TermwizKeyCode::Tab => KeyCode::Tab,
+        TermwizKeyCode::Backspace => KeyCode::Backspace,
+        TermwizKeyCode::Escape => KeyCode::Esc,
+        _ => return Event::Unsupported,
+    };
+    Event::Key(code)
+}
+
+fn map_mouse(previous: &MouseButtons, mouse: &MouseEvent) -> Event {
+    let buttons = &mouse.mouse_buttons;
+    let kind = if buttons.contains(MouseButtons::VERT_WHEEL) {
+        if buttons.contains(MouseButtons::WHEEL_POSITIVE) {
+            MouseKind::WheelUp
+        } else {
+            MouseKind::WheelDown
+        }
+    } else if buttons.contains(MouseButtons::LEFT) {
+        if previous.contains(MouseButtons::LEFT) {
+            MouseKind::Drag
+        } else {
+            MouseKind::Press
+        }
+    } else if previous.contains(MouseButtons::LEFT) {
+        MouseKind::Release
+    } else {
+        return Event::Unsupported;
+    };
+    Event::Mouse(kind, mouse.x.saturating_sub(1), mouse.y.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_buttons_to_tell_presses_from_drags() {
+        let mouse = |buttons, x, y| MouseEvent {
+            x,
+            y,
+            mouse_buttons: buttons,
+            modifiers: Modifiers::NONE,
+        };
+        const NONE: MouseButtons = MouseButtons::NONE;
+        const LEFT: MouseButtons = MouseButtons::LEFT;
+        assert_eq!(
+            map_mouse(&NONE, &mouse(LEFT, 5, 3)),
+            Event::Mouse(MouseKind::Press, 4, 2)
+        );
+        assert_eq!(
+            map_mouse(&LEFT, &mouse(LEFT, 6, 3)),
+            Event::Mouse(MouseKind::Drag, 5, 2)
+        );
+        assert_eq!(
+            map_mouse(&LEFT, &mouse(NONE, 6, 3)),
+            Event::Mouse(MouseKind::Release, 5, 2)
+        );
+        assert_eq!(map_mouse(&NONE, &mouse(NONE, 6, 3)), Event::Unsupported);
+        assert_eq!(
+            map_key(KeyEvent {
+                key: TermwizKeyCode::Char('d'),
+                modifiers: Modifiers::CTRL,
+            }),
+            Event::Key(KeyCode::Ctrl('d'))
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use std::process::{Command, Stdio};
 
+use crate::man_flavor;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ArgsInterpretation {
     SectionAndPages { section: String, pages: Vec<String> },
@@ -64,7 +66,7 @@ pub fn classify_args<S: AsRef<str>>(args: &[S]) -> Result<ArgsInterpretation, Va
 fn section_has_any_pages(section: &str, pages: &[String]) -> Result<bool, ValidationError> {
     let output = Command::new("man")
         .arg("-w")
-        .arg("-S")
+        .arg(man_flavor().section_flag())
         .arg(section)
         .args(pages)
         .stdout(Stdio::piped())
@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::online::{fetch, sanitize};
+use crate::{ManRenderer, RenderError, clean_formatted_text};
+
+const CHEAT_URL: &str = "https://cheat.sh";
+
+#[derive(Debug, Clone)]
+pub struct CheatRenderer {
+    cache_dir: PathBuf,
+    url: String,
+}
+
+impl CheatRenderer {
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            url: CHEAT_URL.to_string(),
+        }
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    fn cache_path(&self, topic: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.txt", sanitize(topic)))
+    }
+
+    fn cached(&self, topic: &str) -> Option<Vec<String>> {
+        let text = fs::read_to_string(self.cache_path(topic)).ok()?;
+        Some(text.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn store(&self, topic: &str, lines: &[String]) {
+        if fs::create_dir_all(&self.cache_dir).is_ok() {
+            let _ = fs::write(self.cache_path(topic), lines.join("\n"));
+        }
+    }
+}
+
+impl ManRenderer for CheatRenderer {
+    fn render(
+        &self,
+        name: &str,
+        _section: Option<&str>,
+        _width: u16,
+    ) -> Result<Vec<String>, RenderError> {
+        let topic = name.trim();
+        if let Some(lines) = self.cached(topic) {
+            return Ok(lines);
+        }
+        let Some(text) = fetch(&cheat_url(&self.url, topic)).ok().flatten() else {
+            return Err(RenderError::CommandFailed(format!(
+                "cheat.sh is unreachable and {topic} is not cached"
+            )));
+        };
+        let lines = clean_formatted_text(&text);
+        if lines.is_empty() || lines[0].starts_with("Unknown topic") {
+            return Err(RenderError::CommandFailed(format!(
+                "No cheat sheet for {topic}"
+            )));
+        }
+        self.store(topic, &lines);
+        Ok(lines)
+    }
+}
+
+fn cheat_url(base: &str, topic: &str) -> String {
+    let query: Vec<&str> = topic.split_whitespace().collect();
+    format!("{base}/{}?T", query.join("+"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::TempDir;
+
+    #[test]
+    fn serves_cached_sheets_and_reports_when_offline() {
+        let dir = TempDir::new("cheat").expect("temp dir");
+        let renderer = CheatRenderer::new(dir.path()).with_url("http://127.0.0.1:9");
+        renderer.store(
+            "tar",
+            &["# extract".to_string(), "tar xf a.tar".to_string()],
+        );
+        assert_eq!(
+            renderer.render("tar", None, 80).expect("cached"),
+            vec!["# extract".to_string(), "tar xf a.tar".to_string()]
+        );
+        let err = renderer.render("rsync", None, 80).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "command failed: cheat.sh is unreachable and rsync is not cached"
+        );
+    }
+
+    #[test]
+    fn joins_query_words_with_plus() {
+        assert_eq!(
+            cheat_url(CHEAT_URL, "python reverse list"),
+            "https://cheat.sh/python+reverse+list?T"
+        );
+        assert_eq!(
+            cheat_url(CHEAT_URL, "rust/Vec"),
+            "https://cheat.sh/rust/Vec?T"
+        );
+    }
+}
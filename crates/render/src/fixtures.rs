@@ -0,0 +1,129 @@
+use crate::{ManRenderer, RenderError, clean_formatted_text};
+
+pub struct Fixture {
+    pub name: &'static str,
+    pub section: &'static str,
+    pub text: &'static str,
+}
+
+pub const FIXTURES: &[Fixture] = &[
+    Fixture {
+        name: "ls",
+        section: "1",
+        text: include_str!("../fixtures/ls.1.txt"),
+    },
+    Fixture {
+        name: "grep",
+        section: "1",
+        text: include_str!("../fixtures/grep.1.txt"),
+    },
+    Fixture {
+        name: "ascii",
+        section: "7",
+        text: include_str!("../fixtures/ascii.7.txt"),
+    },
+    Fixture {
+        name: "unicode",
+        section: "7",
+        text: include_str!("../fixtures/unicode.7.txt"),
+    },
+];
+
+pub const HUGE_FIXTURE: &str = "huge";
+const HUGE_PARAGRAPHS: usize = 5000;
+
+#[derive(Debug, Default)]
+pub struct PreformattedRenderer;
+
+impl PreformattedRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ManRenderer for PreformattedRenderer {
+    fn render(
+        &self,
+        name: &str,
+        section: Option<&str>,
+        width: u16,
+    ) -> Result<Vec<String>, RenderError> {
+        if name == HUGE_FIXTURE {
+            return Ok(wrap_lines(&huge_page(), width));
+        }
+        let fixture = FIXTURES
+            .iter()
+            .find(|fixture| fixture.name == name && section.is_none_or(|s| s == fixture.section))
+            .ok_or_else(|| RenderError::CommandFailed(format!("No manual entry for {name}")))?;
+        Ok(wrap_lines(&clean_formatted_text(fixture.text), width))
+    }
+}
+
+fn huge_page() -> Vec<String> {
+    let mut lines = vec![
+        "NAME".to_string(),
+        "       huge - a very long page".to_string(),
+    ];
+    lines.push(String::new());
+    lines.push("DESCRIPTION".to_string());
+    for index in 0..HUGE_PARAGRAPHS {
+        lines.push(format!(
+            "       Paragraph {index} repeats filler text so that scrolling, searching and \
+             reflow can be exercised on a page with tens of thousands of lines."
+        ));
+        lines.push(String::new());
+    }
+    lines
+}
+
+fn wrap_lines(lines: &[String], width: u16) -> Vec<String> {
+    let width = usize::from(width.max(1));
+    let mut out = Vec::with_capacity(lines.len());
+    for line in lines {
+        if line.chars().count() <= width {
+            out.push(line.clone());
+            continue;
+        }
+        let indent_len = line.len() - line.trim_start().len();
+        let indent = &line[..indent_len.min(width.saturating_sub(1))];
+        let mut current = indent.to_string();
+        for word in line.split_whitespace() {
+            let current_len = current.chars().count();
+            let needed = word.chars().count() + usize::from(current_len > indent.len());
+            if current_len > indent.len() && current_len + needed > width {
+                out.push(std::mem::replace(&mut current, indent.to_string()));
+            }
+            if current.len() > indent.len() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        out.push(current);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_fixtures_without_overstrike() {
+        let renderer = PreformattedRenderer::new();
+        let lines = renderer.render("grep", Some("1"), 80).expect("render");
+        assert_eq!(lines[2], "NAME");
+        assert!(lines.iter().all(|line| !line.contains('\u{8}')));
+        assert!(renderer.render("grep", Some("7"), 80).is_err());
+        assert!(renderer.render("missing", None, 80).is_err());
+    }
+
+    #[test]
+    fn rewraps_to_requested_width() {
+        let renderer = PreformattedRenderer::new();
+        let narrow = renderer.render("ls", None, 40).expect("render");
+        let wide = renderer.render("ls", None, 80).expect("render");
+        assert!(narrow.len() > wide.len());
+        assert!(narrow.iter().all(|line| line.chars().count() <= 40));
+        assert!(renderer.render(HUGE_FIXTURE, None, 80).expect("huge").len() > 10_000);
+    }
+}
@@ -0,0 +1,99 @@
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use crate::find_executable;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManFlavor {
+    ManDb,
+    Bsd,
+    Mandoc,
+}
+
+impl ManFlavor {
+    pub fn name(self) -> &'static str {
+        match self {
+            ManFlavor::ManDb => "man-db",
+            ManFlavor::Bsd => "bsd",
+            ManFlavor::Mandoc => "mandoc",
+        }
+    }
+
+    pub fn section_flag(self) -> &'static str {
+        match self {
+            ManFlavor::ManDb => "-S",
+            ManFlavor::Bsd | ManFlavor::Mandoc => "-s",
+        }
+    }
+
+    pub fn width_args(self, width: u16) -> Vec<String> {
+        match self {
+            ManFlavor::Mandoc => vec!["-O".to_string(), format!("width={}", width.max(1))],
+            ManFlavor::ManDb | ManFlavor::Bsd => Vec::new(),
+        }
+    }
+
+    pub fn reads_stdin(self) -> bool {
+        self == ManFlavor::ManDb
+    }
+}
+
+pub fn man_flavor() -> ManFlavor {
+    static FLAVOR: OnceLock<ManFlavor> = OnceLock::new();
+    *FLAVOR.get_or_init(detect_man_flavor)
+}
+
+fn detect_man_flavor() -> ManFlavor {
+    let version = Command::new("man")
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned());
+    classify_flavor(version.as_deref(), find_executable("mandoc").is_some())
+}
+
+pub fn classify_flavor(version: Option<&str>, has_mandoc: bool) -> ManFlavor {
+    let is_man_db = version.is_some_and(|text| {
+        text.contains("man-db")
+            || text
+                .strip_prefix("man ")
+                .is_some_and(|rest| rest.starts_with(|ch: char| ch.is_ascii_digit()))
+    });
+    if is_man_db {
+        ManFlavor::ManDb
+    } else if has_mandoc {
+        ManFlavor::Mandoc
+    } else {
+        ManFlavor::Bsd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_version_output() {
+        assert_eq!(
+            classify_flavor(Some("man 2.12.0\n"), false),
+            ManFlavor::ManDb
+        );
+        assert_eq!(
+            classify_flavor(Some("man-db 2.9.4\n"), true),
+            ManFlavor::ManDb
+        );
+        assert_eq!(classify_flavor(None, true), ManFlavor::Mandoc);
+        assert_eq!(classify_flavor(Some("usage: man"), false), ManFlavor::Bsd);
+    }
+
+    #[test]
+    fn adapts_flags_to_flavor() {
+        assert_eq!(ManFlavor::ManDb.section_flag(), "-S");
+        assert_eq!(ManFlavor::Mandoc.section_flag(), "-s");
+        assert!(ManFlavor::ManDb.width_args(80).is_empty());
+        assert_eq!(ManFlavor::Mandoc.width_args(80), vec!["-O", "width=80"]);
+    }
+}
@@ -0,0 +1,228 @@
+use std::env;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{RenderError, clean_formatted_text};
+
+const HELP_TIMEOUT: Duration = Duration::from_secs(2);
+const SHELL_TIMEOUT: Duration = Duration::from_secs(10);
+const PASSED_VARIABLES: &[&str] = &[
+    "PATH",
+    "HOME",
+    "LANG",
+    "LC_ALL",
+    "LC_CTYPE",
+    "SYSTEMROOT",
+    "PATHEXT",
+];
+
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return None;
+    }
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .flat_map(|dir| {
+            executable_names(name)
+                .into_iter()
+                .map(move |file| dir.join(file))
+        })
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(windows)]
+fn executable_names(name: &str) -> Vec<String> {
+    let extensions = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    std::iter::once(name.to_string())
+        .chain(
+            extensions
+                .split(';')
+                .filter(|extension| !extension.is_empty())
+                .map(|extension| format!("{name}{extension}")),
+        )
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn executable_names(name: &str) -> Vec<String> {
+    vec![name.to_string()]
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+pub fn run_help(program: &Path, width: u16) -> Result<Vec<String>, RenderError> {
+    let mut command = Command::new(program);
+    command
+        .arg("--help")
+        .env_clear()
+        .env("COLUMNS", width.max(1).to_string())
+        .env("TERM", "dumb")
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    for name in PASSED_VARIABLES {
+        if let Some(value) = env::var_os(name) {
+            command.env(name, value);
+        }
+    }
+    let mut child = command.spawn()?;
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+    let Some(status) = wait_with_timeout(&mut child, HELP_TIMEOUT)? else {
+        return Err(RenderError::CommandFailed(format!(
+            "{} --help timed out",
+            program.display()
+        )));
+    };
+
+    let mut output = stdout.map(join_output).unwrap_or_default();
+    if output.trim().is_empty() {
+        output = stderr.map(join_output).unwrap_or_default();
+    }
+    if output.trim().is_empty() {
+        return Err(RenderError::CommandFailed(format!(
+            "{} --help printed nothing ({status})",
+            program.display()
+        )));
+    }
+    Ok(clean_formatted_text(&output))
+}
+
+pub fn run_shell(line: &str, width: u16) -> Result<Vec<String>, RenderError> {
+    let mut command = shell_command(line);
+    command
+        .env("COLUMNS", width.max(1).to_string())
+        .env("TERM", "dumb")
+        .env("NO_COLOR", "1")
+        .env("PAGER", "cat")
+        .env("MANPAGER", "cat")
+        .env("GIT_PAGER", "cat")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = command.spawn()?;
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+    let Some(status) = wait_with_timeout(&mut child, SHELL_TIMEOUT)? else {
+        return Err(RenderError::CommandFailed(format!("{line} timed out")));
+    };
+
+    let mut output = stdout.map(join_output).unwrap_or_default();
+    let errors = stderr.map(join_output).unwrap_or_default();
+    if !errors.trim().is_empty() {
+        if !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str(&errors);
+    }
+    if output.trim().is_empty() {
+        return Err(RenderError::CommandFailed(format!(
+            "{line} printed nothing ({status})"
+        )));
+    }
+    Ok(clean_formatted_text(&output))
+}
+
+#[cfg(not(windows))]
+fn shell_command(line: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(line);
+    command
+}
+
+#[cfg(windows)]
+fn shell_command(line: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.arg("/C").arg(line);
+    command
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if started.elapsed() > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+fn read_in_background(mut reader: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = reader.read_to_end(&mut bytes);
+        bytes
+    })
+}
+
+fn join_output(handle: thread::JoinHandle<Vec<u8>>) -> String {
+    let bytes = handle.join().unwrap_or_default();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::TempDir;
+
+    #[test]
+    fn finds_executables_on_path() {
+        assert!(find_executable("sh").is_some());
+        assert_eq!(find_executable("./sh"), None);
+        assert_eq!(find_executable("definitely-not-a-command-xyz"), None);
+    }
+
+    #[test]
+    fn captures_help_output() {
+        let Some(sh) = find_executable("sh") else {
+            return;
+        };
+        let dir = TempDir::new("help").expect("temp dir");
+        let script = dir.join("tool");
+        std::fs::write(
+            &script,
+            format!("#!{}\necho \"usage: tool $1\" >&2\n", sh.display()),
+        )
+        .expect("script");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))
+                .expect("chmod");
+        }
+        let lines = run_help(&script, 80).expect("help");
+        assert_eq!(lines, vec!["usage: tool --help".to_string()]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn captures_shell_output_and_errors() {
+        let lines = run_shell("echo \"$COLUMNS\"; echo oops >&2", 72).expect("shell");
+        assert_eq!(lines, vec!["72".to_string(), "oops".to_string()]);
+        let err = run_shell("exit 3", 80).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "command failed: exit 3 printed nothing (exit status: 3)"
+        );
+    }
+}
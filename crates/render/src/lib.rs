@@ -1,10 +1,50 @@
+use std::env;
 use std::fmt;
-use std::io::Read;
-use std::process::{Command, Stdio};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
 
 mod args_validation;
+mod cheat;
+#[cfg(feature = "fixtures")]
+mod fixtures;
+mod flavor;
+mod help;
+mod lookup;
+mod markdown;
+mod online;
+mod remote;
+mod source;
+mod stream;
+mod text;
+mod welcome;
+mod width;
 
 pub use args_validation::{ArgsInterpretation, ValidationError, classify_args};
+pub use cheat::CheatRenderer;
+#[cfg(feature = "fixtures")]
+pub use fixtures::{FIXTURES, Fixture, HUGE_FIXTURE, PreformattedRenderer};
+pub use flavor::{ManFlavor, classify_flavor, man_flavor};
+pub use help::{find_executable, run_help, run_shell};
+pub use lookup::{
+    PageEntry, build_page_index, load_page_index, lookup_sections, order_sections, parse_apropos,
+    parse_page_paths, parse_whatis, save_page_index, search_apropos,
+};
+pub use markdown::{is_markdown_page, render_markdown};
+pub use online::{OnlineRenderer, html_to_lines};
+pub use remote::{RemotePage, parse_remote_page, render_remote};
+pub use source::{Compression, decompress, is_local_page, read_page_source, typeset_page};
+use source::{join_writer, pipe_through};
+pub use stream::RenderChunk;
+pub use text::{clean_formatted_line, clean_formatted_text, decode_output};
+pub use welcome::{WELCOME_PAGE, WELCOME_SECTION, WelcomeRenderer};
+
+const NO_MAN_COMMAND: &str =
+    "no man command found; install man or mandoc, or use --online to fetch pages from man7.org";
+pub use width::{char_at_column, display_column, display_width};
 
 #[derive(Debug)]
 pub enum RenderError {
@@ -52,77 +92,405 @@ pub trait ManRenderer {
         section: Option<&str>,
         width: u16,
     ) -> Result<Vec<String>, RenderError>;
+
+    fn render_stream(
+        &self,
+        _name: &str,
+        _section: Option<&str>,
+        _width: u16,
+    ) -> Result<Option<Receiver<RenderChunk>>, RenderError> {
+        Ok(None)
+    }
+
+    fn lookup(&self, _name: &str) -> Result<Vec<PageEntry>, RenderError> {
+        Ok(Vec::new())
+    }
+
+    fn apropos(&self, _keyword: &str) -> Result<Vec<PageEntry>, RenderError> {
+        Ok(Vec::new())
+    }
+
+    fn locate(&self, _name: &str, _section: Option<&str>) -> Result<Option<PathBuf>, RenderError> {
+        Ok(None)
+    }
+
+    fn sections(&self, name: &str) -> Result<Vec<String>, RenderError> {
+        let mut sections: Vec<String> = Vec::new();
+        for entry in self.lookup(name)? {
+            if !sections.contains(&entry.section) {
+                sections.push(entry.section);
+            }
+        }
+        Ok(sections)
+    }
+
+    fn help_command(&self, _name: &str) -> Option<PathBuf> {
+        None
+    }
+
+    fn take_warning(&self) -> Option<String> {
+        None
+    }
+
+    fn render_help(&self, program: &Path, width: u16) -> Result<Vec<String>, RenderError> {
+        run_help(program, width)
+    }
+
+    fn run_command(&self, command: &str, width: u16) -> Result<Vec<String>, RenderError> {
+        run_shell(command, width)
+    }
+
+    fn typeset(&self, source: Vec<u8>, device: &str) -> Result<Vec<u8>, RenderError> {
+        typeset_page(source, device)
+    }
 }
 
 #[derive(Debug, Default)]
-pub struct SystemManRenderer;
+pub struct SystemManRenderer {
+    section_order: Vec<String>,
+    roff_options: Option<String>,
+    warning: Mutex<Option<String>>,
+}
 
 impl SystemManRenderer {
     pub fn new() -> Self {
-        Self
+        Self::default()
     }
-}
 
-impl ManRenderer for SystemManRenderer {
-    fn render(
+    pub fn with_section_order(section_order: Vec<String>) -> Self {
+        Self {
+            section_order,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_roff_options(mut self, options: &str) -> Self {
+        self.roff_options = Some(roff_options(
+            env::var("MANROFFOPT").ok().as_deref(),
+            options,
+        ));
+        self
+    }
+
+    fn warn_lossy(&self, name: &str) {
+        if let Ok(mut warning) = self.warning.lock() {
+            *warning = Some(lossy_warning(name));
+        }
+    }
+
+    fn spawn_man(
         &self,
         name: &str,
         section: Option<&str>,
         width: u16,
-    ) -> Result<Vec<String>, RenderError> {
+    ) -> Result<ManProcess, RenderError> {
+        let flavor = man_flavor();
         let safe_width = width.max(1).to_string();
-        let mut man_cmd = Command::new("man");
-        man_cmd.env("MANWIDTH", &safe_width).env("MANPAGER", "cat");
+        let mut man_cmd = self.man_command();
+        man_cmd
+            .env("MANWIDTH", &safe_width)
+            .env("MANPAGER", "cat")
+            .args(flavor.width_args(width));
 
-        if let Some(section) = section {
-            man_cmd.arg(section);
-        }
-        man_cmd.arg(name);
+        let source = if is_local_page(name) {
+            man_cmd.arg("-l").arg("-").stdin(Stdio::piped());
+            Some(read_page_source(Path::new(name))?)
+        } else {
+            if let Some(section) = section {
+                man_cmd.arg(flavor.section_flag()).arg(section);
+            }
+            man_cmd.arg(name);
+            None
+        };
         man_cmd.stdout(Stdio::piped());
         man_cmd.stderr(Stdio::piped());
 
-        let mut man_child = man_cmd.spawn()?;
-        let man_stdout = man_child
+        tracing::debug!(name, section, width, flavor = flavor.name(), "running man");
+        let mut child = man_cmd.spawn().map_err(|err| {
+            tracing::warn!("could not start man: {err}");
+            if err.kind() == std::io::ErrorKind::NotFound {
+                RenderError::CommandFailed(NO_MAN_COMMAND.to_string())
+            } else {
+                err.into()
+            }
+        })?;
+        let writer = match (source, child.stdin.take()) {
+            (Some(bytes), Some(mut stdin)) => Some(thread::spawn(move || stdin.write_all(&bytes))),
+            _ => None,
+        };
+        let stdout = child
             .stdout
             .take()
             .ok_or_else(|| RenderError::CommandFailed("man stdout unavailable".to_string()))?;
-        let mut man_stderr = man_child
+        let mut stderr = child
             .stderr
             .take()
             .ok_or_else(|| RenderError::CommandFailed("man stderr unavailable".to_string()))?;
+        let errors = thread::spawn(move || {
+            let mut error_output = Vec::new();
+            stderr.read_to_end(&mut error_output).map(|_| error_output)
+        });
+        Ok(ManProcess {
+            name: name.to_string(),
+            child,
+            stdout: Some(stdout),
+            writer,
+            errors,
+        })
+    }
 
-        let mut col_child = Command::new("col")
-            .arg("-bx")
-            .stdin(Stdio::from(man_stdout))
-            .stdout(Stdio::piped())
-            .spawn()?;
+    fn man_command(&self) -> Command {
+        let mut man_cmd = Command::new("man");
+        man_cmd.env(
+            "MANROFFSEQ",
+            preprocessors(env::var("MANROFFSEQ").ok().as_deref()),
+        );
+        if !self.section_order.is_empty() {
+            man_cmd.env("MANSECT", self.section_order.join(":"));
+        }
+        if let Some(options) = &self.roff_options {
+            man_cmd.env("MANROFFOPT", options);
+        }
+        man_cmd
+    }
+}
 
+impl ManRenderer for SystemManRenderer {
+    fn render(
+        &self,
+        name: &str,
+        section: Option<&str>,
+        width: u16,
+    ) -> Result<Vec<String>, RenderError> {
+        if !is_local_page(name)
+            && let Some(page) = parse_remote_page(name)
+        {
+            return render_remote(page, section, width);
+        }
+        if is_markdown_page(name) {
+            let source = read_page_source(Path::new(name))?;
+            let (text, lossy) = decode_output(source);
+            if lossy {
+                self.warn_lossy(name);
+            }
+            return Ok(render_markdown(&text, width));
+        }
+        let flavor = man_flavor();
+        if is_local_page(name)
+            && (!flavor.reads_stdin() || find_executable("man").is_none())
+            && let Some(mandoc) = find_executable("mandoc")
+        {
+            let source = read_page_source(Path::new(name))?;
+            return render_with_mandoc(&mandoc, source, width);
+        }
+        let mut man = self.spawn_man(name, section, width)?;
         let mut output = Vec::new();
-        if let Some(mut stdout) = col_child.stdout.take() {
+        if let Some(mut stdout) = man.stdout.take() {
             stdout.read_to_end(&mut output)?;
         }
+        man.finish()?;
 
-        let man_status = man_child.wait()?;
-        if !man_status.success() {
-            let mut error_output = Vec::new();
-            man_stderr.read_to_end(&mut error_output)?;
-            let message = String::from_utf8_lossy(&error_output).trim().to_string();
-            let message = if message.is_empty() {
-                format!("man exited with {man_status}")
-            } else {
-                message
+        let (text, lossy) = decode_output(output);
+        if lossy {
+            tracing::warn!(name, section, "man output is not valid UTF-8");
+            self.warn_lossy(name);
+        }
+        Ok(clean_formatted_text(&text))
+    }
+
+    fn render_stream(
+        &self,
+        name: &str,
+        section: Option<&str>,
+        width: u16,
+    ) -> Result<Option<Receiver<RenderChunk>>, RenderError> {
+        if is_local_page(name) || parse_remote_page(name).is_some() {
+            return Ok(None);
+        }
+        let mut man = self.spawn_man(name, section, width)?;
+        let Some(stdout) = man.stdout.take() else {
+            return Ok(None);
+        };
+        let (sender, receiver) = mpsc::channel();
+        let name = name.to_string();
+        thread::spawn(move || {
+            let result = match stream::send_lines(BufReader::new(stdout), &sender) {
+                Ok(None) => return,
+                Ok(Some(lossy)) => {
+                    if lossy {
+                        let _ = sender.send(RenderChunk::Warning(lossy_warning(&name)));
+                    }
+                    man.finish()
+                }
+                Err(err) => Err(err.into()),
             };
-            return Err(RenderError::CommandFailed(message));
+            let _ = sender.send(RenderChunk::Done(result));
+        });
+        Ok(Some(receiver))
+    }
+
+    fn lookup(&self, name: &str) -> Result<Vec<PageEntry>, RenderError> {
+        if is_local_page(name) || parse_remote_page(name).is_some() {
+            return Ok(Vec::new());
+        }
+        let mut entries = lookup_sections(name)?;
+        order_sections(&mut entries, &self.section_order);
+        Ok(entries)
+    }
+
+    fn apropos(&self, keyword: &str) -> Result<Vec<PageEntry>, RenderError> {
+        search_apropos(keyword)
+    }
+
+    fn help_command(&self, name: &str) -> Option<PathBuf> {
+        find_executable(name)
+    }
+
+    fn take_warning(&self) -> Option<String> {
+        self.warning.lock().ok()?.take()
+    }
+
+    fn sections(&self, name: &str) -> Result<Vec<String>, RenderError> {
+        if is_local_page(name) || parse_remote_page(name).is_some() {
+            return Ok(Vec::new());
+        }
+        let output = match self
+            .man_command()
+            .args(["-a", "-w", name])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .output()
+        {
+            Ok(output) => output,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+        Ok(parse_page_paths(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn locate(&self, name: &str, section: Option<&str>) -> Result<Option<PathBuf>, RenderError> {
+        if is_local_page(name) {
+            return Ok(Some(PathBuf::from(name)));
+        }
+        if parse_remote_page(name).is_some() {
+            return Ok(None);
+        }
+        let mut man_cmd = self.man_command();
+        man_cmd.arg("-w");
+        if let Some(section) = section {
+            man_cmd.arg(man_flavor().section_flag()).arg(section);
+        }
+        let output = match man_cmd
+            .arg(name)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .output()
+        {
+            Ok(output) => output,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        if !output.status.success() {
+            return Ok(None);
         }
+        let text = String::from_utf8_lossy(&output.stdout);
+        Ok(text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(PathBuf::from))
+    }
+}
+
+struct ManProcess {
+    name: String,
+    child: Child,
+    stdout: Option<ChildStdout>,
+    writer: Option<thread::JoinHandle<std::io::Result<()>>>,
+    errors: thread::JoinHandle<std::io::Result<Vec<u8>>>,
+}
 
-        let col_status = col_child.wait()?;
-        if !col_status.success() {
-            return Err(RenderError::CommandFailed(format!(
-                "col exited with {col_status}"
-            )));
+impl ManProcess {
+    fn finish(mut self) -> Result<(), RenderError> {
+        drop(self.stdout.take());
+        let written = self.writer.map_or(Ok(()), join_writer);
+        let status = self.child.wait()?;
+        let error_output = self.errors.join().unwrap_or_else(|_| Ok(Vec::new()))?;
+        if status.success() {
+            return written;
         }
+        let message = String::from_utf8_lossy(&error_output).trim().to_string();
+        let message = if message.is_empty() {
+            format!("man exited with {status}")
+        } else {
+            message
+        };
+        tracing::warn!(name = self.name, %status, "man failed: {message}");
+        Err(RenderError::CommandFailed(message))
+    }
+}
+
+fn lossy_warning(name: &str) -> String {
+    format!("{name} is not UTF-8; unreadable characters are shown as \u{fffd}")
+}
+
+fn roff_options(inherited: Option<&str>, configured: &str) -> String {
+    match inherited.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(inherited) => format!("{inherited} {}", configured.trim()),
+        None => configured.trim().to_string(),
+    }
+}
+
+fn preprocessors(inherited: Option<&str>) -> String {
+    match inherited.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(inherited) if inherited.contains('t') => inherited.to_string(),
+        Some(inherited) => format!("{inherited}t"),
+        None => "t".to_string(),
+    }
+}
+
+fn render_with_mandoc(
+    mandoc: &Path,
+    source: Vec<u8>,
+    width: u16,
+) -> Result<Vec<String>, RenderError> {
+    let mut child = Command::new(mandoc)
+        .arg("-T")
+        .arg("utf8")
+        .arg("-O")
+        .arg(format!("width={}", width.max(1)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let output = pipe_through(&mut child, source)?;
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(RenderError::CommandFailed(format!(
+            "mandoc exited with {status}"
+        )));
+    }
+    Ok(clean_formatted_text(&String::from_utf8_lossy(&output)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_configured_roff_options() {
+        assert_eq!(roff_options(None, " -P -c "), "-P -c");
+        assert_eq!(roff_options(Some("-rLL=70n"), "-P -c"), "-rLL=70n -P -c");
+        assert_eq!(roff_options(Some("  "), "-P -c"), "-P -c");
+    }
 
-        let text = String::from_utf8(output)?;
-        Ok(text.lines().map(|line| line.to_string()).collect())
+    #[test]
+    fn always_preprocesses_tables() {
+        assert_eq!(preprocessors(None), "t");
+        assert_eq!(preprocessors(Some("e")), "et");
+        assert_eq!(preprocessors(Some("te")), "te");
     }
 }
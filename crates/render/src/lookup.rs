@@ -0,0 +1,253 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::RenderError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageEntry {
+    pub name: String,
+    pub section: String,
+    pub description: String,
+}
+
+pub fn lookup_sections(name: &str) -> Result<Vec<PageEntry>, RenderError> {
+    let output = match Command::new("whatis")
+        .arg(name)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+    {
+        Ok(output) => output,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_whatis(&text, name))
+}
+
+pub fn search_apropos(keyword: &str) -> Result<Vec<PageEntry>, RenderError> {
+    run_apropos(Command::new("apropos").arg(keyword))
+}
+
+pub fn build_page_index() -> Result<Vec<PageEntry>, RenderError> {
+    run_apropos(Command::new("man").args(["-k", "."]))
+}
+
+pub fn load_page_index(path: &Path) -> Vec<PageEntry> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let name = fields.next()?;
+            let section = fields.next()?;
+            Some(PageEntry {
+                name: name.to_string(),
+                section: section.to_string(),
+                description: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn save_page_index(path: &Path, entries: &[PageEntry]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let text: String = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}\t{}\t{}\n",
+                entry.name,
+                entry.section,
+                entry.description.replace(['\t', '\n'], " ")
+            )
+        })
+        .collect();
+    fs::write(path, text)
+}
+
+fn run_apropos(command: &mut Command) -> Result<Vec<PageEntry>, RenderError> {
+    let output = match command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+    {
+        Ok(output) => output,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_apropos(&text))
+}
+
+pub fn parse_apropos(text: &str) -> Vec<PageEntry> {
+    let mut entries: Vec<PageEntry> = Vec::new();
+    for line in text.lines() {
+        let Some(mut entry) = parse_whatis_line(line) else {
+            continue;
+        };
+        if let Some((first, _)) = entry.name.split_once(',') {
+            entry.name = first.trim().to_string();
+        }
+        if entries
+            .iter()
+            .any(|known| known.name == entry.name && known.section == entry.section)
+        {
+            continue;
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+pub fn parse_whatis(text: &str, name: &str) -> Vec<PageEntry> {
+    let mut entries: Vec<PageEntry> = Vec::new();
+    for line in text.lines() {
+        let Some(entry) = parse_whatis_line(line) else {
+            continue;
+        };
+        if entry.name != name {
+            continue;
+        }
+        if entries.iter().any(|known| known.section == entry.section) {
+            continue;
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+pub fn order_sections(entries: &mut [PageEntry], order: &[String]) {
+    let rank = |section: &str| {
+        order
+            .iter()
+            .position(|preferred| section.starts_with(preferred.as_str()))
+            .map_or((order.len(), false), |position| {
+                (position, order[position] != section)
+            })
+    };
+    entries.sort_by_key(|entry| rank(&entry.section));
+}
+
+pub fn parse_page_paths(text: &str) -> Vec<String> {
+    let mut sections: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let file = line.rsplit('/').next().unwrap_or(line);
+        let file = ["gz", "bz2", "xz", "zst", "lzma", "Z"]
+            .iter()
+            .find_map(|ext| file.strip_suffix(ext)?.strip_suffix('.'))
+            .unwrap_or(file);
+        if let Some((_, section)) = file.rsplit_once('.')
+            && !section.is_empty()
+            && !sections.iter().any(|known| known == section)
+        {
+            sections.push(section.to_string());
+        }
+    }
+    sections
+}
+
+fn parse_whatis_line(line: &str) -> Option<PageEntry> {
+    let (head, description) = match line.split_once(" - ") {
+        Some((head, description)) => (head, description.trim()),
+        None => (line, ""),
+    };
+    let open = head.find('(')?;
+    let close = head[open..].find(')')? + open;
+    let name = head[..open].trim();
+    let section = head[open + 1..close].trim();
+    if name.is_empty() || section.is_empty() {
+        return None;
+    }
+    Some(PageEntry {
+        name: name.to_string(),
+        section: section.to_string(),
+        description: description.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::TempDir;
+
+    #[test]
+    fn parses_sections_from_page_paths() {
+        let text = "/usr/share/man/man2/open.2.gz\n\
+                    /usr/share/man/man3/open.3p.gz\n\
+                    /usr/local/man/man2/open.2\n\
+                    /usr/share/man/mann/open.n.zst\n";
+        assert_eq!(parse_page_paths(text), vec!["2", "3p", "n"]);
+        assert!(parse_page_paths("").is_empty());
+    }
+
+    #[test]
+    fn parses_whatis_output() {
+        let text = "printf (1)           - format and print data\n\
+                    printf (3)           - formatted output conversion\n\
+                    printf (1)           - duplicate entry\n\
+                    printf (3p)          - print formatted output\n";
+        let entries = parse_whatis(text, "printf");
+        let sections: Vec<&str> = entries.iter().map(|e| e.section.as_str()).collect();
+        assert_eq!(sections, vec!["1", "3", "3p"]);
+        assert_eq!(entries[1].description, "formatted output conversion");
+    }
+
+    #[test]
+    fn orders_sections_by_preference() {
+        let text = "printf (1) - a\nprintf (3p) - b\nprintf (3) - c\nprintf (1p) - d\n";
+        let mut entries = parse_whatis(text, "printf");
+        order_sections(&mut entries, &["3".to_string(), "1".to_string()]);
+        let sections: Vec<&str> = entries.iter().map(|e| e.section.as_str()).collect();
+        assert_eq!(sections, vec!["3", "3p", "1", "1p"]);
+        order_sections(&mut entries, &[]);
+        assert_eq!(entries[0].section, "3");
+    }
+
+    #[test]
+    fn parses_apropos_output() {
+        let text = "ls (1)               - list directory contents\n\
+                    lsblk (8)            - list block devices\n\
+                    dir, vdir(1) - list directory contents\n\
+                    ls (1)               - duplicate entry\n\
+                    nothing appropriate.\n";
+        let entries = parse_apropos(text);
+        let names: Vec<String> = entries
+            .iter()
+            .map(|e| format!("{}({})", e.name, e.section))
+            .collect();
+        assert_eq!(names, vec!["ls(1)", "lsblk(8)", "dir(1)"]);
+        assert_eq!(entries[1].description, "list block devices");
+    }
+
+    #[test]
+    fn round_trips_page_index() {
+        let dir = TempDir::new("index").expect("temp dir");
+        let path = dir.join("index.tsv");
+        let entries = parse_apropos("ls (1) - list\tdirectory contents\nlsblk (8) -\n");
+        save_page_index(&path, &entries).expect("save");
+        let loaded = load_page_index(&path);
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].description, "list directory contents");
+        assert_eq!(loaded[1].name, "lsblk");
+        let _ = fs::remove_file(&path);
+        assert!(load_page_index(&path).is_empty());
+    }
+
+    #[test]
+    fn skips_other_names_and_garbage() {
+        let text = "printf: nothing appropriate.\nfprintf (3) - other\n";
+        assert!(parse_whatis(text, "printf").is_empty());
+    }
+}
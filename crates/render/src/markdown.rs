@@ -0,0 +1,357 @@
+use std::path::Path;
+
+use crate::display_width;
+
+const BODY_INDENT: &str = "       ";
+const SUBHEADING_INDENT: &str = "   ";
+const CODE_INDENT: &str = "           ";
+
+pub fn is_markdown_page(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|value| value.to_str())
+        .is_some_and(|value| {
+            value.eq_ignore_ascii_case("md") || value.eq_ignore_ascii_case("markdown")
+        })
+}
+
+pub fn render_markdown(text: &str, width: u16) -> Vec<String> {
+    let mut writer = Writer::new(usize::from(width.max(1)));
+    let mut fence: Option<&str> = None;
+    for raw in text.lines() {
+        let line = raw.trim_end();
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+                writer.blank();
+            } else {
+                writer.verbatim(CODE_INDENT, line);
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            writer.flush();
+            fence = Some(marker);
+            continue;
+        }
+        if trimmed.is_empty() {
+            writer.flush();
+            writer.blank();
+            continue;
+        }
+        if let Some(level) = setext_level(trimmed)
+            && writer.has_plain_paragraph()
+        {
+            let title = writer.take_paragraph();
+            writer.heading(level, &title);
+            continue;
+        }
+        if let Some((level, title)) = atx_heading(trimmed) {
+            writer.flush();
+            writer.heading(level, title);
+            continue;
+        }
+        if is_rule(trimmed) {
+            writer.flush();
+            writer.blank();
+            continue;
+        }
+        if trimmed.starts_with('|') {
+            writer.flush();
+            writer.verbatim(BODY_INDENT, trimmed);
+            continue;
+        }
+        if line.starts_with("    ") && !writer.in_paragraph() {
+            writer.verbatim(CODE_INDENT, &line[4..]);
+            continue;
+        }
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            let prefix = format!("{BODY_INDENT}| ");
+            writer.start(prefix.clone(), prefix);
+            writer.push(quote.trim_start());
+            continue;
+        }
+        if let Some((marker, rest)) = list_item(trimmed) {
+            let nesting = (line.len() - trimmed.len()) / 2 * 2;
+            let first = format!("{BODY_INDENT}{}{marker} ", " ".repeat(nesting));
+            let hanging = " ".repeat(first.chars().count());
+            writer.flush();
+            writer.start(first, hanging);
+            writer.push(rest);
+            continue;
+        }
+        if !writer.in_paragraph() {
+            writer.start(BODY_INDENT.to_string(), BODY_INDENT.to_string());
+        }
+        writer.push(trimmed);
+    }
+    writer.flush();
+    writer.finish()
+}
+
+struct Writer {
+    width: usize,
+    lines: Vec<String>,
+    words: Vec<String>,
+    first_prefix: String,
+    rest_prefix: String,
+}
+
+impl Writer {
+    fn new(width: usize) -> Self {
+        Self {
+            width,
+            lines: Vec::new(),
+            words: Vec::new(),
+            first_prefix: String::new(),
+            rest_prefix: String::new(),
+        }
+    }
+
+    fn in_paragraph(&self) -> bool {
+        !self.words.is_empty()
+    }
+
+    fn has_plain_paragraph(&self) -> bool {
+        self.in_paragraph() && self.first_prefix == BODY_INDENT && self.rest_prefix == BODY_INDENT
+    }
+
+    fn start(&mut self, first_prefix: String, rest_prefix: String) {
+        self.flush();
+        self.first_prefix = first_prefix;
+        self.rest_prefix = rest_prefix;
+    }
+
+    fn push(&mut self, text: &str) {
+        self.words
+            .extend(inline(text).split_whitespace().map(str::to_string));
+    }
+
+    fn take_paragraph(&mut self) -> String {
+        std::mem::take(&mut self.words).join(" ")
+    }
+
+    fn blank(&mut self) {
+        if self.lines.last().is_some_and(|line| !line.is_empty()) {
+            self.lines.push(String::new());
+        }
+    }
+
+    fn heading(&mut self, level: usize, title: &str) {
+        self.blank();
+        let title = inline(title);
+        if level <= 2 {
+            self.lines.push(title.to_uppercase());
+        } else {
+            self.lines.push(format!("{SUBHEADING_INDENT}{title}"));
+        }
+    }
+
+    fn verbatim(&mut self, prefix: &str, text: &str) {
+        self.lines
+            .push(format!("{prefix}{text}").trim_end().to_string());
+    }
+
+    fn flush(&mut self) {
+        if self.words.is_empty() {
+            return;
+        }
+        let mut current = self.first_prefix.clone();
+        let mut current_width = display_width(&current);
+        let mut empty = true;
+        for word in std::mem::take(&mut self.words) {
+            let word_width = display_width(&word);
+            if !empty && current_width + 1 + word_width > self.width {
+                self.lines.push(current);
+                current = self.rest_prefix.clone();
+                current_width = display_width(&current);
+                empty = true;
+            }
+            if !empty {
+                current.push(' ');
+                current_width += 1;
+            }
+            current.push_str(&word);
+            current_width += word_width;
+            empty = false;
+        }
+        self.lines.push(current);
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        while self.lines.last().is_some_and(|line| line.is_empty()) {
+            self.lines.pop();
+        }
+        self.lines
+    }
+}
+
+fn atx_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|ch| *ch == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn setext_level(line: &str) -> Option<usize> {
+    if line.chars().all(|ch| ch == '=') {
+        Some(1)
+    } else if line.chars().all(|ch| ch == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|ch| !ch.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| compact.chars().all(|ch| ch == *marker))
+}
+
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = line.strip_prefix(bullet) {
+            return Some(("\u{2022}".to_string(), rest));
+        }
+    }
+    let digits = line.chars().take_while(|ch| ch.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    let rest = line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))?;
+    Some((format!("{}.", &line[..digits]), rest))
+}
+
+fn inline(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        if ch == '`'
+            && let Some(end) = rest[1..].find('`')
+        {
+            out.push_str(&rest[1..end + 1]);
+            rest = &rest[end + 2..];
+            continue;
+        }
+        if ch == '!'
+            && rest[1..].starts_with('[')
+            && let Some((label, _, used)) = link(&rest[1..])
+        {
+            out.push_str(&format!("[image: {label}]"));
+            rest = &rest[1 + used..];
+            continue;
+        }
+        if ch == '['
+            && let Some((label, target, used)) = link(rest)
+        {
+            out.push_str(&inline(label));
+            if !target.is_empty() && !target.starts_with('#') && target != label {
+                out.push_str(&format!(" <{target}>"));
+            }
+            rest = &rest[used..];
+            continue;
+        }
+        if rest.starts_with("**") || rest.starts_with("__") {
+            rest = &rest[2..];
+            continue;
+        }
+        if ch == '*' && is_emphasis(&out, &rest[1..]) {
+            rest = &rest[1..];
+            continue;
+        }
+        out.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    out
+}
+
+fn is_emphasis(before: &str, after: &str) -> bool {
+    let prev = before.chars().next_back();
+    let next = after.chars().next();
+    let opens =
+        prev.is_none_or(|ch| !ch.is_alphanumeric()) && next.is_some_and(|ch| !ch.is_whitespace());
+    let closes =
+        prev.is_some_and(|ch| !ch.is_whitespace()) && next.is_none_or(|ch| !ch.is_alphanumeric());
+    opens || closes
+}
+
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let close = text.find("](")?;
+    let label = &text[1..close];
+    let target_start = close + 2;
+    let target_end = text[target_start..].find(')')? + target_start;
+    let target = text[target_start..target_end]
+        .split_whitespace()
+        .next()
+        .unwrap_or("");
+    Some((label, target, target_end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_markdown_files() {
+        assert!(is_markdown_page("README.md"));
+        assert!(is_markdown_page("docs/intro.MARKDOWN"));
+        assert!(!is_markdown_page("ls.1"));
+        assert!(!is_markdown_page("md"));
+    }
+
+    #[test]
+    fn renders_headings_paragraphs_and_code() {
+        let text = "# Manifold\n\
+                    \n\
+                    A **tabbed** pager for `man` pages, see [docs](https://example.org).\n\
+                    \n\
+                    ## Usage\n\
+                    ### Flags\n\
+                    ```sh\n\
+                    manifold ls\n\
+                    ```\n\
+                    - one\n\
+                    - two\n";
+        assert_eq!(
+            render_markdown(text, 80),
+            vec![
+                "MANIFOLD",
+                "",
+                "       A tabbed pager for man pages, see docs <https://example.org>.",
+                "",
+                "USAGE",
+                "",
+                "   Flags",
+                "           manifold ls",
+                "",
+                "       \u{2022} one",
+                "       \u{2022} two",
+            ]
+        );
+    }
+
+    #[test]
+    fn wraps_paragraphs_with_hanging_indent() {
+        let text = "1. alpha beta gamma delta\n\nSetext\n======\n";
+        assert_eq!(
+            render_markdown(text, 24),
+            vec![
+                "       1. alpha beta",
+                "          gamma delta",
+                "",
+                "SETEXT",
+            ]
+        );
+    }
+}
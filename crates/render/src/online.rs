@@ -0,0 +1,359 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::{
+    ManRenderer, PageEntry, RenderError, clean_formatted_line, is_local_page, parse_remote_page,
+};
+
+const SEARCH_SECTIONS: &[&str] = &["1", "8", "3", "2", "5", "4", "6", "7"];
+const FETCH_TIMEOUT_SECS: &str = "10";
+
+pub struct OnlineRenderer<R> {
+    inner: R,
+    cache_dir: PathBuf,
+    sections: Vec<String>,
+}
+
+impl<R: ManRenderer> OnlineRenderer<R> {
+    pub fn new(inner: R, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            cache_dir: cache_dir.into(),
+            sections: SEARCH_SECTIONS
+                .iter()
+                .map(|section| section.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn with_sections(mut self, sections: Vec<String>) -> Self {
+        if !sections.is_empty() {
+            self.sections = sections;
+        }
+        self
+    }
+
+    fn render_online(&self, name: &str, section: Option<&str>) -> Result<Vec<String>, RenderError> {
+        let sections: Vec<&str> = match section {
+            Some(section) => vec![section],
+            None => self.sections.iter().map(String::as_str).collect(),
+        };
+        if let Some(lines) = sections
+            .iter()
+            .find_map(|section| self.cached(name, section))
+        {
+            return Ok(lines);
+        }
+        for section in sections {
+            let Some(url) = man7_url(name, section) else {
+                continue;
+            };
+            let Some(html) = fetch(&url)? else {
+                continue;
+            };
+            let lines = html_to_lines(&html);
+            if lines.is_empty() {
+                continue;
+            }
+            self.store(name, section, &lines);
+            return Ok(lines);
+        }
+        Err(RenderError::CommandFailed(format!(
+            "No manual entry for {name} (checked man7.org)"
+        )))
+    }
+
+    fn cache_path(&self, name: &str, section: &str) -> PathBuf {
+        self.cache_dir
+            .join(format!("{}.{}.txt", sanitize(name), sanitize(section)))
+    }
+
+    fn cached(&self, name: &str, section: &str) -> Option<Vec<String>> {
+        let text = fs::read_to_string(self.cache_path(name, section)).ok()?;
+        Some(text.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn store(&self, name: &str, section: &str, lines: &[String]) {
+        if fs::create_dir_all(&self.cache_dir).is_ok() {
+            let _ = fs::write(self.cache_path(name, section), lines.join("\n"));
+        }
+    }
+}
+
+impl<R: ManRenderer> ManRenderer for OnlineRenderer<R> {
+    fn render(
+        &self,
+        name: &str,
+        section: Option<&str>,
+        width: u16,
+    ) -> Result<Vec<String>, RenderError> {
+        match self.inner.render(name, section, width) {
+            Ok(lines) => Ok(lines),
+            Err(RenderError::CommandFailed(_) | RenderError::Io(_))
+                if !is_local_page(name) && parse_remote_page(name).is_none() =>
+            {
+                self.render_online(name, section)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<Vec<PageEntry>, RenderError> {
+        self.inner.lookup(name)
+    }
+
+    fn apropos(&self, keyword: &str) -> Result<Vec<PageEntry>, RenderError> {
+        self.inner.apropos(keyword)
+    }
+
+    fn locate(&self, name: &str, section: Option<&str>) -> Result<Option<PathBuf>, RenderError> {
+        self.inner.locate(name, section)
+    }
+
+    fn sections(&self, name: &str) -> Result<Vec<String>, RenderError> {
+        self.inner.sections(name)
+    }
+
+    fn help_command(&self, name: &str) -> Option<PathBuf> {
+        self.inner.help_command(name)
+    }
+
+    fn take_warning(&self) -> Option<String> {
+        self.inner.take_warning()
+    }
+
+    fn render_help(&self, program: &Path, width: u16) -> Result<Vec<String>, RenderError> {
+        self.inner.render_help(program, width)
+    }
+
+    fn run_command(&self, command: &str, width: u16) -> Result<Vec<String>, RenderError> {
+        self.inner.run_command(command, width)
+    }
+
+    fn typeset(&self, source: Vec<u8>, device: &str) -> Result<Vec<u8>, RenderError> {
+        self.inner.typeset(source, device)
+    }
+}
+
+fn man7_url(name: &str, section: &str) -> Option<String> {
+    let chapter: String = section
+        .chars()
+        .take_while(|ch| ch.is_ascii_digit())
+        .collect();
+    if chapter.is_empty() || !is_page_name(name) || !is_page_name(section) {
+        return None;
+    }
+    Some(format!(
+        "https://man7.org/linux/man-pages/man{chapter}/{name}.{section}.html"
+    ))
+}
+
+fn is_page_name(value: &str) -> bool {
+    !value.is_empty()
+        && !value.starts_with('.')
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.' | '+' | ':'))
+}
+
+pub(crate) fn fetch(url: &str) -> Result<Option<String>, RenderError> {
+    tracing::debug!(url, "fetching page");
+    let output = Command::new("curl")
+        .arg("-fsSL")
+        .arg("--max-time")
+        .arg(FETCH_TIMEOUT_SECS)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        tracing::debug!(url, status = %output.status, "fetch failed");
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+pub fn html_to_lines(html: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut rest = html;
+    while let Some(start) = find_block(rest) {
+        let (tag, body_start) = start;
+        let close = format!("</{tag}>");
+        let Some(end) = rest[body_start..].find(&close) else {
+            break;
+        };
+        let body = decode_entities(&strip_tags(&rest[body_start..body_start + end]));
+        if tag == "h2" {
+            let heading = body.trim().trim_end_matches("top").trim();
+            if !heading.is_empty() {
+                if lines.last().is_some_and(|line| !line.is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(heading.to_string());
+            }
+        } else {
+            lines.extend(body.trim_matches('\n').lines().map(clean_formatted_line));
+        }
+        rest = &rest[body_start + end + close.len()..];
+    }
+    lines
+}
+
+fn find_block(html: &str) -> Option<(&'static str, usize)> {
+    ["pre", "h2"]
+        .into_iter()
+        .filter_map(|tag| {
+            let open = format!("<{tag}");
+            let mut offset = 0;
+            while let Some(pos) = html[offset..].find(&open) {
+                let at = offset + pos;
+                let after = html[at + open.len()..].chars().next();
+                if matches!(after, Some('>' | ' ' | '\n' | '\t')) {
+                    let end = html[at..].find('>')? + at + 1;
+                    return Some((at, tag, end));
+                }
+                offset = at + open.len();
+            }
+            None
+        })
+        .min_by_key(|(at, _, _)| *at)
+        .map(|(_, tag, end)| (tag, end))
+}
+
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for ch in text.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(ch),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            }?;
+            Some((ch, end))
+        });
+        match decoded {
+            Some((ch, end)) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub(crate) fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.' | '+') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::TempDir;
+
+    struct MissingRenderer;
+
+    impl ManRenderer for MissingRenderer {
+        fn render(
+            &self,
+            name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Err(RenderError::CommandFailed(format!(
+                "No manual entry for {name}"
+            )))
+        }
+    }
+
+    #[test]
+    fn converts_man7_html_to_lines() {
+        let html = "<html><body><h1>ls(1)</h1>\n\
+                    <h2><a id=\"NAME\" href=\"#NAME\"></a>NAME &nbsp; &nbsp; \
+                    <a href=\"#top_of_page\"><span class=\"top-link\">top</span></a></h2><pre>\n\
+                    \x20      ls - list directory contents\n\
+                    </pre>\n\
+                    <h2>SYNOPSIS</h2><pre>\n\
+                    \x20      <b>ls</b> [<i>OPTION</i>]... &lt;dir&gt; &#x41;&#66;\n\
+                    </pre></body></html>";
+        assert_eq!(
+            html_to_lines(html),
+            vec![
+                "NAME",
+                "       ls - list directory contents",
+                "",
+                "SYNOPSIS",
+                "       ls [OPTION]... <dir> AB",
+            ]
+        );
+    }
+
+    #[test]
+    fn serves_cached_pages_without_fetching() {
+        let dir = TempDir::new("online").expect("temp dir");
+        let renderer = OnlineRenderer::new(MissingRenderer, dir.path());
+        renderer.store("ls", "1", &["NAME".to_string(), "   ls".to_string()]);
+        assert_eq!(
+            renderer.render("ls", None, 80).expect("cached"),
+            vec!["NAME".to_string(), "   ls".to_string()]
+        );
+        assert!(matches!(
+            renderer.render("./missing.1", None, 80),
+            Err(RenderError::CommandFailed(_))
+        ));
+    }
+
+    #[test]
+    fn builds_man7_urls() {
+        assert_eq!(
+            man7_url("printf", "3p").as_deref(),
+            Some("https://man7.org/linux/man-pages/man3/printf.3p.html")
+        );
+        assert_eq!(man7_url("../../evil", "1"), None);
+        assert_eq!(man7_url("ls?x=1#", "1"), None);
+        assert_eq!(man7_url("ls", "1/../8"), None);
+        assert_eq!(man7_url("ls", "n"), None);
+    }
+}
@@ -0,0 +1,107 @@
+use std::process::{Command, Stdio};
+
+use crate::{RenderError, clean_formatted_text, decode_output};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemotePage<'a> {
+    pub host: &'a str,
+    pub topic: &'a str,
+}
+
+pub fn parse_remote_page(name: &str) -> Option<RemotePage<'_>> {
+    if name.contains("::") {
+        return None;
+    }
+    let (host, topic) = name.split_once(':')?;
+    if host.is_empty()
+        || topic.is_empty()
+        || host.contains(std::path::is_separator)
+        || host.starts_with('-')
+    {
+        return None;
+    }
+    Some(RemotePage { host, topic })
+}
+
+pub fn render_remote(
+    page: RemotePage<'_>,
+    section: Option<&str>,
+    width: u16,
+) -> Result<Vec<String>, RenderError> {
+    let output = Command::new("ssh")
+        .arg("-o")
+        .arg("BatchMode=yes")
+        .arg("--")
+        .arg(page.host)
+        .arg(remote_command(page.topic, section, width))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let message = if message.is_empty() {
+            format!("ssh {} exited with {}", page.host, output.status)
+        } else {
+            message
+        };
+        return Err(RenderError::CommandFailed(message));
+    }
+    let (text, lossy) = decode_output(output.stdout);
+    if lossy {
+        tracing::warn!(
+            host = page.host,
+            topic = page.topic,
+            "remote man output is not valid UTF-8"
+        );
+    }
+    Ok(clean_formatted_text(&text))
+}
+
+fn remote_command(topic: &str, section: Option<&str>, width: u16) -> String {
+    let mut command = format!("MANWIDTH={} MANPAGER=cat man", width.max(1));
+    if let Some(section) = section {
+        command.push(' ');
+        command.push_str(&shell_quote(section));
+    }
+    command.push(' ');
+    command.push_str(&shell_quote(topic));
+    command
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_host_prefixed_topics() {
+        assert_eq!(
+            parse_remote_page("web1:nginx"),
+            Some(RemotePage {
+                host: "web1",
+                topic: "nginx"
+            })
+        );
+        assert_eq!(
+            parse_remote_page("admin@db:pg_dump").map(|page| page.host),
+            Some("admin@db")
+        );
+        assert_eq!(parse_remote_page("File::Temp"), None);
+        assert_eq!(parse_remote_page("ls"), None);
+        assert_eq!(parse_remote_page("./a:b"), None);
+        assert_eq!(parse_remote_page("host:"), None);
+        assert_eq!(parse_remote_page("-oProxyCommand=sh:ls"), None);
+    }
+
+    #[test]
+    fn quotes_remote_arguments() {
+        assert_eq!(
+            remote_command("it's", Some("1"), 72),
+            "MANWIDTH=72 MANPAGER=cat man '1' 'it'\\''s'"
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::{RenderError, is_markdown_page};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    pub fn detect(path: &Path, bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            return Compression::Gzip;
+        }
+        if bytes.starts_with(b"BZh") {
+            return Compression::Bzip2;
+        }
+        if bytes.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            return Compression::Xz;
+        }
+        if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            return Compression::Zstd;
+        }
+        match path.extension().and_then(|value| value.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("bz2") => Compression::Bzip2,
+            Some("xz") => Compression::Xz,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    fn decompressor(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gzip"),
+            Compression::Bzip2 => Some("bzip2"),
+            Compression::Xz => Some("xz"),
+            Compression::Zstd => Some("zstd"),
+        }
+    }
+}
+
+pub fn is_local_page(name: &str) -> bool {
+    name.contains(std::path::is_separator) || is_markdown_page(name)
+}
+
+pub fn read_page_source(path: &Path) -> Result<Vec<u8>, RenderError> {
+    let bytes = fs::read(path)?;
+    let compression = Compression::detect(path, &bytes);
+    decompress(bytes, compression)
+}
+
+pub fn decompress(bytes: Vec<u8>, compression: Compression) -> Result<Vec<u8>, RenderError> {
+    let Some(program) = compression.decompressor() else {
+        return Ok(bytes);
+    };
+    let mut child = Command::new(program)
+        .arg("-dc")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| RenderError::CommandFailed(format!("{program} unavailable: {err}")))?;
+
+    let output = pipe_through(&mut child, bytes)?;
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(RenderError::CommandFailed(format!(
+            "{program} exited with {status}"
+        )));
+    }
+    Ok(output)
+}
+
+pub fn typeset_page(source: Vec<u8>, device: &str) -> Result<Vec<u8>, RenderError> {
+    let mut child = Command::new("groff")
+        .args(["-k", "-t", "-e", "-mandoc"])
+        .arg(format!("-T{device}"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| RenderError::CommandFailed(format!("groff unavailable: {err}")))?;
+
+    let output = pipe_through(&mut child, source)?;
+    let status = child.wait()?;
+    if !status.success() || output.is_empty() {
+        return Err(RenderError::CommandFailed(format!(
+            "groff -T{device} exited with {status}"
+        )));
+    }
+    Ok(output)
+}
+
+pub(crate) fn pipe_through(
+    child: &mut std::process::Child,
+    input: Vec<u8>,
+) -> Result<Vec<u8>, RenderError> {
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| RenderError::CommandFailed("stdin unavailable".to_string()))?;
+    let writer = thread::spawn(move || stdin.write_all(&input));
+
+    let mut output = Vec::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        stdout.read_to_end(&mut output)?;
+    }
+    join_writer(writer)?;
+    Ok(output)
+}
+
+pub(crate) fn join_writer(
+    writer: thread::JoinHandle<std::io::Result<()>>,
+) -> Result<(), RenderError> {
+    match writer.join() {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(RenderError::CommandFailed(
+            "stdin writer panicked".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn gzip_available() -> bool {
+        Command::new("gzip")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn detects_compression_by_magic_then_extension() {
+        let path = PathBuf::from("ls.1");
+        assert_eq!(
+            Compression::detect(&path, &[0x1f, 0x8b, 0x08]),
+            Compression::Gzip
+        );
+        assert_eq!(Compression::detect(&path, b"BZh91AY"), Compression::Bzip2);
+        assert_eq!(
+            Compression::detect(&path, &[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            Compression::Zstd
+        );
+        assert_eq!(
+            Compression::detect(&PathBuf::from("ls.1.xz"), b""),
+            Compression::Xz
+        );
+        assert_eq!(Compression::detect(&path, b".TH LS 1"), Compression::None);
+    }
+
+    #[test]
+    fn reports_failed_stdin_writers() {
+        let failed = thread::spawn(|| Err(std::io::Error::other("pipe closed")));
+        assert!(matches!(join_writer(failed), Err(RenderError::Io(_))));
+        let panicked = thread::spawn(|| -> std::io::Result<()> { panic!("writer") });
+        assert!(matches!(
+            join_writer(panicked),
+            Err(RenderError::CommandFailed(_))
+        ));
+        assert!(join_writer(thread::spawn(|| Ok(()))).is_ok());
+    }
+
+    #[test]
+    fn recognizes_local_page_paths() {
+        assert!(is_local_page("./ls.1.gz"));
+        assert!(is_local_page("/usr/share/man/man1/ls.1.gz"));
+        assert!(is_local_page("README.md"));
+        #[cfg(windows)]
+        assert!(is_local_page(".\\tool.1"));
+        assert!(!is_local_page("ls"));
+    }
+
+    #[test]
+    fn decompresses_gzip_source() {
+        if !gzip_available() {
+            return;
+        }
+        let source = b".TH TEST 1\n.SH NAME\ntest \\- a test\n".to_vec();
+        let mut child = Command::new("gzip")
+            .arg("-c")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("spawn gzip");
+        let compressed = pipe_through(&mut child, source.clone()).expect("compress");
+        child.wait().expect("wait gzip");
+
+        let path = PathBuf::from("test.1.gz");
+        let compression = Compression::detect(&path, &compressed);
+        assert_eq!(compression, Compression::Gzip);
+        assert_eq!(
+            decompress(compressed, compression).expect("decompress"),
+            source
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::{self, BufRead};
+use std::sync::mpsc::Sender;
+
+use crate::RenderError;
+use crate::text::{clean_formatted_line, decode_output};
+
+const BATCH_LINES: usize = 200;
+
+#[derive(Debug)]
+pub enum RenderChunk {
+    Lines(Vec<String>),
+    Warning(String),
+    Done(Result<(), RenderError>),
+}
+
+pub(crate) fn send_lines(
+    mut reader: impl BufRead,
+    sender: &Sender<RenderChunk>,
+) -> io::Result<Option<bool>> {
+    let mut batch = Vec::with_capacity(BATCH_LINES);
+    let mut lossy = false;
+    loop {
+        let mut line = Vec::new();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        let (text, bad) = decode_output(line);
+        lossy |= bad;
+        batch.push(clean_formatted_line(&text));
+        if batch.len() == BATCH_LINES
+            && sender
+                .send(RenderChunk::Lines(std::mem::take(&mut batch)))
+                .is_err()
+        {
+            return Ok(None);
+        }
+    }
+    if !batch.is_empty() && sender.send(RenderChunk::Lines(batch)).is_err() {
+        return Ok(None);
+    }
+    Ok(Some(lossy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn sends_cleaned_lines_in_batches() {
+        let text: String = (0..450).map(|idx| format!("line\t{idx}\n")).collect();
+        let (sender, receiver) = mpsc::channel();
+        let lossy = send_lines(io::Cursor::new(text.into_bytes()), &sender).expect("read");
+        assert_eq!(lossy, Some(false));
+        drop(sender);
+        let batches: Vec<Vec<String>> = receiver
+            .iter()
+            .map(|chunk| match chunk {
+                RenderChunk::Lines(lines) => lines,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        let sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![200, 200, 50]);
+        assert_eq!(batches[2][49], "line    449");
+
+        let (sender, receiver) = mpsc::channel();
+        drop(receiver);
+        let bytes = b"N\x08NAME\n\xff\n".to_vec();
+        assert_eq!(
+            send_lines(io::Cursor::new(bytes), &sender).expect("read"),
+            None
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use unicode_width::UnicodeWidthChar;
+
+const TAB_WIDTH: usize = 8;
+
+pub fn decode_output(bytes: Vec<u8>) -> (String, bool) {
+    match String::from_utf8(bytes) {
+        Ok(text) => (text, false),
+        Err(err) => (String::from_utf8_lossy(err.as_bytes()).into_owned(), true),
+    }
+}
+
+pub fn clean_formatted_text(text: &str) -> Vec<String> {
+    text.lines().map(clean_formatted_line).collect()
+}
+
+pub fn clean_formatted_line(line: &str) -> String {
+    let mut out: Vec<char> = Vec::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\u{8}' => {
+                out.pop();
+            }
+            '\u{1b}' => skip_escape(&mut chars),
+            '\t' => {
+                let column: usize = out.iter().filter_map(|ch| ch.width()).sum();
+                let pad = TAB_WIDTH - column % TAB_WIDTH;
+                out.extend(std::iter::repeat_n(' ', pad));
+            }
+            '\r' => {}
+            _ => out.push(ch),
+        }
+    }
+    let mut line: String = out.into_iter().collect();
+    line.truncate(line.trim_end().len());
+    line
+}
+
+fn skip_escape(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    match chars.peek() {
+        Some('[') => {
+            chars.next();
+            for ch in chars.by_ref() {
+                if ('\u{40}'..='\u{7e}').contains(&ch) {
+                    break;
+                }
+            }
+        }
+        Some(']') => {
+            chars.next();
+            while let Some(ch) = chars.next() {
+                if ch == '\u{7}' {
+                    break;
+                }
+                if ch == '\u{1b}' && chars.peek() == Some(&'\\') {
+                    chars.next();
+                    break;
+                }
+            }
+        }
+        Some(_) => {
+            chars.next();
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_overstrike_bold_and_underline() {
+        assert_eq!(clean_formatted_line("N\u{8}NA\u{8}AM\u{8}ME\u{8}E"), "NAME");
+        assert_eq!(clean_formatted_line("_\u{8}f_\u{8}i_\u{8}l_\u{8}e"), "file");
+    }
+
+    #[test]
+    fn strips_sgr_and_osc_sequences() {
+        assert_eq!(
+            clean_formatted_line("\u{1b}[1mNAME\u{1b}[0m and \u{1b}]8;;http://x\u{1b}\\link"),
+            "NAME and link"
+        );
+    }
+
+    #[test]
+    fn decodes_invalid_utf8_lossily() {
+        assert_eq!(
+            decode_output(b"caf\xc3\xa9".to_vec()),
+            ("café".to_string(), false)
+        );
+        assert_eq!(
+            decode_output(b"caf\xe9 noir".to_vec()),
+            ("caf\u{fffd} noir".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn expands_tabs_and_trims_trailing_space() {
+        assert_eq!(clean_formatted_line("a\tb   "), "a       b");
+        assert_eq!(clean_formatted_line("漢\tb"), "漢      b");
+        assert_eq!(
+            clean_formatted_text("one\r\ntwo\n"),
+            vec!["one".to_string(), "two".to_string()]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+
+use crate::{ManRenderer, PageEntry, RenderChunk, RenderError};
+
+pub const WELCOME_PAGE: &str = "welcome";
+pub const WELCOME_SECTION: &str = "manifold";
+
+const KEYS: &[(&str, &str)] = &[
+    ("j/k", "scroll a line down/up"),
+    ("d/u, Ctrl-f/b", "half a page, a whole page"),
+    ("g/G, 42G", "top, bottom, line 42"),
+    ("/", "search; n and N step through matches"),
+    ("]]/[[", "next/previous section"),
+    ("f", "label references on screen and open one"),
+    ("H/L", "previous/next tab"),
+    ("Ctrl-w", "focus the next split pane"),
+    ("?", "all keys and commands"),
+    ("q, :q", "quit"),
+];
+
+const EXAMPLES: &[(&str, &str)] = &[
+    (":man 2 open", "open a page, here from section 2"),
+    (":man -a printf", "every section of printf in its own tab"),
+    (":find sock", "fuzzy-find installed pages"),
+    (":apropos signal", "search page descriptions"),
+    (":split write", "show another page side by side"),
+    (":goto ERRORS", "jump to a section or an option"),
+    (":cheat tar", "examples from cheat.sh"),
+    (":mksession work", "save the open tabs for later"),
+];
+
+fn welcome_lines() -> Vec<String> {
+    let mut lines = vec![
+        "NAME".to_string(),
+        "       manifold - a tabbed man page reader".to_string(),
+        String::new(),
+        "DESCRIPTION".to_string(),
+        "       Open a page with :man TOPIC, or start manifold with a topic as".to_string(),
+        "       its argument, e.g. manifold 2 open. Set startup_page in the".to_string(),
+        "       config file to open something else instead of this page.".to_string(),
+        String::new(),
+        "KEYS".to_string(),
+    ];
+    lines.extend(table(KEYS));
+    lines.push(String::new());
+    lines.push("EXAMPLES".to_string());
+    lines.extend(table(EXAMPLES));
+    lines.push(String::new());
+    lines.push("SEE ALSO".to_string());
+    lines.push("       man(1), apropos(1)".to_string());
+    lines
+}
+
+fn table(rows: &[(&str, &str)]) -> Vec<String> {
+    let width = rows.iter().map(|(left, _)| left.len()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(left, right)| format!("       {left:<width$}  {right}"))
+        .collect()
+}
+
+fn is_welcome(name: &str, section: Option<&str>) -> bool {
+    name == WELCOME_PAGE && section == Some(WELCOME_SECTION)
+}
+
+pub struct WelcomeRenderer<R> {
+    inner: R,
+}
+
+impl<R: ManRenderer> WelcomeRenderer<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+}
+
+impl<R: ManRenderer> ManRenderer for WelcomeRenderer<R> {
+    fn render(
+        &self,
+        name: &str,
+        section: Option<&str>,
+        width: u16,
+    ) -> Result<Vec<String>, RenderError> {
+        if is_welcome(name, section) {
+            return Ok(welcome_lines());
+        }
+        self.inner.render(name, section, width)
+    }
+
+    fn render_stream(
+        &self,
+        name: &str,
+        section: Option<&str>,
+        width: u16,
+    ) -> Result<Option<Receiver<RenderChunk>>, RenderError> {
+        if is_welcome(name, section) {
+            return Ok(None);
+        }
+        self.inner.render_stream(name, section, width)
+    }
+
+    fn lookup(&self, name: &str) -> Result<Vec<PageEntry>, RenderError> {
+        self.inner.lookup(name)
+    }
+
+    fn apropos(&self, keyword: &str) -> Result<Vec<PageEntry>, RenderError> {
+        self.inner.apropos(keyword)
+    }
+
+    fn locate(&self, name: &str, section: Option<&str>) -> Result<Option<PathBuf>, RenderError> {
+        if is_welcome(name, section) {
+            return Ok(None);
+        }
+        self.inner.locate(name, section)
+    }
+
+    fn sections(&self, name: &str) -> Result<Vec<String>, RenderError> {
+        self.inner.sections(name)
+    }
+
+    fn help_command(&self, name: &str) -> Option<PathBuf> {
+        self.inner.help_command(name)
+    }
+
+    fn take_warning(&self) -> Option<String> {
+        self.inner.take_warning()
+    }
+
+    fn render_help(&self, program: &Path, width: u16) -> Result<Vec<String>, RenderError> {
+        self.inner.render_help(program, width)
+    }
+
+    fn run_command(&self, command: &str, width: u16) -> Result<Vec<String>, RenderError> {
+        self.inner.run_command(command, width)
+    }
+
+    fn typeset(&self, source: Vec<u8>, device: &str) -> Result<Vec<u8>, RenderError> {
+        self.inner.typeset(source, device)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MissingRenderer;
+
+    impl ManRenderer for MissingRenderer {
+        fn render(
+            &self,
+            name: &str,
+            _section: Option<&str>,
+            _width: u16,
+        ) -> Result<Vec<String>, RenderError> {
+            Err(RenderError::CommandFailed(format!(
+                "No manual entry for {name}"
+            )))
+        }
+    }
+
+    #[test]
+    fn serves_the_welcome_page_and_delegates_the_rest() {
+        let renderer = WelcomeRenderer::new(MissingRenderer);
+        let lines = renderer
+            .render(WELCOME_PAGE, Some(WELCOME_SECTION), 80)
+            .expect("welcome");
+        assert_eq!(lines[0], "NAME");
+        assert!(
+            lines.contains(&"       :man 2 open      open a page, here from section 2".to_string())
+        );
+        assert!(renderer.render(WELCOME_PAGE, None, 80).is_err());
+        assert!(
+            renderer
+                .locate(WELCOME_PAGE, Some(WELCOME_SECTION))
+                .expect("locate")
+                .is_none()
+        );
+    }
+}
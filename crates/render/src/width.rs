@@ -0,0 +1,42 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+pub fn display_column(text: &str, index: usize) -> usize {
+    text.chars().take(index).map(char_width).sum()
+}
+
+pub fn char_at_column(text: &str, column: usize) -> usize {
+    let mut width = 0;
+    for (index, ch) in text.chars().enumerate() {
+        width += char_width(ch);
+        if width > column {
+            return index;
+        }
+    }
+    text.chars().count() + column - width
+}
+
+fn char_width(ch: char) -> usize {
+    ch.width().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_wide_and_combining_characters() {
+        let text = "a漢e\u{301}b";
+        assert_eq!(display_width(text), 5);
+        assert_eq!(display_column(text, 2), 3);
+        assert_eq!(display_column(text, 4), 4);
+        assert_eq!(char_at_column(text, 1), 1);
+        assert_eq!(char_at_column(text, 2), 1);
+        assert_eq!(char_at_column(text, 3), 2);
+        assert_eq!(char_at_column(text, 4), 4);
+        assert_eq!(char_at_column(text, 7), 7);
+    }
+}
@@ -0,0 +1,85 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::dirs::state_dir;
+use crate::file::{fields, read_lines, record, write_lines};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub name: String,
+    pub page: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bookmarks {
+    path: PathBuf,
+}
+
+impl Bookmarks {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn open_default() -> Option<Self> {
+        state_dir().map(|dir| Self::new(dir.join("bookmarks")))
+    }
+
+    pub fn list(&self) -> Vec<Bookmark> {
+        let mut bookmarks: Vec<Bookmark> = read_lines(&self.path)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|line| {
+                let [name, line, page] = fields(line)?;
+                Some(Bookmark {
+                    name: name.to_string(),
+                    page: page.to_string(),
+                    line: line.parse().ok()?,
+                })
+            })
+            .collect();
+        bookmarks.sort_by(|a, b| a.name.cmp(&b.name));
+        bookmarks
+    }
+
+    pub fn set(&self, bookmark: Bookmark) -> io::Result<()> {
+        let mut bookmarks = self.list();
+        bookmarks.retain(|known| known.name != bookmark.name);
+        bookmarks.push(bookmark);
+        write_lines(
+            &self.path,
+            bookmarks.iter().map(|bookmark| {
+                record(&[&bookmark.name, &bookmark.line.to_string(), &bookmark.page])
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn replaces_bookmarks_by_name() {
+        let dir = TempDir::new("bookmarks").expect("temp dir");
+        let bookmarks = Bookmarks::new(dir.join("bookmarks"));
+        let mark = |name: &str, page: &str, line| Bookmark {
+            name: name.to_string(),
+            page: page.to_string(),
+            line,
+        };
+        bookmarks.set(mark("traps", "bash(1)", 10)).expect("set");
+        bookmarks
+            .set(mark("exec", "systemd.exec(5)", 4))
+            .expect("set");
+        bookmarks.set(mark("traps", "bash(1)", 42)).expect("set");
+        assert_eq!(
+            bookmarks.list(),
+            vec![
+                mark("exec", "systemd.exec(5)", 4),
+                mark("traps", "bash(1)", 42)
+            ]
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use std::env;
+use std::path::PathBuf;
+
+const APP_DIR: &str = "manifold";
+
+pub fn cache_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CACHE_HOME", ".cache")
+        .or_else(|| windows_dir("LOCALAPPDATA").map(|dir| dir.join("cache")))
+}
+
+pub fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state").or_else(|| windows_dir("LOCALAPPDATA"))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config").or_else(|| windows_dir("APPDATA"))
+}
+
+fn xdg_dir(variable: &str, home_fallback: &str) -> Option<PathBuf> {
+    let base = absolute_var(variable)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(home_fallback)))?;
+    Some(base.join(APP_DIR))
+}
+
+fn windows_dir(variable: &str) -> Option<PathBuf> {
+    absolute_var(variable).map(|base| base.join(APP_DIR))
+}
+
+fn absolute_var(variable: &str) -> Option<PathBuf> {
+    env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
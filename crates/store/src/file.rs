@@ -0,0 +1,96 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::temp::create_temp_file_in;
+
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir)?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (temp, mut file) = create_temp_file_in(dir, &format!("{name}.tmp"))?;
+    let written = file
+        .write_all(contents)
+        .and_then(|()| file.sync_all())
+        .and_then(|()| fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+pub(crate) fn read_lines(path: &Path) -> Option<Vec<String>> {
+    let text = fs::read_to_string(path).ok()?;
+    Some(text.lines().map(str::to_string).collect())
+}
+
+pub(crate) fn write_lines<S: AsRef<str>>(
+    path: &Path,
+    lines: impl IntoIterator<Item = S>,
+) -> io::Result<()> {
+    let mut text = String::new();
+    for line in lines {
+        text.push_str(line.as_ref());
+        text.push('\n');
+    }
+    write_atomic(path, text.as_bytes())
+}
+
+pub(crate) fn fields<const N: usize>(line: &str) -> Option<[&str; N]> {
+    let mut parts = line.splitn(N, '\t');
+    let fields: [Option<&str>; N] = std::array::from_fn(|_| parts.next());
+    fields
+        .iter()
+        .all(Option::is_some)
+        .then(|| fields.map(Option::unwrap_or_default))
+}
+
+pub(crate) fn record(fields: &[&str]) -> String {
+    let last = fields.len().saturating_sub(1);
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            if index < last {
+                field.replace(['\t', '\n'], " ")
+            } else {
+                field.replace('\n', " ")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn replaces_files_without_leaving_temp_files() {
+        let dir = TempDir::new("atomic").expect("temp dir");
+        let path = dir.join("state/lines");
+        write_lines(&path, ["first", "second"]).expect("write");
+        write_lines(&path, [record(&["a\tb", "1", "tail\tkept"])]).expect("rewrite");
+        assert_eq!(
+            read_lines(&path),
+            Some(vec!["a b\t1\ttail\tkept".to_string()])
+        );
+        let entries = fs::read_dir(dir.join("state")).expect("read dir").count();
+        assert_eq!(entries, 1);
+        assert_eq!(read_lines(&dir.join("missing")), None);
+    }
+
+    #[test]
+    fn splits_records_into_fields() {
+        assert_eq!(fields::<3>("a\t1\tb\tc"), Some(["a", "1", "b\tc"]));
+        assert_eq!(fields::<2>("a"), None);
+        assert_eq!(fields::<1>("a\tb"), Some(["a\tb"]));
+    }
+}
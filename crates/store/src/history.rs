@@ -0,0 +1,122 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dirs::state_dir;
+use crate::file::{fields, read_lines, record};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    pub page: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn open_default() -> Option<Self> {
+        state_dir().map(|dir| Self::new(dir.join("history")))
+    }
+
+    pub fn record(&self, page: &str) -> io::Result<()> {
+        self.record_at(now(), page)
+    }
+
+    pub fn record_at(&self, timestamp: u64, page: &str) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", record(&[&timestamp.to_string(), page]))
+    }
+
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        read_lines(&self.path)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|line| {
+                let [timestamp, page] = fields(line)?;
+                Some(HistoryEntry {
+                    timestamp: timestamp.parse().ok()?,
+                    page: page.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn appends_and_reads_entries() {
+        let dir = TempDir::new("history").expect("temp dir");
+        let history = History::new(dir.join("history"));
+        assert!(history.entries().is_empty());
+        history.record_at(10, "ls(1)").expect("record");
+        history.record_at(20, "./a b.1").expect("record");
+        assert_eq!(
+            history.entries(),
+            vec![
+                HistoryEntry {
+                    timestamp: 10,
+                    page: "ls(1)".to_string()
+                },
+                HistoryEntry {
+                    timestamp: 20,
+                    page: "./a b.1".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_timestamps_in_utc() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1_709_210_096), "2024-02-29 12:34");
+    }
+}
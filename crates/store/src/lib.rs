@@ -0,0 +1,20 @@
+mod bookmarks;
+mod dirs;
+mod file;
+mod history;
+mod pins;
+mod positions;
+mod searches;
+mod sessions;
+mod snapshots;
+mod temp;
+
+pub use bookmarks::{Bookmark, Bookmarks};
+pub use dirs::{cache_dir, config_dir, state_dir};
+pub use history::{History, HistoryEntry, format_timestamp};
+pub use pins::PinnedPages;
+pub use positions::PositionStore;
+pub use searches::SearchHistory;
+pub use sessions::{Session, SessionStore};
+pub use snapshots::SnapshotStore;
+pub use temp::{TempDir, create_temp_file};
@@ -0,0 +1,49 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::dirs::state_dir;
+use crate::file::{read_lines, write_lines};
+
+#[derive(Debug, Clone)]
+pub struct PinnedPages {
+    path: PathBuf,
+}
+
+impl PinnedPages {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn open_default() -> Option<Self> {
+        state_dir().map(|dir| Self::new(dir.join("pinned")))
+    }
+
+    pub fn pages(&self) -> Vec<String> {
+        let mut pages = read_lines(&self.path).unwrap_or_default();
+        pages.retain(|line| !line.is_empty());
+        pages
+    }
+
+    pub fn save(&self, pages: &[String]) -> io::Result<()> {
+        write_lines(&self.path, pages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn round_trips_pinned_pages() {
+        let dir = TempDir::new("pinned").expect("temp dir");
+        let path = dir.join("pinned");
+        let pins = PinnedPages::new(&path);
+        assert!(pins.pages().is_empty());
+        let pages = vec!["signal(7)".to_string(), "errno(3)".to_string()];
+        pins.save(&pages).expect("save");
+        assert_eq!(pins.pages(), pages);
+        pins.save(&[]).expect("save");
+        assert!(pins.pages().is_empty());
+    }
+}
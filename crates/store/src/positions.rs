@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+
+use crate::dirs::state_dir;
+use crate::file::{fields, read_lines, record, write_lines};
+
+#[derive(Debug, Clone)]
+pub struct PositionStore {
+    path: PathBuf,
+    positions: BTreeMap<String, usize>,
+}
+
+impl PositionStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let positions = read_lines(&path)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|line| {
+                let [line, key] = fields(line)?;
+                Some((key.to_string(), line.parse().ok()?))
+            })
+            .collect();
+        Self { path, positions }
+    }
+
+    pub fn open_default() -> Option<Self> {
+        state_dir().map(|dir| Self::new(dir.join("positions")))
+    }
+
+    pub fn get(&self, key: &str) -> Option<usize> {
+        self.positions.get(key).copied()
+    }
+
+    pub fn set(&mut self, key: &str, line: usize) {
+        if line == 0 {
+            self.positions.remove(key);
+        } else {
+            self.positions.insert(key.to_string(), line);
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        write_lines(
+            &self.path,
+            self.positions
+                .iter()
+                .map(|(key, line)| record(&[&line.to_string(), key])),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn persists_positions() {
+        let dir = TempDir::new("positions").expect("temp dir");
+        let mut store = PositionStore::new(dir.join("positions"));
+        store.set("bash(1)", 120);
+        store.set("ls(1)", 4);
+        store.set("ls(1)", 0);
+        store.save().expect("save");
+
+        let store = PositionStore::new(dir.join("positions"));
+        assert_eq!(store.get("bash(1)"), Some(120));
+        assert_eq!(store.get("ls(1)"), None);
+    }
+}
@@ -0,0 +1,62 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::dirs::state_dir;
+use crate::file::{read_lines, write_lines};
+
+const MAX_QUERIES: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct SearchHistory {
+    path: PathBuf,
+}
+
+impl SearchHistory {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn open_default() -> Option<Self> {
+        state_dir().map(|dir| Self::new(dir.join("searches")))
+    }
+
+    pub fn queries(&self) -> Vec<String> {
+        let mut queries = read_lines(&self.path).unwrap_or_default();
+        queries.retain(|line| !line.is_empty());
+        queries
+    }
+
+    pub fn record(&self, query: &str) -> io::Result<()> {
+        if query.is_empty() || query.contains('\n') {
+            return Ok(());
+        }
+        let mut queries = self.queries();
+        queries.retain(|existing| existing != query);
+        queries.push(query.to_string());
+        let skip = queries.len().saturating_sub(MAX_QUERIES);
+        write_lines(&self.path, &queries[skip..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn records_recent_queries_without_duplicates() {
+        let dir = TempDir::new("searches").expect("temp dir");
+        let history = SearchHistory::new(dir.join("searches"));
+        assert!(history.queries().is_empty());
+        for query in ["EINTR", "errno", "EINTR", ""] {
+            history.record(query).expect("record");
+        }
+        assert_eq!(history.queries(), vec!["errno", "EINTR"]);
+        for index in 0..MAX_QUERIES {
+            history.record(&format!("q{index}")).expect("record");
+        }
+        let queries = history.queries();
+        assert_eq!(queries.len(), MAX_QUERIES);
+        assert_eq!(queries[0], "q0");
+    }
+}
@@ -0,0 +1,86 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::dirs::state_dir;
+use crate::file::{fields, read_lines, record, write_lines};
+use crate::snapshots::sanitize_key;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Session {
+    pub pages: Vec<(String, usize)>,
+    pub active: usize,
+    pub layout: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn open_default() -> Option<Self> {
+        state_dir().map(|dir| Self::new(dir.join("sessions")))
+    }
+
+    pub fn load(&self, name: &str) -> Option<Session> {
+        let mut session = Session::default();
+        for line in read_lines(&self.path(name))? {
+            let Some((kind, value)) = line.split_once(' ') else {
+                continue;
+            };
+            match kind {
+                "active" => session.active = value.parse().unwrap_or(0),
+                "layout" => session.layout = Some(value.to_string()),
+                "page" => {
+                    if let Some([line, page]) = fields(value)
+                        && let Ok(line) = line.parse()
+                    {
+                        session.pages.push((page.to_string(), line));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(session)
+    }
+
+    pub fn save(&self, name: &str, session: &Session) -> io::Result<()> {
+        let mut lines = vec![format!("active {}", session.active)];
+        if let Some(layout) = &session.layout {
+            lines.push(format!("layout {}", record(&[layout])));
+        }
+        for (page, line) in &session.pages {
+            lines.push(format!("page {}", record(&[&line.to_string(), page])));
+        }
+        write_lines(&self.path(name), lines)
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(sanitize_key(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn round_trips_named_sessions() {
+        let dir = TempDir::new("sessions").expect("temp dir");
+        let store = SessionStore::new(dir.path());
+        assert_eq!(store.load("work"), None);
+        let session = Session {
+            pages: vec![("signal(7)".to_string(), 40), ("errno(3)".to_string(), 0)],
+            active: 1,
+            layout: Some("1:h50(0,1)".to_string()),
+        };
+        store.save("work", &session).expect("save");
+        assert_eq!(store.load("work"), Some(session));
+        assert_eq!(store.load("home"), None);
+    }
+}
@@ -0,0 +1,66 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::dirs::cache_dir;
+use crate::file::{read_lines, write_lines};
+
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn open_default() -> Option<Self> {
+        cache_dir().map(|dir| Self::new(dir.join("snapshots")))
+    }
+
+    pub fn load(&self, key: &str) -> Option<Vec<String>> {
+        read_lines(&self.path(key))
+    }
+
+    pub fn save(&self, key: &str, lines: &[String]) -> io::Result<()> {
+        write_lines(&self.path(key), lines)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.txt", sanitize_key(key)))
+    }
+}
+
+pub(crate) fn sanitize_key(key: &str) -> String {
+    key.chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '+' | '_') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TempDir;
+
+    #[test]
+    fn round_trips_snapshots() {
+        let dir = TempDir::new("snapshots").expect("temp dir");
+        let store = SnapshotStore::new(dir.path());
+        assert_eq!(store.load("ls(1)"), None);
+        let lines = vec!["NAME".to_string(), "   ls".to_string()];
+        store.save("ls(1)", &lines).expect("save");
+        assert_eq!(store.load("ls(1)"), Some(lines));
+    }
+
+    #[test]
+    fn sanitizes_keys_for_file_names() {
+        assert_eq!(sanitize_key("ls(1)"), "ls_1_");
+        assert_eq!(sanitize_key("./a/b.1.gz"), "._a_b.1.gz");
+    }
+}
@@ -0,0 +1,115 @@
+use std::fs::{self, DirBuilder, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const MAX_ATTEMPTS: usize = 100;
+
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+pub fn create_temp_file(name: &str) -> io::Result<(PathBuf, File)> {
+    create_temp_file_in(&std::env::temp_dir(), name)
+}
+
+pub(crate) fn create_temp_file_in(dir: &Path, name: &str) -> io::Result<(PathBuf, File)> {
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    create_unique(dir, name, |path| options.open(path))
+}
+
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new(name: &str) -> io::Result<Self> {
+        let mut builder = DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        let (path, ()) = create_unique(&std::env::temp_dir(), name, |path| builder.create(path))?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn create_unique<T>(
+    dir: &Path,
+    name: &str,
+    mut create: impl FnMut(&Path) -> io::Result<T>,
+) -> io::Result<(PathBuf, T)> {
+    for _ in 0..MAX_ATTEMPTS {
+        let path = dir.join(format!(
+            "manifold-{}-{}-{name}",
+            std::process::id(),
+            NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+        ));
+        match create(&path) {
+            Ok(created) => return Ok((path, created)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "no free temporary file name",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_distinct_private_files() {
+        let taken = std::env::temp_dir().join(format!(
+            "manifold-{}-{}-page.txt",
+            std::process::id(),
+            NEXT_FILE.load(Ordering::Relaxed)
+        ));
+        std::fs::write(&taken, "keep").unwrap();
+        let (first, _) = create_temp_file("page.txt").unwrap();
+        assert_ne!(first, taken);
+        assert_eq!(std::fs::read_to_string(&taken).unwrap(), "keep");
+        let _ = std::fs::remove_file(&taken);
+        let (second, _) = create_temp_file("page.txt").unwrap();
+        assert_ne!(first, second);
+        assert!(first.to_string_lossy().ends_with("-page.txt"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&first).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let _ = std::fs::remove_file(first);
+        let _ = std::fs::remove_file(second);
+    }
+
+    #[test]
+    fn removes_temp_dirs_when_dropped() {
+        let dir = TempDir::new("dir").unwrap();
+        let other = TempDir::new("dir").unwrap();
+        assert_ne!(dir.path(), other.path());
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("nested/file"), "x").unwrap();
+        let path = dir.path().to_path_buf();
+        drop(dir);
+        assert!(!path.exists());
+        assert!(other.path().is_dir());
+    }
+}
@@ -1,11 +1,26 @@
-use app::{App, Mode};
+use app::{
+    App, CodeToken, DiffKind, Mode, PaneArea, PaneLayout, PickerEntry, SearchMatch, SplitDirection,
+    highlight_code, option_definition, picker_offset,
+};
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::{Modifier, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
 
-pub fn draw(frame: &mut Frame, app: &App) {
+const PINNED_LABEL_WIDTH: usize = 8;
+
+#[cfg(any(test, feature = "snapshot"))]
+mod snapshot;
+mod theme;
+
+#[cfg(any(test, feature = "snapshot"))]
+pub use snapshot::{buffer_text, render_buffer, snapshot};
+pub use theme::Theme;
+
+pub fn draw(frame: &mut Frame, app: &App, theme: &Theme) {
     let area = frame.area();
 
     if matches!(app.mode(), Mode::Help) {
@@ -13,37 +28,166 @@ pub fn draw(frame: &mut Frame, app: &App) {
         return;
     }
 
-    let chunks = layout(area);
+    let chunks = layout(area, app.zen());
 
-    let tab_line = format_tabs(app);
-    frame.render_widget(Paragraph::new(tab_line), chunks[0]);
+    if !app.zen() {
+        frame.render_widget(Paragraph::new(format_tabs(app, theme)), chunks[0]);
+    }
 
-    if app.has_tabs() {
-        let text: Vec<Line> = build_lines(app);
-        let paragraph = Paragraph::new(text).scroll((app.scroll() as u16, 0));
-        frame.render_widget(paragraph, chunks[1]);
+    if let Some(layout) = app.layout() {
+        draw_layout(frame, app, theme, layout, chunks[1]);
+    } else if app.has_tabs() {
+        draw_active_page(frame, app, theme, chunks[1]);
     } else {
         draw_intro(frame, chunks[1]);
     }
 
-    let viewport_height = content_height(area.height);
+    let viewport_height = content_height(area.height, app.zen());
     let status = match app.mode() {
         Mode::Normal => status_line(app, viewport_height),
         Mode::Help => String::new(),
         Mode::Command { line } => format!(":{line}"),
-        Mode::Search { line, .. } => format!("/{line}"),
+        Mode::Search { line, .. } => search_prompt(app, line),
+        Mode::Picker { title, .. } => title.clone(),
+        Mode::Visual { .. } => visual_status(app),
+        Mode::Confirm { topic, .. } => {
+            format!("No manual entry for {topic}. Run `{topic} --help`? (y/n)")
+        }
+        Mode::Pending { key } => key.to_string(),
+        Mode::Count { count } => count.to_string(),
+        Mode::Matches { selected } => {
+            let count = app
+                .tabs()
+                .get(app.active_index())
+                .map_or(0, |page| page.search_matches().len());
+            format!("Match {} of {count}", selected + 1)
+        }
+        Mode::Hints { typed, .. } => format!("-- HINTS -- {typed}"),
+        Mode::Cursor { row, column } => format!(
+            "-- CURSOR --  line {} column {}  (Enter open, K look up, y yank)",
+            row + 1,
+            column + 1
+        ),
     };
-    frame.render_widget(Paragraph::new(status), chunks[2]);
+    let status_area = if app.zen() {
+        Rect {
+            y: area.bottom().saturating_sub(1),
+            height: area.height.min(1),
+            ..area
+        }
+    } else {
+        chunks[2]
+    };
+    if !app.zen() || *app.mode() != Mode::Normal || app.status_message().is_some() {
+        frame.render_widget(Clear, status_area);
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    if app.debug_overlay() {
+        draw_debug(frame, app, theme, chunks[1]);
+    }
 
     match app.mode() {
-        Mode::Command { line } => set_prompt_cursor(frame, chunks[2], line),
-        Mode::Search { line, .. } => set_prompt_cursor(frame, chunks[2], line),
-        Mode::Normal | Mode::Help => {}
+        Mode::Command { line } => {
+            let before: String = line.chars().take(app.prompt_cursor()).collect();
+            set_prompt_cursor(frame, status_area, &format!(":{before}"));
+        }
+        Mode::Search { line, .. } => {
+            let before: String = line.chars().take(app.prompt_cursor()).collect();
+            set_prompt_cursor(frame, status_area, &search_prompt(app, &before));
+        }
+        Mode::Picker {
+            entries,
+            selected,
+            offset,
+            ..
+        } => match app.picker_preview() {
+            Some((title, lines)) => {
+                let [list, preview] = split_preview(chunks[1]);
+                draw_picker(frame, theme, list, entries, (*selected, *offset));
+                draw_preview(frame, theme, preview, title, lines);
+            }
+            None => draw_picker(frame, theme, chunks[1], entries, (*selected, *offset)),
+        },
+        Mode::Matches { selected } => draw_match_list(frame, app, theme, chunks[1], *selected),
+        Mode::Normal
+        | Mode::Help
+        | Mode::Visual { .. }
+        | Mode::Confirm { .. }
+        | Mode::Pending { .. }
+        | Mode::Count { .. }
+        | Mode::Cursor { .. }
+        | Mode::Hints { .. } => {}
+    }
+}
+
+fn draw_active_page(frame: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let text: Vec<Line> = build_lines(app, theme);
+    let (first, _) = app.window();
+    let paragraph = Paragraph::new(text).scroll((
+        app.scroll().saturating_sub(first) as u16,
+        app.column() as u16,
+    ));
+    if app.changed_lines().is_empty() {
+        frame.render_widget(paragraph, area);
+    } else {
+        let [gutter, body] = split_gutter(area);
+        frame.render_widget(
+            Paragraph::new(change_gutter(app, theme, gutter.height)),
+            gutter,
+        );
+        frame.render_widget(paragraph, body);
+    }
+}
+
+fn draw_layout(frame: &mut Frame, app: &App, theme: &Theme, layout: &PaneLayout, area: Rect) {
+    let offset = |pane: PaneArea| Rect {
+        x: area.x + pane.x,
+        y: area.y + pane.y,
+        width: pane.width,
+        height: pane.height,
+    };
+    for (direction, separator) in layout.separators(area.width, area.height) {
+        let rect = offset(separator);
+        let lines = match direction {
+            SplitDirection::Horizontal => vec![Line::from(theme.rule(true)); rect.height as usize],
+            SplitDirection::Vertical => {
+                vec![Line::from(theme.rule(false).repeat(rect.width as usize))]
+            }
+        };
+        frame.render_widget(Paragraph::new(lines), rect);
+    }
+    let tabs = layout.tabs();
+    for (index, pane) in layout
+        .areas(area.width, area.height)
+        .into_iter()
+        .enumerate()
+    {
+        let rect = offset(pane);
+        if index == layout.focus() {
+            draw_active_page(frame, app, theme, rect);
+        } else if let Some(page) = app.tabs().get(tabs[index]) {
+            let (first, lines) = page.window();
+            let text: Vec<Line> = lines
+                .iter()
+                .map(|line| Line::from(line.to_string()))
+                .collect();
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(theme.faint_style())
+                    .scroll((page.scroll.saturating_sub(first) as u16, page.column as u16)),
+                rect,
+            );
+        }
     }
 }
 
-pub fn content_height(height: u16) -> usize {
-    height.saturating_sub(2) as usize
+pub fn chrome_height(zen: bool) -> u16 {
+    if zen { 0 } else { 2 }
+}
+
+pub fn content_height(height: u16, zen: bool) -> usize {
+    height.saturating_sub(chrome_height(zen)) as usize
 }
 
 fn status_line(app: &App, viewport_height: usize) -> String {
@@ -54,15 +198,29 @@ fn status_line(app: &App, viewport_height: usize) -> String {
         return String::new();
     }
     let line = app.scroll() + 1;
-    let title = app.title();
-    let total_lines = app.lines().len();
+    let title = if app.showing_source() {
+        format!("{} [source]", app.title())
+    } else if app.loading() {
+        format!("{} [loading]", app.title())
+    } else {
+        app.title()
+    };
+    let total_lines = app.line_count();
     let percent = percent_label(app.scroll(), total_lines, viewport_height);
     match percent {
-        Some(label) => format!("{title}  line {line}  {label}"),
-        None => format!("{title}  line {line}"),
+        Some(label) => format!("{title}  line {line}/{total_lines}  {label}"),
+        None => format!("{title}  line {line}/{total_lines}"),
     }
 }
 
+fn visual_status(app: &App) -> String {
+    let count = app
+        .selection()
+        .map(|(start, end)| end - start + 1)
+        .unwrap_or(0);
+    format!("-- VISUAL -- {count} lines  y yank  o open reference  K look up")
+}
+
 fn percent_label(scroll: usize, total_lines: usize, viewport_height: usize) -> Option<String> {
     if total_lines == 0 {
         return None;
@@ -78,29 +236,47 @@ fn percent_label(scroll: usize, total_lines: usize, viewport_height: usize) -> O
     Some(format!("{percent}%"))
 }
 
-fn layout(area: Rect) -> [Rect; 3] {
+fn layout(area: Rect, zen: bool) -> [Rect; 3] {
+    let bar = u16::from(!zen);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(1),
+            Constraint::Length(bar),
             Constraint::Min(1),
-            Constraint::Length(1),
+            Constraint::Length(bar),
         ])
         .split(area);
     [chunks[0], chunks[1], chunks[2]]
 }
 
-fn format_tabs(app: &App) -> Line<'static> {
+fn format_tabs(app: &App, theme: &Theme) -> Line<'static> {
     let mut spans: Vec<Span<'static>> = Vec::with_capacity(app.tabs().len());
-    let active_style = Style::default().add_modifier(Modifier::REVERSED);
+    let active_style = theme.highlight_style();
+    if app.group_count() > 1 {
+        spans.push(Span::styled(
+            format!("[{}]", app.group_name()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+    }
     for (index, page) in app.tabs().iter().enumerate() {
-        let label = match page.section() {
-            Some(section) => format!("{}({})", page.name(), section),
-            None => page.name().to_string(),
+        let label = match (&page.label, page.section()) {
+            (Some(label), _) => label.clone(),
+            _ if page.pinned => page.name().chars().take(PINNED_LABEL_WIDTH).collect(),
+            (None, Some(section)) => format!("{}({})", page.name(), section),
+            (None, None) => page.name().to_string(),
+        };
+        let text = if page.pinned {
+            format!(" {}^{} ", index + 1, label)
+        } else {
+            format!(" {}:{} ", index + 1, label)
         };
-        let text = format!(" {} ", label);
         let span = if index == app.active_index() {
             Span::styled(text, active_style)
+        } else if app
+            .layout()
+            .is_some_and(|layout| layout.tabs().contains(&index))
+        {
+            Span::styled(text, Style::default().add_modifier(Modifier::UNDERLINED))
         } else {
             Span::raw(text)
         };
@@ -109,42 +285,221 @@ fn format_tabs(app: &App) -> Line<'static> {
     Line::from(spans)
 }
 
-fn build_lines(app: &App) -> Vec<Line<'static>> {
-    let Some(query) = app.search_query() else {
-        return app
-            .lines()
-            .iter()
-            .map(|line| Line::from(line.to_string()))
-            .collect();
-    };
-    if query.is_empty() {
-        return app
-            .lines()
+fn split_gutter(area: Rect) -> [Rect; 2] {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(area);
+    [chunks[0], chunks[1]]
+}
+
+fn change_gutter(app: &App, theme: &Theme, height: u16) -> Vec<Line<'static>> {
+    let changed = app.changed_lines();
+    let marker = Style::default().fg(theme.changed);
+    (app.scroll()..app.scroll() + height as usize)
+        .map(|index| {
+            if changed.binary_search(&index).is_ok() {
+                Line::from(Span::styled("+", marker))
+            } else {
+                Line::from("")
+            }
+        })
+        .collect()
+}
+
+fn window_line<'a>(
+    lines: &'a mut [Line<'static>],
+    first: usize,
+    row: usize,
+) -> Option<&'a mut Line<'static>> {
+    lines.get_mut(row.checked_sub(first)?)
+}
+
+fn build_lines(app: &App, theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = build_text_lines(app, theme);
+    let (first, _) = app.window();
+    for entry in app.keyword_matches() {
+        if let Some(style) = theme.highlights.get(entry.keyword)
+            && let Some(line) = window_line(&mut lines, first, entry.line)
+        {
+            *line = patch_columns(std::mem::take(line), entry.start, entry.end, *style);
+        }
+    }
+    let folded = theme.faint_style();
+    for (row, hidden) in app.fold_markers() {
+        if let Some(line) = window_line(&mut lines, first, *row) {
+            line.push_span(Span::styled(format!("  [+{hidden} lines]"), folded));
+        }
+    }
+    for (line, kind) in lines.iter_mut().zip(app.diff_kinds().iter().skip(first)) {
+        let color = match kind {
+            DiffKind::Same => continue,
+            DiffKind::Added => theme.added,
+            DiffKind::Removed => theme.removed,
+        };
+        *line = std::mem::take(line).patch_style(Style::default().fg(color));
+    }
+    if let Some(entry) = app
+        .tabs()
+        .get(app.active_index())
+        .and_then(|page| page.current_match())
+        && let Some(line) = window_line(&mut lines, first, entry.line)
+    {
+        let current = theme.current_match_style();
+        *line = patch_columns(std::mem::take(line), entry.start, entry.end, current);
+    }
+    if let Some((start, end)) = app.selection() {
+        let selected = Style::default().bg(theme.selection);
+        for (_, line) in (first..)
+            .zip(lines.iter_mut())
+            .filter(|(row, _)| (start..=end).contains(row))
+        {
+            *line = std::mem::take(line).patch_style(selected);
+        }
+    }
+    if let Mode::Cursor { row, column } = *app.mode()
+        && let Some(line) = window_line(&mut lines, first, row)
+    {
+        let cursor = theme.highlight_style();
+        let length: usize = line
+            .spans
             .iter()
-            .map(|line| Line::from(line.to_string()))
-            .collect();
+            .map(|span| span.content.chars().count())
+            .sum();
+        if column >= length {
+            line.push_span(Span::styled(" ", cursor));
+        } else {
+            *line = patch_columns(std::mem::take(line), column, column + 1, cursor);
+        }
+    }
+    if let Mode::Hints { typed, hints } = app.mode() {
+        let label = Style::default()
+            .fg(Color::Black)
+            .bg(theme.hint)
+            .add_modifier(Modifier::BOLD);
+        for hint in hints {
+            if let Some(rest) = hint.label.strip_prefix(typed.as_str())
+                && let Some(line) = window_line(&mut lines, first, hint.row)
+            {
+                *line = overlay_text(std::mem::take(line), hint.column, rest, label);
+            }
+        }
+    }
+    if let Some(((top, start), (last, end))) = app.text_selection() {
+        let selected = Style::default().bg(theme.selection);
+        for (row, line) in (first..)
+            .zip(lines.iter_mut())
+            .filter(|(row, _)| (top..=last).contains(row))
+        {
+            let from = if row == top { start } else { 0 };
+            let to = if row == last { end + 1 } else { usize::MAX };
+            *line = patch_columns(std::mem::take(line), from, to, selected);
+        }
     }
-    let highlight = Style::default().add_modifier(Modifier::REVERSED);
-    app.lines()
+    lines
+}
+
+fn patch_columns(mut line: Line<'static>, from: usize, to: usize, style: Style) -> Line<'static> {
+    let mut spans = Vec::with_capacity(line.spans.len() + 2);
+    let mut column = 0;
+    for span in std::mem::take(&mut line.spans) {
+        let chars: Vec<char> = span.content.chars().collect();
+        let start = from.clamp(column, column + chars.len()) - column;
+        let end = to.clamp(column, column + chars.len()) - column;
+        column += chars.len();
+        if start == end {
+            spans.push(span);
+            continue;
+        }
+        let piece = |range: std::ops::Range<usize>| chars[range].iter().collect::<String>();
+        if start > 0 {
+            spans.push(Span::styled(piece(0..start), span.style));
+        }
+        spans.push(Span::styled(piece(start..end), span.style.patch(style)));
+        if end < chars.len() {
+            spans.push(Span::styled(piece(end..chars.len()), span.style));
+        }
+    }
+    line.spans = spans;
+    line
+}
+
+fn overlay_text(line: Line<'static>, column: usize, text: &str, style: Style) -> Line<'static> {
+    let mut cells: Vec<(char, Style)> = line
+        .spans
         .iter()
-        .map(|line| highlight_line(line, query, highlight))
+        .flat_map(|span| span.content.chars().map(move |ch| (ch, span.style)))
+        .collect();
+    for (offset, ch) in text.chars().enumerate() {
+        match cells.get_mut(column + offset) {
+            Some(cell) => *cell = (ch, style),
+            None => cells.push((ch, style)),
+        }
+    }
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    for (ch, style) in cells {
+        match spans.last_mut() {
+            Some(span) if span.style == style => span.content.to_mut().push(ch),
+            _ => spans.push(Span::styled(ch.to_string(), style)),
+        }
+    }
+    Line { spans, ..line }
+}
+
+fn build_text_lines(app: &App, theme: &Theme) -> Vec<Line<'static>> {
+    let matches = app
+        .tabs()
+        .get(app.active_index())
+        .map(|page| page.search_matches())
+        .unwrap_or_default();
+    let code = app.code_lines();
+    let highlight = theme.highlight_style();
+    let (first, lines) = app.window();
+    (first..)
+        .zip(lines)
+        .map(|(index, line)| {
+            let first = matches.partition_point(|entry| entry.line < index);
+            let last = matches.partition_point(|entry| entry.line <= index);
+            if first < last {
+                highlight_line(line, &matches[first..last], highlight)
+            } else if code.binary_search(&index).is_ok() {
+                code_line(line, theme)
+            } else {
+                option_line(line, theme)
+            }
+        })
         .collect()
 }
 
-fn highlight_line(line: &str, query: &str, style: Style) -> Line<'static> {
+fn option_line(line: &str, theme: &Theme) -> Line<'static> {
+    let Some(range) = option_definition(line) else {
+        return Line::from(line.to_string());
+    };
+    let style = Style::default()
+        .fg(theme.option)
+        .add_modifier(Modifier::BOLD);
+    Line::from(vec![
+        Span::raw(line[..range.start].to_string()),
+        Span::styled(line[range.clone()].to_string(), style),
+        Span::raw(line[range.end..].to_string()),
+    ])
+}
+
+fn code_line(line: &str, theme: &Theme) -> Line<'static> {
     let mut spans = Vec::new();
     let mut offset = 0;
-    while let Some(pos) = line[offset..].find(query) {
-        let start = offset + pos;
-        let end = start + query.len();
-        if start > offset {
-            spans.push(Span::raw(line[offset..start].to_string()));
+    for (token, range) in highlight_code(line) {
+        if range.start > offset {
+            spans.push(Span::raw(line[offset..range.start].to_string()));
         }
-        spans.push(Span::styled(line[start..end].to_string(), style));
-        offset = end;
-    }
-    if spans.is_empty() {
-        return Line::from(line.to_string());
+        let style = match token {
+            CodeToken::Keyword => Style::default().fg(theme.keyword),
+            CodeToken::String => Style::default().fg(theme.string),
+            CodeToken::Comment => Style::default().fg(theme.comment),
+            CodeToken::Number => Style::default().fg(theme.number),
+        };
+        offset = range.end;
+        spans.push(Span::styled(line[range].to_string(), style));
     }
     if offset < line.len() {
         spans.push(Span::raw(line[offset..].to_string()));
@@ -152,6 +507,14 @@ fn highlight_line(line: &str, query: &str, style: Style) -> Line<'static> {
     Line::from(spans)
 }
 
+fn highlight_line(line: &str, matches: &[SearchMatch], style: Style) -> Line<'static> {
+    matches
+        .iter()
+        .fold(Line::from(line.to_string()), |line, entry| {
+            patch_columns(line, entry.start, entry.end, style)
+        })
+}
+
 fn draw_intro(frame: &mut Frame, area: Rect) {
     let lines = vec![
         Line::from("Manifold"),
@@ -171,19 +534,79 @@ fn draw_help(frame: &mut Frame, area: Rect) {
         Line::from(""),
         Line::from("Commands"),
         Line::from("  :man [SECTION] TOPIC   Open a man page"),
+        Line::from("  :man -a TOPIC          Open every section of TOPIC in its own tab"),
+        Line::from("  :man ./PATH            Open a local page file (.gz/.bz2/.xz/.zst)"),
+        Line::from("  :man HOST:TOPIC        Open a page from another machine over ssh"),
+        Line::from("  :history               Reopen a previously visited page"),
+        Line::from("  :bookmark NAME         Bookmark the current page and position"),
+        Line::from("  :bookmarks             Jump to a saved bookmark"),
+        Line::from("  :mksession NAME        Save the tabs and layout as a named session"),
+        Line::from("  :session NAME          Reopen a saved session"),
+        Line::from("  :!COMMAND              Show the output of a shell command in a tab"),
+        Line::from("  :cheat TOPIC           Open the cheat.sh sheet for TOPIC"),
+        Line::from("  :apropos KEYWORD       Search page descriptions with a preview"),
+        Line::from("  :find QUERY            Fuzzy-find installed pages by name"),
+        Line::from("  :split [TOPIC]         Split the pane side by side"),
+        Line::from("  :hsplit [TOPIC]        Split the pane top and bottom"),
+        Line::from("  :close, :only          Close this pane / all other panes"),
+        Line::from("  :scrollbind, :scb      Scroll split panes together"),
         Line::from("  :help, :h              Show this help"),
         Line::from("  :wipe, :w              Close current tab"),
+        Line::from("  :wipe all              Close every tab that is not pinned"),
+        Line::from("  :pin, :unpin           Keep the tab at the front and reopen it on start"),
+        Line::from("  :rename [TITLE]        Label the tab (no title restores the topic)"),
+        Line::from("  :width [N|auto]        Render this tab N columns wide, or follow the window"),
+        Line::from("  :goto SECTION|FLAG     Jump to a section (prefix) or option"),
+        Line::from("  :option, :opt FLAG     Jump to the definition of a flag"),
+        Line::from("  :diff TOPIC[(SECTION)] Compare the page with another page"),
+        Line::from("  :export PATH           Save page as .txt, .md, .html, .pdf or .ps"),
+        Line::from("  :edit, :pager [source] Open page text or source in $EDITOR/$PAGER"),
+        Line::from("  :tab N, :tabn, :tabp   Go to tab N, the next or previous tab"),
+        Line::from("  :group [NAME]          Switch to (or create) a tab group, or list them"),
+        Line::from("  :movetab NAME          Move the tab to another group"),
+        Line::from("  :tab-to-split          Move the tab into or out of a split"),
+        Line::from("  :reload-config         Re-read the config file"),
+        Line::from("  :theme dark|light|auto Pick the palette, or follow the terminal"),
+        Line::from("  :refresh               Re-render the page, e.g. after an update"),
+        Line::from("  :debug                 Toggle render and timing statistics"),
+        Line::from("  Z, :zen                Hide the tab bar and status line"),
+        Line::from("  S, :source             Switch between the page and its roff source"),
         Line::from("  :quit, :q              Quit Manifold"),
         Line::from(""),
         Line::from("Keys"),
         Line::from("  j/k, Up/Down           Scroll line"),
-        Line::from("  f/b, PageDown/PageUp   Forward/back a page"),
+        Line::from("  Left/Right             Scroll wide tables sideways"),
+        Line::from("  Ctrl-f/b, PageDown/Up  Forward/back a page (b also goes back)"),
+        Line::from("  f                      Label visible references; type a label to open it"),
         Line::from("  d/u                    Half page down/up"),
         Line::from("  g/G                    Top/bottom"),
+        Line::from("  42G, :42               Go to line 42"),
         Line::from("  H/L                    Previous/next tab"),
-        Line::from("  /                      Search"),
-        Line::from("  n/p                    Next/previous match"),
+        Line::from("  Ctrl-w                 Focus the next pane"),
+        Line::from("  Ctrl-n/Ctrl-p          Next/previous tab group"),
+        Line::from("  </>, {/}               Resize pane width/height"),
+        Line::from("  /                      Search (words must all appear, \"quote\" phrases)"),
+        Line::from("                         Lowercase queries ignore case"),
+        Line::from("  Ctrl-s (in search)     Limit the search to the current section"),
+        Line::from("  Up/Down (in search)    Recall earlier searches"),
+        Line::from("  Ctrl-a/e, Left/Right   Move the prompt cursor"),
+        Line::from("  Ctrl-w/u/k             Delete word, to start, to end of the prompt"),
+        Line::from("  Tab (in :man)          Complete a page name"),
+        Line::from("  n, p/N                 Next/previous match"),
+        Line::from("  &                      Search again for the last query, even after Esc"),
+        Line::from("  .                      Repeat the last : command"),
+        Line::from("  M, :matches            List all matches (j/k to jump, Enter to close)"),
+        Line::from("  ]]/[[                  Next/previous section"),
+        Line::from("  ]o/[o                  Next/previous option definition"),
+        Line::from("  za, zM/zR              Toggle fold of this section, fold/open all"),
         Line::from("  -/+                    Narrow/widen text column"),
+        Line::from("  y                      Copy top line to clipboard"),
+        Line::from("  c                      Cursor mode: h/j/k/l, w/b, 0/$ move; Enter opens"),
+        Line::from("                         the reference, K looks up and y yanks the word"),
+        Line::from("  V                      Visual line selection (y yank, o open, K look up)"),
+        Line::from("  K                      Look up the selected word as a man page"),
+        Line::from("  r                      Retry a page that failed to render"),
+        Line::from("  R                      Re-render the page"),
         Line::from("  ?                      Show help"),
         Line::from("  q                      Quit help"),
     ];
@@ -191,6 +614,188 @@ fn draw_help(frame: &mut Frame, area: Rect) {
     frame.render_widget(paragraph, area);
 }
 
+fn draw_picker(
+    frame: &mut Frame,
+    theme: &Theme,
+    area: Rect,
+    entries: &[PickerEntry],
+    (selected, offset): (usize, usize),
+) {
+    let labels: Vec<String> = entries
+        .iter()
+        .map(|entry| match &entry.section {
+            Some(section) => format!(" {}({})  {} ", entry.name, section, entry.description),
+            None => format!(" {}  {} ", entry.name, entry.description),
+        })
+        .collect();
+    let selected_style = theme.highlight_style();
+    let lines: Vec<Line> = labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            if index == selected {
+                Line::from(Span::styled(label.clone(), selected_style))
+            } else {
+                Line::from(label.clone())
+            }
+        })
+        .collect();
+    let content_width = labels.iter().map(|label| label.width()).max().unwrap_or(0) as u16;
+    let rect = popup_rect(
+        area,
+        content_width.saturating_add(2),
+        (lines.len() as u16).saturating_add(2),
+    );
+    let visible = usize::from(rect.height.saturating_sub(2));
+    let offset = picker_offset(offset, selected, visible) as u16;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.border_set());
+    frame.render_widget(Clear, rect);
+    frame.render_widget(Paragraph::new(lines).block(block).scroll((offset, 0)), rect);
+}
+
+fn split_preview(area: Rect) -> [Rect; 2] {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    [chunks[0], chunks[1]]
+}
+
+fn draw_preview(frame: &mut Frame, theme: &Theme, area: Rect, title: &str, lines: &[String]) {
+    let text: Vec<Line> = lines.iter().map(|line| Line::from(line.clone())).collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.border_set())
+        .title(title);
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(text).block(block), area);
+}
+
+fn match_list_lines(app: &App, entries: Range<usize>) -> Vec<String> {
+    let Some(page) = app.tabs().get(app.active_index()) else {
+        return Vec::new();
+    };
+    let matches = page.search_matches();
+    let entries = &matches[entries.start.min(matches.len())..entries.end.min(matches.len())];
+    let (Some(top), Some(bottom)) = (entries.first(), entries.last()) else {
+        return Vec::new();
+    };
+    let lines = page.line_range(top.line..bottom.line + 1);
+    entries
+        .iter()
+        .map(|entry| {
+            let text = lines
+                .get(entry.line - top.line)
+                .map_or("", |line| line.trim());
+            format!("{:>5}  {text}", entry.line + 1)
+        })
+        .collect()
+}
+
+fn draw_match_list(frame: &mut Frame, app: &App, theme: &Theme, area: Rect, selected: usize) {
+    let count = app
+        .tabs()
+        .get(app.active_index())
+        .map_or(0, |page| page.search_matches().len());
+    let max_rows = (area.height / 3).max(3);
+    let rows = (count as u16).min(max_rows);
+    let height = (rows + 2).min(area.height);
+    let rect = Rect {
+        x: area.x,
+        y: area.y + area.height - height,
+        width: area.width,
+        height,
+    };
+    let selected_style = theme.highlight_style();
+    let visible = usize::from(rect.height.saturating_sub(2)).max(1);
+    let offset = selected.saturating_sub(visible - 1);
+    let lines: Vec<Line> = (offset..)
+        .zip(match_list_lines(app, offset..offset + visible))
+        .map(|(index, label)| {
+            if index == selected {
+                Line::from(Span::styled(label, selected_style))
+            } else {
+                Line::from(label)
+            }
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.border_set())
+        .title("matches");
+    frame.render_widget(Clear, rect);
+    frame.render_widget(Paragraph::new(lines).block(block), rect);
+}
+
+fn draw_debug(frame: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let lines = debug_lines(app);
+    let width = lines.iter().map(|line| line.width()).max().unwrap_or(0) as u16 + 2;
+    let width = width.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let rect = Rect {
+        x: area.x + area.width - width,
+        y: area.y,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.border_set())
+        .title("debug");
+    frame.render_widget(Clear, rect);
+    frame.render_widget(
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>()).block(block),
+        rect,
+    );
+}
+
+fn debug_lines(app: &App) -> Vec<String> {
+    let millis = |duration: Option<std::time::Duration>| match duration {
+        Some(duration) => format!("{:.1} ms", duration.as_secs_f64() * 1000.0),
+        None => "-".to_string(),
+    };
+    let (renders, hits) = app.tabs().iter().fold((0, 0), |(renders, hits), page| {
+        let stats = page.render_stats();
+        (renders + stats.renders, hits + stats.cache_hits)
+    });
+    let last_render = app
+        .tabs()
+        .get(app.active_index())
+        .and_then(|page| page.render_stats().last_render);
+    let mut lines = vec![
+        format!("frame   {}", millis(app.frame_time())),
+        format!("render  {}", millis(last_render)),
+        format!("cache   {renders} misses, {hits} hits"),
+        format!("memory  {} KiB", app.memory_usage().div_ceil(1024)),
+    ];
+    for (index, page) in app.tabs().iter().enumerate() {
+        let marker = if index == app.active_index() {
+            '*'
+        } else {
+            ' '
+        };
+        lines.push(format!(
+            "{marker} {:<12} {:>6} lines",
+            page.name(),
+            page.line_count()
+        ));
+    }
+    lines
+}
+
+fn popup_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
 fn centered_rect(area: Rect, height: u16) -> Rect {
     let y = area.y + area.height.saturating_sub(height) / 2;
     Rect {
@@ -201,8 +806,15 @@ fn centered_rect(area: Rect, height: u16) -> Rect {
     }
 }
 
-fn set_prompt_cursor(frame: &mut Frame, area: Rect, line: &str) {
-    let mut cursor_x = area.x + 1 + line.len() as u16;
+fn search_prompt(app: &App, line: &str) -> String {
+    match app.search_scope() {
+        Some(scope) => format!("/[{scope}] {line}"),
+        None => format!("/{line}"),
+    }
+}
+
+fn set_prompt_cursor(frame: &mut Frame, area: Rect, prompt: &str) {
+    let mut cursor_x = area.x + prompt.width() as u16;
     let max_x = area.x + area.width.saturating_sub(1);
     if cursor_x > max_x {
         cursor_x = max_x;
@@ -213,8 +825,9 @@ fn set_prompt_cursor(frame: &mut Frame, area: Rect, line: &str) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use app::App;
-    use render::{ManRenderer, RenderError};
+    use app::{Action, App};
+    use ratatui::style::Color;
+    use render::{ManRenderer, PreformattedRenderer, RenderError};
 
     struct TestRenderer {
         lines: Vec<String>,
@@ -244,9 +857,15 @@ mod tests {
     fn status_line_shows_top_and_bottom_labels() {
         let viewport_height = 10;
         let mut app = make_app(100, viewport_height);
-        assert_eq!(status_line(&app, viewport_height), "example  line 1  Top");
+        assert_eq!(
+            status_line(&app, viewport_height),
+            "example  line 1/100  Top"
+        );
         app.go_bottom(viewport_height);
-        assert_eq!(status_line(&app, viewport_height), "example  line 91  Bot");
+        assert_eq!(
+            status_line(&app, viewport_height),
+            "example  line 91/100  Bot"
+        );
     }
 
     #[test]
@@ -254,6 +873,246 @@ mod tests {
         let viewport_height = 10;
         let mut app = make_app(100, viewport_height);
         app.scroll_down(49, viewport_height);
-        assert_eq!(status_line(&app, viewport_height), "example  line 50  50%");
+        assert_eq!(
+            status_line(&app, viewport_height),
+            "example  line 50/100  50%"
+        );
+    }
+
+    #[test]
+    fn status_line_reports_fixture_page() {
+        let renderer = PreformattedRenderer::new();
+        let viewport_height = 10;
+        let mut app = App::new("ascii", Some("7".to_string()));
+        app.resize_active(&renderer, 80, viewport_height)
+            .expect("render");
+        app.go_bottom(viewport_height);
+        assert_eq!(
+            status_line(&app, viewport_height),
+            format!(
+                "ascii(7)  line {}/{}  Bot",
+                app.line_count() - 9,
+                app.line_count()
+            )
+        );
+    }
+
+    #[test]
+    fn patches_styles_across_span_boundaries() {
+        let line = Line::from(vec![Span::raw("ab"), Span::raw("cdef")]);
+        let style = Style::default().add_modifier(Modifier::REVERSED);
+        let patched = patch_columns(line, 1, 3, style);
+        let spans: Vec<(&str, bool)> = patched
+            .spans
+            .iter()
+            .map(|span| {
+                (
+                    span.content.as_ref(),
+                    span.style.add_modifier.contains(Modifier::REVERSED),
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![("a", false), ("b", true), ("c", true), ("def", false)]
+        );
+    }
+
+    #[test]
+    fn highlights_code_in_examples() {
+        let lines = ["EXAMPLES", "       Run:", "           echo \"hi\" # greet"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        let renderer = TestRenderer { lines };
+        let mut app = App::new("example", None);
+        app.resize_active(&renderer, 80, 10).expect("render");
+        let theme = Theme::default();
+        let text = build_text_lines(&app, &theme);
+        assert_eq!(text[1].spans.len(), 1);
+        let spans: Vec<&str> = text[2]
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(spans, vec!["           echo ", "\"hi\"", " ", "# greet"]);
+        assert_eq!(text[2].spans[1].style.fg, Some(Color::Green));
+
+        app.set_code_highlighting(false);
+        assert_eq!(build_text_lines(&app, &theme)[2].spans.len(), 1);
+    }
+
+    #[test]
+    fn styles_configured_keywords_on_every_page() {
+        let lines = vec!["       EINTR  Interrupted call".to_string()];
+        let renderer = TestRenderer { lines };
+        let mut app = App::new("errno", None);
+        app.resize_active(&renderer, 80, 10).expect("render");
+        let mut theme = Theme::default();
+        theme.add_highlight("red bold").expect("style");
+        assert_eq!(build_lines(&app, &theme)[0].spans.len(), 1);
+        app.set_keywords(vec!["eintr".to_string()]);
+        let line = &build_lines(&app, &theme)[0];
+        let spans: Vec<&str> = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(spans, vec!["       ", "EINTR", "  Interrupted call"]);
+        assert_eq!(line.spans[1].style.fg, Some(Color::Red));
+    }
+
+    #[test]
+    fn lists_render_statistics_per_tab() {
+        let mut app = make_app(30, 10);
+        app.resize_active(&TestRenderer { lines: Vec::new() }, 80, 10)
+            .expect("cached");
+        let lines = debug_lines(&app);
+        assert_eq!(lines[0], "frame   -");
+        assert!(lines[1].ends_with(" ms"), "{}", lines[1]);
+        assert_eq!(lines[2], "cache   1 misses, 1 hits");
+        assert!(lines[3].starts_with("memory  "), "{}", lines[3]);
+        assert_eq!(lines[4], "* example          30 lines");
+    }
+
+    #[test]
+    fn places_prompt_cursor_by_display_width() {
+        let renderer = TestRenderer {
+            lines: vec!["émulateur 終端".to_string()],
+        };
+        let mut app = App::new("example", None);
+        let mut send = |action| app.update(action, &renderer, 40, 8).expect("update");
+        send(Action::EnterSearchMode);
+        for ch in "émulateur 終端".chars() {
+            send(Action::SearchChar(ch));
+        }
+        send(Action::EditLine(app::LineEdit::Left));
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(40, 10)).expect("terminal");
+        terminal
+            .draw(|frame| draw(frame, &app, &Theme::default()))
+            .expect("draw");
+        assert_eq!(app.search_query(), Some("émulateur 終端"));
+        assert_eq!(terminal.get_cursor_position().expect("cursor").x, 13);
+    }
+
+    #[test]
+    fn picker_keeps_the_selection_in_view() {
+        struct AproposRenderer;
+
+        impl ManRenderer for AproposRenderer {
+            fn render(
+                &self,
+                name: &str,
+                _section: Option<&str>,
+                _width: u16,
+            ) -> Result<Vec<String>, RenderError> {
+                Err(RenderError::CommandFailed(format!("no {name}")))
+            }
+
+            fn apropos(&self, _keyword: &str) -> Result<Vec<render::PageEntry>, RenderError> {
+                Ok((0..8)
+                    .map(|idx| render::PageEntry {
+                        name: format!("p{idx}"),
+                        section: "1".to_string(),
+                        description: "終端".to_string(),
+                    })
+                    .collect())
+            }
+        }
+
+        let renderer = AproposRenderer;
+        let mut app = App::empty();
+        let send = |app: &mut App, action| {
+            app.update(action, &renderer, 40, 6).expect("update");
+        };
+        send(&mut app, Action::EnterCommandMode);
+        for ch in "apropos p".chars() {
+            send(&mut app, Action::CommandChar(ch));
+        }
+        send(&mut app, Action::CommandSubmit);
+        for _ in 0..6 {
+            send(&mut app, Action::PickerDown);
+        }
+        let rows = |app: &App| {
+            snapshot(app, &Theme::default(), 40, 8)
+                .lines()
+                .filter_map(|line| line.trim().trim_matches('│').split_whitespace().next())
+                .filter(|name| name.starts_with('p'))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rows(&app), ["p3(1)", "p4(1)", "p5(1)", "p6(1)"]);
+        send(&mut app, Action::PickerUp);
+        assert_eq!(rows(&app), ["p3(1)", "p4(1)", "p5(1)", "p6(1)"]);
+        let buffer = render_buffer(&app, &Theme::default(), 40, 8);
+        let borders: Vec<u16> = (0..20)
+            .filter(|&x| buffer[(x, 2)].symbol() == "│")
+            .collect();
+        assert_eq!(borders, [2, 16]);
+    }
+
+    #[test]
+    fn pans_pages_wider_than_a_tiny_terminal() {
+        let renderer = TestRenderer {
+            lines: vec!["line 0 with words".to_string(); 4],
+        };
+        let mut app = App::new("example", None);
+        let mut send = |action| app.update(action, &renderer, 15, 2).expect("update");
+        send(Action::Resize(6, 3));
+        send(Action::ScrollRight(8));
+        let text = snapshot(&app, &Theme::default(), 6, 3);
+        assert_eq!(text.lines().nth(1), Some("ith wo"));
+    }
+
+    #[test]
+    fn zen_mode_gives_every_row_to_the_page() {
+        let renderer = TestRenderer {
+            lines: (0..10).map(|idx| format!("line {idx}")).collect(),
+        };
+        let mut app = App::new("example", None);
+        app.resize_active(&renderer, 20, 4).expect("render");
+        app.update(Action::ToggleZen, &renderer, 20, 4)
+            .expect("update");
+        assert_eq!(
+            snapshot(&app, &Theme::default(), 20, 4),
+            "line 0\nline 1\nline 2\nline 3\n"
+        );
+        assert_eq!(content_height(4, app.zen()), 4);
+
+        app.update(Action::EnterCommandMode, &renderer, 20, 4)
+            .expect("update");
+        let buffer = render_buffer(&app, &Theme::default(), 20, 4);
+        assert_eq!(buffer[(0, 3)].symbol(), ":");
+    }
+
+    #[test]
+    fn styles_current_match_apart_from_others() {
+        let lines = vec!["foo bar foo".to_string()];
+        let renderer = TestRenderer { lines };
+        let mut app = App::new("example", None);
+        app.resize_active(&renderer, 80, 10).expect("render");
+        for action in [Action::EnterSearchMode, Action::SearchChar('f')]
+            .into_iter()
+            .chain("oo".chars().map(Action::SearchChar))
+            .chain([Action::SearchSubmit, Action::SearchNext])
+        {
+            app.update(action, &renderer, 80, 10).expect("update");
+        }
+        let theme = Theme::default();
+        let lines = build_lines(&app, &theme);
+        let styled: Vec<(&str, Option<Color>)> = lines[0]
+            .spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style.fg))
+            .collect();
+        assert_eq!(
+            styled,
+            vec![
+                ("foo", None),
+                (" bar ", None),
+                ("foo", Some(theme.current_match))
+            ]
+        );
     }
 }
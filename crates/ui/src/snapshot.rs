@@ -0,0 +1,69 @@
+use app::App;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+
+use crate::{Theme, draw};
+
+pub fn render_buffer(app: &App, theme: &Theme, width: u16, height: u16) -> Buffer {
+    let mut terminal =
+        Terminal::new(TestBackend::new(width, height)).expect("test backend never fails");
+    terminal
+        .draw(|frame| draw(frame, app, theme))
+        .expect("test backend never fails");
+    terminal.backend().buffer().clone()
+}
+
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let mut row = String::new();
+        let mut skip = 0;
+        for x in area.left()..area.right() {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let symbol = buffer[(x, y)].symbol();
+            skip = unicode_width::UnicodeWidthStr::width(symbol).saturating_sub(1);
+            row.push_str(symbol);
+        }
+        text.push_str(row.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+pub fn snapshot(app: &App, theme: &Theme, width: u16, height: u16) -> String {
+    buffer_text(&render_buffer(app, theme, width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use app::Action;
+    use render::PreformattedRenderer;
+
+    #[test]
+    fn renders_tab_bar_page_and_status_as_text() {
+        let renderer = PreformattedRenderer::new();
+        let mut app = App::new("ascii", Some("7".to_string()));
+        app.resize_active(&renderer, 30, 4).expect("render");
+        let text = snapshot(&app, &Theme::default(), 30, 6);
+        assert_eq!(
+            text,
+            " 1:ascii(7)\n\
+             ascii(7) Miscellaneous\n\
+             Information Manual ascii(7)\n\
+             \n\
+             NAME\n\
+             ascii(7)  line 1/65  Top\n"
+        );
+
+        app.update(Action::EnterCommandMode, &renderer, 30, 4)
+            .expect("update");
+        let buffer = render_buffer(&app, &Theme::default(), 30, 6);
+        assert_eq!(buffer_text(&buffer).lines().last(), Some(":"));
+    }
+}
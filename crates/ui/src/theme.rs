@@ -0,0 +1,290 @@
+use std::str::FromStr;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::border;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub option: Color,
+    pub keyword: Color,
+    pub string: Color,
+    pub comment: Color,
+    pub number: Color,
+    pub added: Color,
+    pub removed: Color,
+    pub changed: Color,
+    pub selection: Color,
+    pub current_match: Color,
+    pub hint: Color,
+    pub highlight: Color,
+    pub highlight_text: Color,
+    pub reverse_video: bool,
+    pub high_contrast: bool,
+    pub ascii: bool,
+    pub highlights: Vec<Style>,
+}
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            option: Color::Cyan,
+            keyword: Color::Yellow,
+            string: Color::Green,
+            comment: Color::DarkGray,
+            number: Color::Cyan,
+            added: Color::Green,
+            removed: Color::Red,
+            changed: Color::Green,
+            selection: Color::DarkGray,
+            current_match: Color::Yellow,
+            hint: Color::Yellow,
+            highlight: Color::Gray,
+            highlight_text: Color::Black,
+            reverse_video: true,
+            high_contrast: false,
+            ascii: false,
+            highlights: Vec::new(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn high_contrast() -> Self {
+        Self {
+            option: Color::LightCyan,
+            keyword: Color::LightYellow,
+            string: Color::LightGreen,
+            comment: Color::White,
+            number: Color::LightCyan,
+            added: Color::LightGreen,
+            removed: Color::LightRed,
+            changed: Color::LightGreen,
+            selection: Color::Blue,
+            current_match: Color::LightYellow,
+            hint: Color::LightYellow,
+            highlight: Color::White,
+            high_contrast: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            option: Color::Blue,
+            keyword: Color::Magenta,
+            string: Color::Green,
+            comment: Color::DarkGray,
+            number: Color::Blue,
+            added: Color::Green,
+            removed: Color::Red,
+            changed: Color::Blue,
+            selection: Color::Gray,
+            current_match: Color::Magenta,
+            hint: Color::Magenta,
+            highlight: Color::DarkGray,
+            highlight_text: Color::White,
+            ..Self::default()
+        }
+    }
+
+    pub fn high_contrast_light() -> Self {
+        Self {
+            comment: Color::Black,
+            selection: Color::LightBlue,
+            highlight: Color::Black,
+            high_contrast: true,
+            ..Self::light()
+        }
+    }
+
+    pub fn highlight_style(&self) -> Style {
+        if self.reverse_video {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(self.highlight_text).bg(self.highlight)
+        }
+    }
+
+    pub fn current_match_style(&self) -> Style {
+        let style = if self.reverse_video {
+            Style::default()
+                .fg(self.current_match)
+                .add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+                .fg(self.highlight_text)
+                .bg(self.current_match)
+        };
+        style.add_modifier(Modifier::BOLD)
+    }
+
+    pub fn faint_style(&self) -> Style {
+        if self.high_contrast {
+            Style::default()
+        } else {
+            Style::default().add_modifier(Modifier::DIM)
+        }
+    }
+
+    pub fn border_set(&self) -> border::Set<'static> {
+        if self.ascii {
+            ASCII_BORDER
+        } else {
+            border::PLAIN
+        }
+    }
+
+    pub fn rule(&self, vertical: bool) -> &'static str {
+        match (vertical, self.ascii) {
+            (true, true) => "|",
+            (true, false) => "\u{2502}",
+            (false, true) => "-",
+            (false, false) => "\u{2500}",
+        }
+    }
+
+    pub fn add_highlight(&mut self, value: &str) -> Result<(), String> {
+        let mut style = Style::default();
+        let mut background = false;
+        for word in value.split_whitespace() {
+            let modifier = match word {
+                "bold" => Modifier::BOLD,
+                "dim" => Modifier::DIM,
+                "italic" => Modifier::ITALIC,
+                "underline" => Modifier::UNDERLINED,
+                "reverse" => Modifier::REVERSED,
+                "on" => {
+                    background = true;
+                    continue;
+                }
+                _ => {
+                    let color =
+                        Color::from_str(word).map_err(|_| format!("unknown color {word:?}"))?;
+                    style = if std::mem::take(&mut background) {
+                        style.bg(color)
+                    } else {
+                        style.fg(color)
+                    };
+                    continue;
+                }
+            };
+            style = style.add_modifier(modifier);
+        }
+        if background {
+            return Err(format!("missing background color in {value:?}"));
+        }
+        if style == Style::default() {
+            style = self.highlight_style();
+        }
+        self.highlights.push(style);
+        Ok(())
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let color = Color::from_str(value).map_err(|_| format!("unknown color {value:?}"))?;
+        let slot = match name {
+            "option" => &mut self.option,
+            "keyword" => &mut self.keyword,
+            "string" => &mut self.string,
+            "comment" => &mut self.comment,
+            "number" => &mut self.number,
+            "added" => &mut self.added,
+            "removed" => &mut self.removed,
+            "changed" => &mut self.changed,
+            "selection" => &mut self.selection,
+            "current_match" => &mut self.current_match,
+            "hint" => &mut self.hint,
+            "highlight" => &mut self.highlight,
+            "highlight_text" => &mut self.highlight_text,
+            _ => return Err(format!("unknown theme color {name:?}")),
+        };
+        *slot = color;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_named_and_hex_colors() {
+        let mut theme = Theme::default();
+        theme.set("option", "magenta").expect("named");
+        theme.set("keyword", "#ffaa00").expect("hex");
+        assert_eq!(theme.option, Color::Magenta);
+        assert_eq!(theme.keyword, Color::Rgb(0xff, 0xaa, 0x00));
+        assert_eq!(
+            theme.set("heading", "red"),
+            Err("unknown theme color \"heading\"".to_string())
+        );
+        assert_eq!(
+            theme.set("option", "not-a-color"),
+            Err("unknown color \"not-a-color\"".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_keyword_highlight_styles() {
+        let mut theme = Theme::default();
+        theme.add_highlight("red bold").expect("fg");
+        theme
+            .add_highlight("black on #ffaa00 underline")
+            .expect("bg");
+        theme.add_highlight("").expect("default");
+        assert_eq!(
+            theme.highlights,
+            vec![
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Rgb(0xff, 0xaa, 0x00))
+                    .add_modifier(Modifier::UNDERLINED),
+                theme.highlight_style(),
+            ]
+        );
+        assert_eq!(
+            theme.add_highlight("red on"),
+            Err("missing background color in \"red on\"".to_string())
+        );
+        assert_eq!(
+            theme.add_highlight("blinking"),
+            Err("unknown color \"blinking\"".to_string())
+        );
+    }
+
+    #[test]
+    fn replaces_reverse_video_with_explicit_colors() {
+        let mut theme = Theme::high_contrast();
+        assert_eq!(
+            theme.highlight_style(),
+            Style::default().add_modifier(Modifier::REVERSED)
+        );
+        theme.reverse_video = false;
+        assert_eq!(
+            theme.highlight_style(),
+            Style::default().fg(Color::Black).bg(Color::White)
+        );
+        assert!(
+            !theme
+                .current_match_style()
+                .add_modifier
+                .contains(Modifier::REVERSED)
+        );
+        assert_eq!(theme.faint_style(), Style::default());
+        theme.ascii = true;
+        assert_eq!(theme.rule(true), "|");
+        assert_eq!(theme.border_set().top_left, "+");
+    }
+}